@@ -49,6 +49,11 @@ func main{
 
     verify_difficulty_filter(hash, target);
 
+    // Expose the hash that passed the filter, so the host can check it against its own.
+    assert output_ptr[0] = hash.low;
+    assert output_ptr[1] = hash.high;
+    let output_ptr = output_ptr + 2;
+
     let (indices_ptr, indices_len) = indices_from_minimal(solution_bytes);
 
     let (root) = EquihashTree.tree_validator(
@@ -31,4 +31,6 @@ pub enum Error {
     PublicInput(#[from] PublicInputError),
     #[error(transparent)]
     Program(#[from] ProgramError),
+    #[error("Proving failed: {0}")]
+    Prover(#[from] stwo_prover::Error),
 }
@@ -5,20 +5,23 @@ pub mod hint_processor;
 pub mod hints;
 pub mod types;
 
-use crate::types::InputData;
+use crate::types::{InputData, ResourceEstimate};
 use crate::{error::Error, hint_processor::CustomHintProcessor};
 use cairo_vm_base::stwo_utils::FileWriter;
+pub use cairo_vm_base::vm::cairo_vm::Felt252;
 use cairo_vm_base::vm::cairo_vm::{
     cairo_run::{
         self, cairo_run_program_with_initial_scope, write_encoded_memory, write_encoded_trace,
     },
     types::{exec_scope::ExecutionScopes, layout_name::LayoutName, program::Program},
     vm::{
-        errors::trace_errors::TraceError, runners::cairo_pie::CairoPie,
-        runners::cairo_runner::CairoRunner,
+        errors::trace_errors::TraceError,
+        runners::builtin_runner::BuiltinRunner,
+        runners::cairo_pie::CairoPie,
+        runners::cairo_runner::{CairoRunner, ExecutionResources},
     },
 };
-use std::{io, path::Path};
+use std::{borrow::Cow, io, path::Path};
 use tracing::{debug, info};
 
 fn load_program(path: &str) -> Result<Program, Error> {
@@ -42,6 +45,14 @@ fn load_program(path: &str) -> Result<Program, Error> {
     Ok(program)
 }
 
+/// What a [`run_stwo`] run returns besides the files it writes.
+pub struct StwoRun {
+    /// Values the program wrote to its `output` builtin, in order.
+    pub output: Vec<Felt252>,
+    /// The run's PIE, when requested.
+    pub pie: Option<CairoPie>,
+}
+
 pub fn run_stwo(
     path: &str,
     input: InputData,
@@ -50,7 +61,7 @@ pub fn run_stwo(
     prove: bool,
     pie: bool,
     height: Option<u32>,
-) -> Result<Option<CairoPie>, Error> {
+) -> Result<StwoRun, Error> {
     let program = load_program(path)?;
     let overall_start = std::time::Instant::now();
     let proof_mode = false;
@@ -66,15 +77,7 @@ pub fn run_stwo(
             ..Default::default()
         }
     } else {
-        cairo_run::CairoRunConfig {
-            layout: LayoutName::all_cairo_stwo,
-            trace_enabled: true,
-            relocate_trace: true,
-            relocate_mem: true,
-            proof_mode: true,
-            fill_holes: true,
-            ..Default::default()
-        }
+        stwo_run_config()
     };
 
     let mut hint_processor = CustomHintProcessor::new();
@@ -88,10 +91,12 @@ pub fn run_stwo(
         exec_scopes,
     )?;
 
-    debug!(
-        "Execution resources: {:?}",
-        cairo_runner.get_execution_resources()
-    );
+    let estimate = resource_estimate(&cairo_runner.get_execution_resources()?);
+    if prove {
+        debug!("Execution resources: {estimate}");
+    } else {
+        info!("Execution resources: {estimate}");
+    }
     let trace_start = std::time::Instant::now();
     generate_stwo_files(&cairo_runner, output_dir)?;
     let trace_duration = trace_start.elapsed();
@@ -103,14 +108,13 @@ pub fn run_stwo(
             None => "proof.json".to_string(),
         };
         let proof_path = Path::new(output_dir).join(&proof_filename);
-        let _res = stwo_prover::generate_proof(
+        stwo_prover::generate_proof(
             &Path::new(output_dir).join("pub.json"),
             &Path::new(output_dir).join("priv.json"),
             Some(true),
             Some(stwo_prover::ProofFormat::CairoSerde),
-            Some(proof_path.clone()),
-        )
-        .unwrap();
+            Some(proof_path),
+        )?;
         let prove_duration = prove_start.elapsed();
         info!(
             "Trace generation: {:.1?}, Proof generation: {:.1?}",
@@ -125,21 +129,65 @@ pub fn run_stwo(
         overall_start.elapsed()
     );
 
-    if pie {
-        let pie = cairo_runner.get_cairo_pie()?;
-        Ok(Some(pie))
+    let pie = if pie {
+        Some(cairo_runner.get_cairo_pie()?)
     } else {
-        Ok(None)
-    }
+        None
+    };
+    Ok(StwoRun {
+        output: program_output(&cairo_runner)?,
+        pie,
+    })
+}
+
+/// Runs the program at `path` on `input` as [`run_stwo`] does for proving, and reports the
+/// resources the run used without writing the trace files or invoking the prover.
+pub fn estimate_resources(path: &str, input: InputData) -> Result<ResourceEstimate, Error> {
+    let program = load_program(path)?;
+
+    let mut hint_processor = CustomHintProcessor::new();
+    let mut exec_scopes = ExecutionScopes::new();
+    exec_scopes.insert_value("input", input);
+
+    let cairo_runner = cairo_run_program_with_initial_scope(
+        &program,
+        &stwo_run_config(),
+        &mut hint_processor,
+        exec_scopes,
+    )?;
+
+    Ok(resource_estimate(&cairo_runner.get_execution_resources()?))
+}
+
+/// Runs the program at `path` on `input` to completion and nothing else: no trace is recorded
+/// and no file is written, for callers that only need to know the Cairo program accepts the
+/// input. Hints that reject the input, or a failed run, surface as `Err`.
+///
+/// Returns the values the program wrote to its `output` builtin.
+pub fn execute(path: &str, input: InputData) -> Result<Vec<Felt252>, Error> {
+    let program = load_program(path)?;
+
+    let mut hint_processor = CustomHintProcessor::new();
+    let mut exec_scopes = ExecutionScopes::new();
+    exec_scopes.insert_value("input", input);
+
+    let cairo_runner = cairo_run_program_with_initial_scope(
+        &program,
+        &execution_run_config(),
+        &mut hint_processor,
+        exec_scopes,
+    )?;
+
+    debug!(
+        "Execution resources: {:?}",
+        cairo_runner.get_execution_resources()
+    );
+    program_output(&cairo_runner)
 }
 
 pub fn run(path: &str, input: InputData, _log_level: &'static str) -> Result<CairoPie, Error> {
     let program = load_program(path)?;
-    let cairo_run_config = cairo_run::CairoRunConfig {
-        allow_missing_builtins: Some(true),
-        layout: LayoutName::all_cairo,
-        ..Default::default()
-    };
+    let cairo_run_config = execution_run_config();
     // let beacon_mmr_update = input.input.beacon_mmr_update.clone();
     let mut hint_processor = CustomHintProcessor::new();
     let mut exec_scopes = ExecutionScopes::new();
@@ -161,6 +209,82 @@ pub fn run(path: &str, input: InputData, _log_level: &'static str) -> Result<Cai
     Ok(pie)
 }
 
+/// The `output` builtin segment of a finished run, or nothing if the program declares no
+/// `output` builtin.
+fn program_output(cairo_runner: &CairoRunner) -> Result<Vec<Felt252>, Error> {
+    let base = cairo_runner
+        .vm
+        .get_builtin_runners()
+        .iter()
+        .find_map(|builtin| match builtin {
+            BuiltinRunner::Output(output) => Some(output.base()),
+            _ => None,
+        });
+    let Some(base) = base else {
+        return Ok(Vec::new());
+    };
+    let size = cairo_runner.vm.get_segment_used_size(base).unwrap_or(0);
+    let values = cairo_runner
+        .vm
+        .get_integer_range((base as isize, 0).into(), size)?;
+    Ok(values.into_iter().map(Cow::into_owned).collect())
+}
+
+/// Plain execution from `main`, without a trace.
+fn execution_run_config() -> cairo_run::CairoRunConfig<'static> {
+    cairo_run::CairoRunConfig {
+        allow_missing_builtins: Some(true),
+        layout: LayoutName::all_cairo,
+        ..Default::default()
+    }
+}
+
+/// Proof-mode run producing the relocated trace and memory STWO proves.
+fn stwo_run_config() -> cairo_run::CairoRunConfig<'static> {
+    cairo_run::CairoRunConfig {
+        layout: LayoutName::all_cairo_stwo,
+        trace_enabled: true,
+        relocate_trace: true,
+        relocate_mem: true,
+        proof_mode: true,
+        fill_holes: true,
+        ..Default::default()
+    }
+}
+
+fn resource_estimate(resources: &ExecutionResources) -> ResourceEstimate {
+    ResourceEstimate {
+        steps: resources.n_steps,
+        memory_holes: resources.n_memory_holes,
+        builtins: resources
+            .builtin_instance_counter
+            .iter()
+            .map(|(name, &count)| (name.to_str().to_string(), count))
+            .collect(),
+        trace_rows: resources.n_steps.next_power_of_two(),
+    }
+}
+
+/// Files [`run_stwo`] writes into its `output_dir` for the prover; a proof, if any, sits next
+/// to them under its own name.
+pub const STWO_TRACE_FILES: [&str; 4] = ["memory.bin", "trace.bin", "pub.json", "priv.json"];
+
+/// Deletes the [`STWO_TRACE_FILES`] a run left in `output_dir`, and the directory itself if
+/// nothing else (such as a proof) remains in it.
+pub fn remove_stwo_files(output_dir: &str) -> Result<(), Error> {
+    let dir = Path::new(output_dir);
+    for name in STWO_TRACE_FILES {
+        match std::fs::remove_file(dir.join(name)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    if std::fs::read_dir(dir)?.next().is_none() {
+        std::fs::remove_dir(dir)?;
+    }
+    Ok(())
+}
+
 fn generate_stwo_files(cairo_runner: &CairoRunner, output_dir: &str) -> Result<(), Error> {
     std::fs::create_dir_all(output_dir)?;
 
@@ -182,16 +306,49 @@ fn generate_stwo_files(cairo_runner: &CairoRunner, output_dir: &str) -> Result<(
     write_encoded_trace(relocated_trace, &mut trace_writer)?;
     trace_writer.flush()?;
 
-    let public_input = cairo_runner.get_air_public_input();
-    let public_input_json = serde_json::to_string_pretty(&public_input.unwrap()).unwrap();
+    let public_input = cairo_runner.get_air_public_input()?;
+    let public_input_json = serde_json::to_string_pretty(&public_input)?;
     std::fs::write(Path::new(output_dir).join("pub.json"), public_input_json)?;
 
     let private_input = cairo_runner.get_air_private_input();
     let private_input_serializable =
         private_input.to_serializable("trace.bin".to_string(), "memory.bin".to_string());
-    let private_input_json = serde_json::to_string_pretty(&private_input_serializable).unwrap();
+    let private_input_json = serde_json::to_string_pretty(&private_input_serializable)?;
     std::fs::write(Path::new(output_dir).join("priv.json"), private_input_json)?;
     info!("Trace and memory files generated successfully");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_public_input_is_an_error() {
+        let program = load_program(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/programs/proof_mode_return.json"
+        ))
+        .unwrap();
+        // Never run, so there is no execution to build a public input from.
+        let mut cairo_runner = CairoRunner::new(
+            &program,
+            LayoutName::all_cairo_stwo,
+            None,
+            true,
+            true,
+            false,
+        )
+        .unwrap();
+        cairo_runner.relocated_trace = Some(Vec::new());
+
+        let output_dir = std::env::temp_dir().join("zoro_no_public_input");
+        let result = generate_stwo_files(&cairo_runner, &output_dir.to_string_lossy());
+        assert!(
+            matches!(result, Err(Error::PublicInput(_))),
+            "expected a public input error, got {result:?}"
+        );
+        std::fs::remove_dir_all(output_dir).unwrap();
+    }
+}
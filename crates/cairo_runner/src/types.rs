@@ -1,5 +1,142 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::constants::{K, N};
+
+/// `u32` words of the 140-byte powheader.
+pub const HEADER_WORDS: usize = 35;
+
+/// `u32` words of a minimally encoded Equihash `(N, K)` solution: `2^K` indices of
+/// `N / (K + 1) + 1` bits each.
+pub const SOLUTION_WORDS: usize = ((1 << K) * (N / (K + 1) + 1) / 32) as usize;
+
+/// Why an [`InputData`] cannot be handed to the Cairo program.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InputError {
+    #[error("header has {0} words, expected {HEADER_WORDS}")]
+    HeaderLength(usize),
+    #[error("solution has {0} words, expected {SOLUTION_WORDS} (2^{K} indices)")]
+    SolutionLength(usize),
+}
+
 #[derive(Debug, Clone)]
 pub struct InputData {
     pub header_bytes: Vec<u32>,
     pub solution_bytes: Vec<u32>,
 }
+
+impl InputData {
+    /// Packs a 140-byte powheader and its Equihash solution into the big-endian `u32` words
+    /// the Cairo program and its hints read back (see `hints::hashing`).
+    ///
+    /// Both inputs are expected to be a multiple of 4 bytes long, as the Zcash powheader
+    /// (140 bytes) and `(200, 9)` solution (1344 bytes) are.
+    pub fn from_powheader(powheader: &[u8], solution: &[u8]) -> InputData {
+        debug_assert!(powheader.len().is_multiple_of(4) && solution.len().is_multiple_of(4));
+        InputData {
+            header_bytes: pack_be_words(powheader),
+            solution_bytes: pack_be_words(solution),
+        }
+    }
+
+    /// Checks the lengths the Cairo program and its hints assume, which they would otherwise
+    /// only enforce mid-run by panicking the VM.
+    pub fn validate(&self) -> Result<(), InputError> {
+        if self.header_bytes.len() != HEADER_WORDS {
+            return Err(InputError::HeaderLength(self.header_bytes.len()));
+        }
+        if self.solution_bytes.len() != SOLUTION_WORDS {
+            return Err(InputError::SolutionLength(self.solution_bytes.len()));
+        }
+        Ok(())
+    }
+
+    /// The powheader bytes `header_bytes` packs, i.e. the inverse of the packing in
+    /// [`InputData::from_powheader`].
+    pub fn powheader(&self) -> Vec<u8> {
+        self.header_bytes
+            .iter()
+            .flat_map(|w| w.to_be_bytes())
+            .collect()
+    }
+}
+
+/// What a Cairo run costs the STWO prover, measured from trace generation alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceEstimate {
+    /// VM steps executed, including the proof-mode padding steps.
+    pub steps: usize,
+    pub memory_holes: usize,
+    /// Instances used per builtin, keyed by builtin name.
+    pub builtins: BTreeMap<String, usize>,
+    /// Rows of the execution trace the prover commits to: `steps` padded to a power of two.
+    /// Builtin components add their own rows on top.
+    pub trace_rows: usize,
+}
+
+impl fmt::Display for ResourceEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} steps, {} trace rows, {} memory holes",
+            self.steps, self.trace_rows, self.memory_holes
+        )?;
+        for (name, count) in self.builtins.iter().filter(|&(_, &count)| count > 0) {
+            write!(f, ", {name}={count}")?;
+        }
+        Ok(())
+    }
+}
+
+fn pack_be_words(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADERS_JSONL: &str = include_str!("../../../data/headers.jsonl");
+
+    #[test]
+    fn packs_mainnet_header_into_be_words() {
+        let line = HEADERS_JSONL.lines().next().unwrap();
+        let hex = line.split("\"header_hex\":\"").nth(1).unwrap();
+        let raw = (0..hex.len() - 2)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect::<Vec<u8>>();
+        // 140-byte powheader, 3-byte compactSize, 1344-byte solution.
+        let (powheader, solution) = (&raw[..140], &raw[143..]);
+
+        let input = InputData::from_powheader(powheader, solution);
+        assert_eq!(input.header_bytes.len(), 35);
+        assert_eq!(input.solution_bytes.len(), 336);
+        // Version 4, serialized little-endian, reads back as the word 0x04000000.
+        assert_eq!(input.header_bytes[0], 0x0400_0000);
+
+        let unpacked: Vec<u8> = input
+            .header_bytes
+            .iter()
+            .chain(&input.solution_bytes)
+            .flat_map(|w| w.to_be_bytes())
+            .collect();
+        assert_eq!(unpacked, [powheader, solution].concat());
+        assert_eq!(input.powheader(), powheader);
+        input.validate().unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_truncated_input() {
+        let input = InputData::from_powheader(&[0; 136], &[0; 1344]);
+        assert_eq!(input.validate(), Err(InputError::HeaderLength(34)));
+
+        let input = InputData::from_powheader(&[0; 140], &[0; 1340]);
+        assert_eq!(input.validate(), Err(InputError::SolutionLength(335)));
+    }
+}
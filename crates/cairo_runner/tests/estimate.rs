@@ -0,0 +1,28 @@
+use cairo_runner::estimate_resources;
+use cairo_runner::types::InputData;
+
+/// `func main() { ret; }` compiled in proof mode: `__start__` calls `main`, and `__end__`
+/// loops on `jmp rel 0`.
+const RETURN_PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/programs/proof_mode_return.json"
+);
+
+#[test]
+fn estimate_is_populated_for_a_small_program() -> Result<(), Box<dyn std::error::Error>> {
+    let input = InputData {
+        header_bytes: Vec::new(),
+        solution_bytes: Vec::new(),
+    };
+
+    let estimate = estimate_resources(RETURN_PROGRAM, input)?;
+
+    // `call main`, `ret`, then padding on `jmp rel 0` up to a power of two.
+    assert!(estimate.steps >= 2, "{estimate:?}");
+    assert!(estimate.trace_rows.is_power_of_two());
+    assert!(estimate.trace_rows >= estimate.steps);
+    assert!(estimate
+        .to_string()
+        .starts_with(&format!("{} steps", estimate.steps)));
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use std::thread;
+
+use cairo_runner::types::InputData;
+use cairo_runner::{execute, remove_stwo_files, run_stwo, STWO_TRACE_FILES};
+
+const RETURN_PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/programs/proof_mode_return.json"
+);
+
+fn empty_input() -> InputData {
+    InputData {
+        header_bytes: Vec::new(),
+        solution_bytes: Vec::new(),
+    }
+}
+
+#[test]
+fn parallel_runs_keep_their_own_trace_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dirs: Vec<PathBuf> = (0..2)
+        .map(|i| std::env::temp_dir().join(format!("zoro_stwo_files_{}_{i}", std::process::id())))
+        .collect();
+
+    let runs: Vec<_> = dirs
+        .iter()
+        .map(|dir| {
+            let dir = dir.to_string_lossy().into_owned();
+            thread::spawn(move || {
+                run_stwo(
+                    RETURN_PROGRAM,
+                    empty_input(),
+                    "info",
+                    &dir,
+                    false,
+                    false,
+                    None,
+                )
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+            })
+        })
+        .collect();
+    for run in runs {
+        run.join().expect("run panicked")?;
+    }
+
+    for dir in &dirs {
+        for name in STWO_TRACE_FILES {
+            let len = std::fs::metadata(dir.join(name))?.len();
+            assert!(len > 0, "{} is empty", dir.join(name).display());
+        }
+    }
+
+    for dir in &dirs {
+        remove_stwo_files(&dir.to_string_lossy())?;
+        assert!(!dir.exists());
+    }
+    Ok(())
+}
+
+#[test]
+fn execution_only_writes_no_files() -> Result<(), Box<dyn std::error::Error>> {
+    // Relative output paths land in the working directory; the light client's proving path
+    // writes to `output/block_<height>` below it.
+    let listing = || -> std::io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for dir in [".", "output"] {
+            let entries = match std::fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries {
+                paths.push(entry?.path());
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    };
+    let before = listing()?;
+
+    // The program declares no `output` builtin, so it outputs nothing.
+    assert!(execute(RETURN_PROGRAM, empty_input())?.is_empty());
+
+    assert_eq!(listing()?, before);
+    Ok(())
+}
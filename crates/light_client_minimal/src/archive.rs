@@ -0,0 +1,95 @@
+//! Archive linking each proven header to its STWO proof.
+//!
+//! Records are appended to a JSONL file, one per proof, in the same style as
+//! [`store::file`](crate::store::file):
+//! `{ "height": u32, "header_hex": String, "proof_path": String, "verified_at": u64 }`,
+//! where `verified_at` is seconds since the Unix epoch. A height proven again (after a reorg or
+//! a rerun) gets a new record, and the latest one wins on read.
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions, create_dir_all};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// A proven header and where its proof lives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofRecord {
+    pub height: u32,
+    pub header_hex: String,
+    pub proof_path: PathBuf,
+    /// When the record was written, in seconds since the Unix epoch.
+    pub verified_at: u64,
+}
+
+pub struct ArchiveStore {
+    path: PathBuf,
+}
+
+impl ArchiveStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let p = path.as_ref().to_path_buf();
+        if let Some(dir) = p.parent()
+            && !dir.exists()
+        {
+            create_dir_all(dir)?;
+        }
+        if !p.exists() {
+            File::create(&p)?;
+        }
+        Ok(ArchiveStore { path: p })
+    }
+
+    /// Records that the header at `height` was verified and proven, with its proof at
+    /// `proof_path`.
+    pub fn put_proof(&self, height: u32, header_hex: &str, proof_path: &Path) -> io::Result<()> {
+        let verified_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(io::Error::other)?
+            .as_secs();
+        let rec = ProofRecord {
+            height,
+            header_hex: header_hex.to_string(),
+            proof_path: proof_path.to_path_buf(),
+            verified_at,
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let line = serde_json::to_string(&rec).map_err(|e| io::Error::other(e.to_string()))?;
+        file.write_all(line.as_bytes())?;
+        file.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Latest record for `height`, if that height was ever proven.
+    pub fn get(&self, height: u32) -> io::Result<Option<ProofRecord>> {
+        Ok(self.latest_records()?.remove(&height))
+    }
+
+    /// Highest proven height.
+    pub fn tip(&self) -> io::Result<Option<u32>> {
+        Ok(self.latest_records()?.keys().next_back().copied())
+    }
+
+    /// The latest record for every proven height, in ascending height order.
+    pub fn records(&self) -> io::Result<Vec<ProofRecord>> {
+        Ok(self.latest_records()?.into_values().collect())
+    }
+
+    fn latest_records(&self) -> io::Result<BTreeMap<u32, ProofRecord>> {
+        let mut recs = BTreeMap::new();
+        for line in BufReader::new(File::open(&self.path)?).lines() {
+            let l = line?;
+            if l.trim().is_empty() {
+                continue;
+            }
+            if let Ok(rec) = serde_json::from_str::<ProofRecord>(&l) {
+                recs.insert(rec.height, rec);
+            }
+        }
+        Ok(recs)
+    }
+}
@@ -0,0 +1,42 @@
+//! Hex encoding of serialized headers, the form kept by [`crate::store`] and returned by
+//! `getblockheader <hash> false`.
+use core::fmt;
+use std::io;
+
+use zcash_primitives::block::BlockHeader;
+
+/// Errors converting between a [`BlockHeader`] and its hex encoding.
+#[derive(Debug)]
+pub enum CodecError {
+    /// The input is not valid hex.
+    Hex(hex::FromHexError),
+    /// The bytes do not decode as a header.
+    Decode(io::Error),
+    /// The header could not be serialized.
+    Encode(io::Error),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Hex(e) => write!(f, "hex decode: {e}"),
+            CodecError::Decode(e) => write!(f, "decode header: {e}"),
+            CodecError::Encode(e) => write!(f, "serialize header: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// Serializes `header` (powheader, then the compactsize-prefixed solution) as lowercase hex.
+pub fn encode_header(header: &BlockHeader) -> Result<String, CodecError> {
+    let mut buf = Vec::new();
+    header.write(&mut buf).map_err(CodecError::Encode)?;
+    Ok(hex::encode(buf))
+}
+
+/// Decodes a header from the hex of its serialization, as written by [`encode_header`].
+pub fn decode_header(header_hex: &str) -> Result<BlockHeader, CodecError> {
+    let bytes = hex::decode(header_hex).map_err(CodecError::Hex)?;
+    BlockHeader::read(&bytes[..]).map_err(CodecError::Decode)
+}
@@ -1,3 +1,6 @@
+pub mod archive;
+pub mod codec;
 pub mod net;
+pub mod state;
 pub mod store;
 pub mod sync;
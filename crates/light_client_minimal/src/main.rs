@@ -1,10 +1,28 @@
 use std::env;
+use std::io::IsTerminal;
 
-use light_client_minimal::{net::rpc::RpcClient, store::file::FileStore, sync::sync_chain};
+use light_client_minimal::{
+    net::rpc::RpcClient,
+    store::file::FileStore,
+    sync::{
+        SyncOptions, report_header_at, report_header_bytes, report_header_bytes_at, reverify_store,
+        sync_chain_until,
+    },
+};
+#[cfg(feature = "zmq")]
+use light_client_minimal::net::notify::{TipNotifier, ZmqNotifier};
+#[cfg(feature = "zmq")]
+use std::sync::Arc;
 use tracing_subscriber::EnvFilter;
+use zcash_crypto::{Kind, verify_equihash_solution_with_params};
+use tracing_subscriber::fmt::format::FmtSpan;
 use figlet_rs::FIGfont;
 use colored::*;
-use clap::Parser;
+use clap::{Args as ClapArgs, Parser, Subcommand};
+
+const STORE_PATH: &str = "./data/headers.jsonl";
+const ARCHIVE_PATH: &str = "./data/proofs.jsonl";
+const STATE_PATH: &str = "./data/sync_state.bin";
 
 fn print_banner() {
     // Load a custom font from file, or fall back to standard font
@@ -31,16 +49,164 @@ fn print_banner() {
 #[command(name = "zoro-zero")]
 #[command(about = "ZK Client for Zcash • Written in Cairo Zero", long_about = None)]
 struct Args {
-    /// Generate STWO proofs for each verified block
-    #[arg(short, long)]
+    /// Generate STWO proofs for each verified block, indexed in ./data/proofs.jsonl
+    #[arg(short, long, global = true)]
     prove: bool,
+
+    /// Skip the startup banner (also skipped when NO_COLOR is set or stdout is not a terminal)
+    #[arg(long, global = true)]
+    no_banner: bool,
+
+    /// Stop once the node's tip is reached instead of waiting for new blocks
+    #[arg(long, global = true)]
+    exit_at_tip: bool,
+
+    /// Start at --start-height even if the store is further ahead, passing over blocks that are
+    /// already stored (and, with --prove, proven) instead of verifying them again
+    #[arg(long, global = true)]
+    skip_existing: bool,
+
+    /// zcashd `-zmqpubhashblock` endpoint (e.g. tcp://127.0.0.1:28332): at the tip, wait for its
+    /// new-block notifications instead of polling
+    #[cfg(feature = "zmq")]
+    #[arg(long, global = true, env = "ZCASH_ZMQ_URL")]
+    zmq_url: Option<String>,
+
+    /// Zcash node JSON-RPC URL (may embed credentials, so its env value is not echoed in --help)
+    #[arg(long, global = true, env = "ZCASH_RPC_URL", hide_env_values = true)]
+    rpc_url: Option<String>,
+
+    /// Height to start syncing from when the store is empty
+    #[arg(long, global = true, env = "START_HEIGHT", default_value_t = 3_000_000)]
+    start_height: u32,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Continuously sync and verify headers (default)
+    Sync,
+    /// Verify a single header and print the result
+    Verify(VerifyArgs),
+    /// Re-verify every header in the local store without contacting a node
+    Reverify,
+    /// Check an Equihash solution against a powheader, without header parsing or difficulty
+    Equihash(EquihashArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = true, multiple = true)]
+struct VerifyArgs {
+    /// Verify the header at this height in context; fetched from the node unless --hex is given
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Verify a raw hex-encoded serialized header (context-free checks only without --height)
+    #[arg(long)]
+    hex: Option<String>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct EquihashArgs {
+    /// Hex-encoded powheader: the header fields the solution commits to, 140 bytes for Zcash
+    #[arg(long)]
+    powheader: String,
+
+    /// Hex-encoded solution, without its compactSize length prefix
+    #[arg(long)]
+    solution: String,
+
+    /// Equihash n parameter
+    #[arg(long, default_value_t = 200)]
+    n: u32,
+
+    /// Equihash k parameter
+    #[arg(long, default_value_t = 9)]
+    k: u32,
+}
+
+/// The banner is decoration for interactive use; keep it out of service logs and CI output.
+fn show_banner(args: &Args) -> bool {
+    !args.no_banner && env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+}
+
+fn rpc_client(rpc_url: Option<&str>) -> Result<RpcClient, Box<dyn std::error::Error>> {
+    let url = rpc_url.ok_or("no RPC URL given: pass --rpc-url or set ZCASH_RPC_URL")?;
+    Ok(RpcClient::new(url)?)
+}
+
+#[cfg(feature = "zmq")]
+async fn tip_notifier(
+    zmq_url: Option<&str>,
+) -> Result<Option<Arc<dyn TipNotifier>>, Box<dyn std::error::Error>> {
+    let Some(url) = zmq_url else {
+        return Ok(None);
+    };
+    tracing::info!("Following the tip via ZMQ notifications from {url}");
+    Ok(Some(Arc::new(ZmqNotifier::connect(url).await?)))
+}
+
+async fn run_verify(
+    args: VerifyArgs,
+    rpc_url: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let report = match (args.height, args.hex) {
+        (Some(height), Some(hex)) => {
+            report_header_bytes_at(&rpc_client(rpc_url)?, &hex::decode(hex.trim())?, height).await?
+        }
+        (Some(height), None) => report_header_at(&rpc_client(rpc_url)?, height).await?,
+        (None, Some(hex)) => report_header_bytes(&hex::decode(hex.trim())?)?,
+        (None, None) => unreachable!("clap requires --height or --hex"),
+    };
+
+    println!("{report}");
+    if !report.passed() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Decodes the `equihash` arguments and verifies the solution; `Err` only for malformed hex.
+fn check_equihash(args: &EquihashArgs) -> Result<Result<(), Kind>, hex::FromHexError> {
+    let powheader = hex::decode(args.powheader.trim())?;
+    let solution = hex::decode(args.solution.trim())?;
+    Ok(
+        verify_equihash_solution_with_params(args.n, args.k, &powheader, &solution)
+            .map_err(|e| e.0),
+    )
+}
+
+fn run_equihash(args: &EquihashArgs) -> Result<(), Box<dyn std::error::Error>> {
+    match check_equihash(args)? {
+        Ok(()) => println!("Equihash ({}, {}) solution valid: PASS", args.n, args.k),
+        Err(kind) => {
+            println!("FAIL: {kind:?} ({kind})");
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+fn run_reverify() -> Result<(), Box<dyn std::error::Error>> {
+    let store = FileStore::new(STORE_PATH)?;
+    match reverify_store(&store) {
+        Ok(checked) => println!("{checked} stored headers verified: PASS"),
+        Err(e) => {
+            println!("FAIL: {e}");
+            std::process::exit(1);
+        }
+    }
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    print_banner();
-    
     let args = Args::parse();
+    if show_banner(&args) {
+        print_banner();
+    }
     
     let filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info"))
@@ -56,18 +222,222 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt()
         .with_env_filter(filter)
         .with_target(false)
+        .with_span_events(FmtSpan::CLOSE)
         .init();
 
-    let url = env::var("ZCASH_RPC_URL").expect("ZCASH_RPC_URL must be set");
-    let client = RpcClient::new(&url)?;
+    match args.command {
+        Some(Command::Verify(verify_args)) => {
+            return run_verify(verify_args, args.rpc_url.as_deref()).await;
+        }
+        Some(Command::Reverify) => return run_reverify(),
+        Some(Command::Equihash(equihash_args)) => return run_equihash(&equihash_args),
+        Some(Command::Sync) | None => {}
+    }
 
-    let start_height: u32 = match env::var("START_HEIGHT") {
-        Ok(s) => s.parse().expect("START_HEIGHT must be a valid u32"),
-        Err(_) => 3_000_000,
-    };
+    let client = rpc_client(args.rpc_url.as_deref())?;
+
+    #[cfg(feature = "zmq")]
+    let tip_notifier = tip_notifier(args.zmq_url.as_deref()).await?;
+    #[cfg(not(feature = "zmq"))]
+    let tip_notifier = None;
 
-    let store = FileStore::new("./data/headers.jsonl")?;
-    sync_chain(&client, &store, start_height, args.prove).await?;
+    let store = FileStore::new(STORE_PATH)?;
+    let opts = SyncOptions {
+        prove: args.prove,
+        exit_at_tip: args.exit_at_tip,
+        skip_existing: args.skip_existing,
+        tip_notifier,
+        archive: Some(ARCHIVE_PATH.into()),
+        state: Some(STATE_PATH.into()),
+        ..SyncOptions::default()
+    };
+    // Ctrl-C finishes the block in progress, so the store never ends part-way through one.
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::warn!("Cannot listen for Ctrl-C: {e}");
+            std::future::pending::<()>().await;
+        }
+    };
+    match sync_chain_until(&client, &store, args.start_height, &opts, ctrl_c).await? {
+        Some(last) => tracing::info!("Headers stored up to block {last}"),
+        None => tracing::info!("No headers stored"),
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// Held by every test that parses `Args`: clap reads `ZCASH_RPC_URL`, `START_HEIGHT` and
+    /// `ZCASH_ZMQ_URL` through the `env` attributes, and `cli_flags_override_env_vars` sets them.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn env_lock() -> MutexGuard<'static, ()> {
+        // A failed test poisons the lock; the variables are restored on unwind regardless.
+        ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn try_parse<'a>(argv: impl IntoIterator<Item = &'a str>) -> Result<Args, clap::Error> {
+        let _env = env_lock();
+        Args::try_parse_from(argv)
+    }
+
+    /// Sets environment variables for the life of the guard, restoring the previous values on
+    /// drop. Only create one while holding [`ENV_LOCK`].
+    struct EnvVars(Vec<(&'static str, Option<std::ffi::OsString>)>);
+
+    impl EnvVars {
+        fn set(vars: &[(&'static str, Option<&str>)]) -> Self {
+            let saved = vars
+                .iter()
+                .map(|&(name, _)| (name, env::var_os(name)))
+                .collect();
+            for &(name, value) in vars {
+                // SAFETY: the caller holds ENV_LOCK, and no test reads these variables without
+                // it.
+                unsafe {
+                    match value {
+                        Some(value) => env::set_var(name, value),
+                        None => env::remove_var(name),
+                    }
+                }
+            }
+            EnvVars(saved)
+        }
+    }
+
+    impl Drop for EnvVars {
+        fn drop(&mut self) {
+            for (name, value) in self.0.drain(..) {
+                // SAFETY: as in `EnvVars::set`; the guard is dropped before ENV_LOCK.
+                unsafe {
+                    match value {
+                        Some(value) => env::set_var(name, value),
+                        None => env::remove_var(name),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn no_banner_flag_is_parsed() {
+        let args = try_parse(["zoro-zero", "--no-banner"]).unwrap();
+        assert!(args.no_banner);
+        assert!(!show_banner(&args));
+
+        let args = try_parse(["zoro-zero", "verify", "--hex", "00", "--no-banner"]).unwrap();
+        assert!(args.no_banner);
+
+        let args = try_parse(["zoro-zero"]).unwrap();
+        assert!(!args.no_banner);
+    }
+
+    #[test]
+    fn cli_flags_override_env_vars() {
+        let _lock = env_lock();
+        let env = EnvVars::set(&[
+            ("ZCASH_RPC_URL", Some("http://env-node:8232")),
+            ("START_HEIGHT", Some("1000")),
+        ]);
+
+        let args = Args::try_parse_from(["zoro-zero"]).unwrap();
+        assert_eq!(args.rpc_url.as_deref(), Some("http://env-node:8232"));
+        assert_eq!(args.start_height, 1000);
+
+        let args = Args::try_parse_from([
+            "zoro-zero",
+            "--rpc-url",
+            "http://cli-node:8232",
+            "--start-height",
+            "2000",
+        ])
+        .unwrap();
+        assert_eq!(args.rpc_url.as_deref(), Some("http://cli-node:8232"));
+        assert_eq!(args.start_height, 2000);
+
+        drop(env);
+        let _env = EnvVars::set(&[("ZCASH_RPC_URL", None), ("START_HEIGHT", None)]);
+        let args = Args::try_parse_from(["zoro-zero"]).unwrap();
+        assert_eq!(args.start_height, 3_000_000);
+        assert!(rpc_client(args.rpc_url.as_deref()).is_err());
+    }
+
+    #[test]
+    fn verify_accepts_hex_height_or_both() {
+        let parse = |extra: &[&str]| {
+            let argv = ["zoro-zero", "verify"].iter().chain(extra).copied();
+            match try_parse(argv).map(|a| a.command) {
+                Ok(Some(Command::Verify(v))) => Ok((v.height, v.hex)),
+                Ok(other) => panic!("expected verify, got {other:?}"),
+                Err(e) => Err(e),
+            }
+        };
+
+        assert_eq!(parse(&["--hex", "ab"]).unwrap(), (None, Some("ab".into())));
+        assert_eq!(parse(&["--height", "7"]).unwrap(), (Some(7), None));
+        assert_eq!(
+            parse(&["--hex", "ab", "--height", "7"]).unwrap(),
+            (Some(7), Some("ab".into()))
+        );
+        assert!(parse(&[]).is_err());
+    }
+
+    /// Mainnet block 3000000 from the fixtures, as `(powheader, solution)` hex.
+    fn fixture_equihash_vector() -> (String, String) {
+        let line = include_str!("../../../data/headers.jsonl")
+            .lines()
+            .next()
+            .unwrap();
+        let rec: serde_json::Value = serde_json::from_str(line).unwrap();
+        let raw = hex::decode(rec["header_hex"].as_str().unwrap()).unwrap();
+        // 140-byte powheader, 3-byte compactSize, 1344-byte solution.
+        (hex::encode(&raw[..140]), hex::encode(&raw[143..]))
+    }
+
+    fn parse_equihash(extra: &[&str]) -> EquihashArgs {
+        let argv = ["zoro-zero", "equihash"].iter().chain(extra).copied();
+        match try_parse(argv).unwrap().command {
+            Some(Command::Equihash(args)) => args,
+            other => panic!("expected equihash, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn equihash_subcommand_checks_the_solution() {
+        let (powheader, solution) = fixture_equihash_vector();
+
+        let args = parse_equihash(&["--powheader", &powheader, "--solution", &solution]);
+        assert_eq!((args.n, args.k), (200, 9));
+        assert_eq!(check_equihash(&args), Ok(Ok(())));
+
+        let mut tampered = solution.clone();
+        tampered.replace_range(..2, if &solution[..2] == "00" { "01" } else { "00" });
+        let args = parse_equihash(&["--powheader", &powheader, "--solution", &tampered]);
+        assert!(matches!(check_equihash(&args), Ok(Err(_))));
+
+        let args = parse_equihash(&[
+            "--powheader",
+            &powheader,
+            "--solution",
+            &solution,
+            "--n",
+            "96",
+            "--k",
+            "5",
+        ]);
+        assert_eq!(
+            check_equihash(&args),
+            Ok(Err(Kind::WrongSolutionLength {
+                expected: 68,
+                found: 1344
+            }))
+        );
+
+        let args = parse_equihash(&["--powheader", "zz", "--solution", &solution]);
+        assert!(check_equihash(&args).is_err());
+    }
+}
@@ -1 +1,42 @@
+//! Where headers come from. [`rpc::RpcClient`] talks to a `zcashd`-compatible node; anything
+//! else implementing [`HeaderSource`] (an in-memory chain in tests, another backend) can drive
+//! [`crate::sync`] instead.
+use std::future::Future;
+
+use zcash_crypto::Network;
+use zcash_primitives::block::{BlockHash, BlockHeader};
+
+use rpc::RpcError;
+
+pub mod notify;
 pub mod rpc;
+
+/// A read-only view of a chain, addressed by height.
+pub trait HeaderSource {
+    /// Header at height `h`. A height past the tip is [`RpcError::HeightOutOfRange`], which
+    /// the sync loop treats as "not mined yet".
+    fn header_by_height(
+        &self,
+        h: u32,
+    ) -> impl Future<Output = Result<BlockHeader, RpcError>> + Send;
+
+    /// Hash of the header at height `h`.
+    fn block_hash(&self, h: u32) -> impl Future<Output = Result<BlockHash, RpcError>> + Send;
+
+    /// Height of the current tip.
+    fn block_count(&self) -> impl Future<Output = Result<u64, RpcError>> + Send;
+
+    /// Confirmations of the block `hash`: `-1` once it has been disconnected from the best
+    /// chain. `None` if the source cannot tell, which is the default.
+    fn confirmations(
+        &self,
+        _hash: &BlockHash,
+    ) -> impl Future<Output = Result<Option<i64>, RpcError>> + Send {
+        async { Ok(None) }
+    }
+
+    /// Network whose difficulty rules the chain follows; mainnet unless overridden.
+    fn network(&self) -> impl Future<Output = Result<Network, RpcError>> + Send {
+        async { Ok(Network::Mainnet) }
+    }
+}
@@ -0,0 +1,95 @@
+//! Push notifications of new blocks, so a sync loop following the tip can wait for one instead
+//! of re-polling the node.
+use std::fmt;
+
+use futures::future::BoxFuture;
+use tokio::sync::Notify;
+
+/// Signals that the node may have connected a new block.
+///
+/// Wakeups may be spurious (the sync loop just asks the node again), but should not be lost: a
+/// block connected while nobody is waiting must still wake the next caller.
+pub trait TipNotifier: fmt::Debug + Send + Sync {
+    /// Resolves on the next new-block notification.
+    fn notified(&self) -> BoxFuture<'_, ()>;
+}
+
+/// `notify_one` signals a new block; a notification sent while nobody waits is kept for the
+/// next one.
+impl TipNotifier for Notify {
+    fn notified(&self) -> BoxFuture<'_, ()> {
+        Box::pin(Notify::notified(self))
+    }
+}
+
+#[cfg(feature = "zmq")]
+pub use zmq::ZmqNotifier;
+
+#[cfg(feature = "zmq")]
+mod zmq {
+    use std::sync::Arc;
+
+    use futures::future::BoxFuture;
+    use tokio::sync::Notify;
+    use tokio::task::JoinHandle;
+    use tracing::{debug, warn};
+    use zeromq::{Socket, SocketRecv, SubSocket, ZmqError};
+
+    use super::TipNotifier;
+
+    /// The topic `zcashd -zmqpubhashblock=<endpoint>` publishes each connected block's hash on.
+    const HASHBLOCK_TOPIC: &str = "hashblock";
+
+    /// A [`TipNotifier`] fed by a node's `hashblock` ZMQ publisher.
+    ///
+    /// A background task holds the subscription. If it fails the notifier simply goes quiet,
+    /// and the sync loop falls back to polling at its `max_poll_interval`.
+    #[derive(Debug)]
+    pub struct ZmqNotifier {
+        notify: Arc<Notify>,
+        subscription: JoinHandle<()>,
+    }
+
+    impl ZmqNotifier {
+        /// Subscribes to `hashblock` at `endpoint`, e.g. `tcp://127.0.0.1:28332`.
+        pub async fn connect(endpoint: &str) -> Result<Self, ZmqError> {
+            let mut socket = SubSocket::new();
+            socket.connect(endpoint).await?;
+            socket.subscribe(HASHBLOCK_TOPIC).await?;
+
+            let notify = Arc::new(Notify::new());
+            let task_notify = Arc::clone(&notify);
+            let endpoint = endpoint.to_string();
+            let subscription = tokio::spawn(async move {
+                loop {
+                    match socket.recv().await {
+                        Ok(_) => {
+                            debug!("New block announced on {endpoint}");
+                            task_notify.notify_one();
+                        }
+                        Err(e) => {
+                            warn!("ZMQ subscription to {endpoint} failed: {e}; polling instead");
+                            break;
+                        }
+                    }
+                }
+            });
+            Ok(ZmqNotifier {
+                notify,
+                subscription,
+            })
+        }
+    }
+
+    impl Drop for ZmqNotifier {
+        fn drop(&mut self) {
+            self.subscription.abort();
+        }
+    }
+
+    impl TipNotifier for ZmqNotifier {
+        fn notified(&self) -> BoxFuture<'_, ()> {
+            Box::pin(self.notify.notified())
+        }
+    }
+}
@@ -4,19 +4,36 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Value, json};
 use std::fmt;
+use std::time::Duration;
 
+use crate::net::HeaderSource;
+use zcash_crypto::Network;
 use zcash_primitives::block::{BlockHash, BlockHeader};
 
 /// Errors that can occur when talking to a `zcashd` JSON-RPC endpoint.
 #[derive(Debug)]
 pub enum RpcError {
     NonHttpUrl,
+    /// Local failures that did not come from the HTTP stack (bad URL, oversized response, ...).
     Client(String),
+    /// The request did not complete within the client's timeout.
+    Timeout,
+    /// The node could not be reached: connection refused or reset, DNS failure, TLS handshake.
+    Connect(String),
+    /// Any other HTTP-level failure, e.g. the connection dropping mid-body.
+    Transport(String),
     Json(serde_json::Error),
     Status(StatusCode),
-    Rpc { code: i64, message: String },
+    Rpc {
+        code: i64,
+        message: String,
+    },
     Hex(hex::FromHexError),
     DecodeHeader(String),
+    UnknownNetwork(String),
+    /// `getblockhash` was asked for a height past the node's tip (code -8), i.e. the block
+    /// does not exist yet rather than the request having failed.
+    HeightOutOfRange(u32),
 }
 
 impl fmt::Display for RpcError {
@@ -24,6 +41,9 @@ impl fmt::Display for RpcError {
         match self {
             RpcError::NonHttpUrl => write!(f, "only http:// URLs are supported"),
             RpcError::Client(e) => write!(f, "client error: {e}"),
+            RpcError::Timeout => write!(f, "request timed out"),
+            RpcError::Connect(e) => write!(f, "connection failed: {e}"),
+            RpcError::Transport(e) => write!(f, "transport error: {e}"),
             RpcError::Json(e) => write!(f, "JSON error: {e}"),
             RpcError::Status(status) => write!(f, "unexpected HTTP status: {status}"),
             RpcError::Rpc { code, message } => {
@@ -31,18 +51,135 @@ impl fmt::Display for RpcError {
             }
             RpcError::Hex(e) => write!(f, "hex decoding error: {e}"),
             RpcError::DecodeHeader(e) => write!(f, "failed to decode block header: {e}"),
+            RpcError::UnknownNetwork(chain) => write!(f, "unknown network: {chain}"),
+            RpcError::HeightOutOfRange(height) => {
+                write!(f, "block height {height} is past the node's tip")
+            }
         }
     }
 }
 
 impl std::error::Error for RpcError {}
 
+impl RpcError {
+    /// The typed JSON-RPC error code, if the node answered with an error object.
+    pub fn rpc_code(&self) -> Option<RpcErrorCode> {
+        match self {
+            RpcError::Rpc { code, .. } => Some(RpcErrorCode::from(*code)),
+            RpcError::HeightOutOfRange(_) => Some(RpcErrorCode::InvalidParameter),
+            _ => None,
+        }
+    }
+
+    /// `zcashd` answers `getblockhash` past the chain tip with code -8
+    /// ("Block height out of range").
+    pub fn is_height_out_of_range(&self) -> bool {
+        self.rpc_code() == Some(RpcErrorCode::InvalidParameter)
+    }
+}
+
+/// Common `zcashd` JSON-RPC error codes, as defined in its `rpc/protocol.h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorCode {
+    /// -32600
+    InvalidRequest,
+    /// -32601: the node does not implement the method.
+    MethodNotFound,
+    /// -32602
+    InvalidParams,
+    /// -32603
+    InternalError,
+    /// -32700
+    ParseError,
+    /// -1: generic error, e.g. a missing `result` field.
+    MiscError,
+    /// -3: a parameter has the wrong type.
+    TypeError,
+    /// -5: unknown block hash ("Block not found") or address.
+    InvalidAddressOrKey,
+    /// -7
+    OutOfMemory,
+    /// -8: invalid parameter value, e.g. "Block height out of range" past the tip.
+    InvalidParameter,
+    /// -20
+    DatabaseError,
+    /// -22: a hex or serialized argument failed to decode.
+    DeserializationError,
+    /// -28: the node is still loading its block index.
+    InWarmup,
+    /// Any code not listed above.
+    Other(i64),
+}
+
+impl From<i64> for RpcErrorCode {
+    fn from(code: i64) -> Self {
+        match code {
+            -32600 => RpcErrorCode::InvalidRequest,
+            -32601 => RpcErrorCode::MethodNotFound,
+            -32602 => RpcErrorCode::InvalidParams,
+            -32603 => RpcErrorCode::InternalError,
+            -32700 => RpcErrorCode::ParseError,
+            -1 => RpcErrorCode::MiscError,
+            -3 => RpcErrorCode::TypeError,
+            -5 => RpcErrorCode::InvalidAddressOrKey,
+            -7 => RpcErrorCode::OutOfMemory,
+            -8 => RpcErrorCode::InvalidParameter,
+            -20 => RpcErrorCode::DatabaseError,
+            -22 => RpcErrorCode::DeserializationError,
+            -28 => RpcErrorCode::InWarmup,
+            other => RpcErrorCode::Other(other),
+        }
+    }
+}
+
+impl RpcErrorCode {
+    /// The numeric code as sent on the wire.
+    pub fn code(self) -> i64 {
+        match self {
+            RpcErrorCode::InvalidRequest => -32600,
+            RpcErrorCode::MethodNotFound => -32601,
+            RpcErrorCode::InvalidParams => -32602,
+            RpcErrorCode::InternalError => -32603,
+            RpcErrorCode::ParseError => -32700,
+            RpcErrorCode::MiscError => -1,
+            RpcErrorCode::TypeError => -3,
+            RpcErrorCode::InvalidAddressOrKey => -5,
+            RpcErrorCode::OutOfMemory => -7,
+            RpcErrorCode::InvalidParameter => -8,
+            RpcErrorCode::DatabaseError => -20,
+            RpcErrorCode::DeserializationError => -22,
+            RpcErrorCode::InWarmup => -28,
+            RpcErrorCode::Other(code) => code,
+        }
+    }
+}
+
 impl From<serde_json::Error> for RpcError {
     fn from(e: serde_json::Error) -> Self {
         RpcError::Json(e)
     }
 }
 
+/// Classifies transport failures so callers can tell timeouts and unreachable nodes apart.
+impl From<reqwest::Error> for RpcError {
+    fn from(e: reqwest::Error) -> Self {
+        // reqwest's own message is terse ("error sending request"); the cause is in the chain.
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {cause}"));
+            source = cause.source();
+        }
+        if e.is_timeout() {
+            RpcError::Timeout
+        } else if e.is_connect() {
+            RpcError::Connect(message)
+        } else {
+            RpcError::Transport(message)
+        }
+    }
+}
+
 impl From<hex::FromHexError> for RpcError {
     fn from(e: hex::FromHexError) -> Self {
         RpcError::Hex(e)
@@ -52,7 +189,7 @@ impl From<hex::FromHexError> for RpcError {
 #[derive(Serialize)]
 struct JsonRpcRequest<'a> {
     jsonrpc: &'static str,
-    id: &'a str,
+    id: Value,
     method: &'a str,
     #[serde(borrow)]
     params: &'a [Value],
@@ -64,14 +201,121 @@ struct JsonRpcError {
     message: String,
 }
 
+#[derive(Deserialize)]
+struct BlockchainInfo {
+    chain: String,
+}
+
+/// The header fields of a `getblockheader <hash> true` result. `uint256` fields are hex in
+/// RPC (byte-reversed) order; `bits` is 8 hex digits; `solution` is in serialized order.
+#[derive(Deserialize)]
+struct VerboseHeader {
+    hash: String,
+    version: i32,
+    /// Absent for the genesis block.
+    previousblockhash: Option<String>,
+    merkleroot: String,
+    /// The header's commitment field since Heartwood; older nodes only report
+    /// `finalsaplingroot`, which it equalled before then.
+    blockcommitments: Option<String>,
+    finalsaplingroot: Option<String>,
+    time: u32,
+    bits: String,
+    nonce: String,
+    solution: String,
+}
+
+impl VerboseHeader {
+    /// Re-serializes the fields and decodes the result, checking it hashes to `hash`.
+    fn into_header(self) -> Result<BlockHeader, RpcError> {
+        let commitments = self
+            .blockcommitments
+            .or(self.finalsaplingroot)
+            .ok_or_else(|| {
+                RpcError::DecodeHeader("missing blockcommitments/finalsaplingroot".to_string())
+            })?;
+        let prev = match &self.previousblockhash {
+            Some(prev) => decode_uint256_from_hex(prev)?,
+            None => [0; 32],
+        };
+        let bits = u32::from_str_radix(&self.bits, 16)
+            .map_err(|e| RpcError::DecodeHeader(format!("bits {:?}: {e}", self.bits)))?;
+        let solution = hex::decode(&self.solution)?;
+
+        let mut raw = Vec::with_capacity(143 + solution.len());
+        raw.extend_from_slice(&self.version.to_le_bytes());
+        raw.extend_from_slice(&prev);
+        raw.extend_from_slice(&decode_uint256_from_hex(&self.merkleroot)?);
+        raw.extend_from_slice(&decode_uint256_from_hex(&commitments)?);
+        raw.extend_from_slice(&self.time.to_le_bytes());
+        raw.extend_from_slice(&bits.to_le_bytes());
+        raw.extend_from_slice(&decode_uint256_from_hex(&self.nonce)?);
+        write_compact_size(&mut raw, solution.len());
+        raw.extend_from_slice(&solution);
+
+        let header =
+            BlockHeader::read(&raw[..]).map_err(|e| RpcError::DecodeHeader(e.to_string()))?;
+        if header.hash() != decode_block_hash_from_hex(&self.hash)? {
+            return Err(RpcError::DecodeHeader(format!(
+                "reconstructed header does not hash to {}",
+                self.hash
+            )));
+        }
+        Ok(header)
+    }
+}
+
+/// A `getblock <hash> 1` result: the verbose header fields plus where the node places the
+/// block. The transaction list and other fields are ignored.
+#[derive(Deserialize)]
+struct VerboseBlock {
+    height: u32,
+    confirmations: i64,
+    #[serde(flatten)]
+    header: VerboseHeader,
+}
+
+/// A block header with the node's view of its position, from [`RpcClient::get_block_verbose`].
+#[derive(Debug, Clone)]
+pub struct BlockMeta {
+    pub height: u32,
+    /// Blocks on the best chain from this one to the tip, itself included; `-1` once the block
+    /// has been disconnected from the best chain.
+    pub confirmations: i64,
+    pub header: BlockHeader,
+}
+
 #[derive(Deserialize)]
 struct JsonRpcResponse<T> {
     result: Option<T>,
     error: Option<JsonRpcError>,
-    #[allow(dead_code)]
     id: Value,
 }
 
+impl<T> JsonRpcResponse<T> {
+    fn into_result(self) -> Result<T, RpcError> {
+        if let Some(err) = self.error {
+            return Err(RpcError::Rpc {
+                code: err.code,
+                message: err.message,
+            });
+        }
+
+        self.result.ok_or_else(|| RpcError::Rpc {
+            code: -1,
+            message: "missing result field in RPC response".to_string(),
+        })
+    }
+}
+
+/// Default cap on a single response body: a maximum-size (2 MB) block as hex plus JSON
+/// framing fits comfortably, while header-only responses are a few KB.
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Default limit on a whole request, from connecting to reading the last byte of the body:
+/// ample for a header or a full block, but a node that stops responding cannot stall a sync.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Minimal JSON-RPC client for talking to a `zcashd`-compatible node over HTTP(S).
 ///
 /// This is intentionally small and opinionated:
@@ -79,6 +323,8 @@ struct JsonRpcResponse<T> {
 pub struct RpcClient {
     client: Client,
     url: Url,
+    max_response_bytes: usize,
+    timeout: Duration,
 }
 
 impl RpcClient {
@@ -95,9 +341,34 @@ impl RpcClient {
             }
         }
 
-        let client = Client::new();
+        let client = Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .build()
+            .map_err(|e| RpcError::Client(e.to_string()))?;
 
-        Ok(RpcClient { client, url })
+        Ok(RpcClient {
+            client,
+            url,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// Caps the size of any response body (default [`DEFAULT_MAX_RESPONSE_BYTES`]).
+    ///
+    /// Bodies are read incrementally and abandoned with [`RpcError::Client`] as soon as
+    /// they exceed the cap, so a misbehaving node cannot make the client buffer
+    /// arbitrarily large responses.
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Limits how long any one request may take (default [`DEFAULT_TIMEOUT`]), reading the
+    /// response body included; a request that runs over fails with [`RpcError::Timeout`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
     }
 
     async fn call<T>(&self, method: &str, params: &[Value]) -> Result<T, RpcError>
@@ -106,43 +377,100 @@ impl RpcClient {
     {
         let request_body = JsonRpcRequest {
             jsonrpc: "1.0",
-            id: "light-client-minimal",
+            id: json!("light-client-minimal"),
             method,
             params,
         };
+        let bytes = self.post(method, &request_body).await?;
+        serde_json::from_slice::<JsonRpcResponse<T>>(&bytes)?.into_result()
+    }
 
+    /// Sends one JSON-RPC batch calling `method` once per entry of `params`, and returns each
+    /// call's outcome in the order of `params`.
+    ///
+    /// The node may answer a batch in any order; responses are matched back by id. The whole
+    /// batch fails if the request does, or if any call goes unanswered.
+    async fn call_batch<T>(
+        &self,
+        method: &str,
+        params: &[Vec<Value>],
+    ) -> Result<Vec<Result<T, RpcError>>, RpcError>
+    where
+        T: DeserializeOwned,
+    {
+        let request_body: Vec<JsonRpcRequest> = params
+            .iter()
+            .enumerate()
+            .map(|(i, params)| JsonRpcRequest {
+                jsonrpc: "1.0",
+                id: json!(i),
+                method,
+                params,
+            })
+            .collect();
+        let bytes = self.post(method, &request_body).await?;
+        let responses: Vec<JsonRpcResponse<T>> = serde_json::from_slice(&bytes)?;
+
+        let mut results: Vec<Option<Result<T, RpcError>>> =
+            std::iter::repeat_with(|| None).take(params.len()).collect();
+        for response in responses {
+            let slot = response
+                .id
+                .as_u64()
+                .and_then(|id| results.get_mut(usize::try_from(id).ok()?))
+                .ok_or_else(|| {
+                    RpcError::Client(format!("{method} batch: unexpected id {}", response.id))
+                })?;
+            *slot = Some(response.into_result());
+        }
+        results
+            .into_iter()
+            .enumerate()
+            .map(|(i, result)| {
+                let missing = || RpcError::Client(format!("{method} batch: no response to {i}"));
+                result.ok_or_else(missing)
+            })
+            .collect()
+    }
+
+    /// POSTs `body` and reads the response body, within `max_response_bytes`.
+    async fn post<B: Serialize + ?Sized>(
+        &self,
+        method: &str,
+        body: &B,
+    ) -> Result<Vec<u8>, RpcError> {
         let req = self
             .client
             .post(self.url.clone())
+            .timeout(self.timeout)
             .header(header::CONTENT_TYPE, "application/json");
 
-        let res = req
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| RpcError::Client(e.to_string()))?;
+        let mut res = req.json(body).send().await?;
 
         if !res.status().is_success() {
             return Err(RpcError::Status(res.status()));
         }
 
-        let bytes = res
-            .bytes()
-            .await
-            .map_err(|e| RpcError::Client(e.to_string()))?;
-        let rpc_response: JsonRpcResponse<T> = serde_json::from_slice(&bytes)?;
-
-        if let Some(err) = rpc_response.error {
-            return Err(RpcError::Rpc {
-                code: err.code,
-                message: err.message,
-            });
+        let too_large = || {
+            RpcError::Client(format!(
+                "{method} response exceeds {} bytes",
+                self.max_response_bytes
+            ))
+        };
+        if res
+            .content_length()
+            .is_some_and(|len| len > self.max_response_bytes as u64)
+        {
+            return Err(too_large());
         }
-
-        rpc_response.result.ok_or_else(|| RpcError::Rpc {
-            code: -1,
-            message: "missing result field in RPC response".to_string(),
-        })
+        let mut bytes = Vec::new();
+        while let Some(chunk) = res.chunk().await? {
+            if bytes.len() + chunk.len() > self.max_response_bytes {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
     }
 
     /// Returns the current block height reported by the node (`getblockcount`).
@@ -150,6 +478,18 @@ impl RpcClient {
         self.call("getblockcount", &[]).await
     }
 
+    /// Returns the network the node is running on, from the `chain` field of
+    /// `getblockchaininfo` (`"main"`, `"test"` or `"regtest"`).
+    pub async fn get_network(&self) -> Result<Network, RpcError> {
+        let info: BlockchainInfo = self.call("getblockchaininfo", &[]).await?;
+        match info.chain.as_str() {
+            "main" => Ok(Network::Mainnet),
+            "test" => Ok(Network::Testnet),
+            "regtest" => Ok(Network::Regtest),
+            _ => Err(RpcError::UnknownNetwork(info.chain)),
+        }
+    }
+
     /// Returns the hash of the best chain tip (`getbestblockhash`).
     pub async fn get_best_block_hash(&self) -> Result<BlockHash, RpcError> {
         let hash_hex: String = self.call("getbestblockhash", &[]).await?;
@@ -157,11 +497,39 @@ impl RpcClient {
     }
 
     /// Returns the block hash at the given height (`getblockhash`).
+    ///
+    /// A height past the tip yields [`RpcError::HeightOutOfRange`].
     pub async fn get_block_hash(&self, height: u32) -> Result<BlockHash, RpcError> {
-        let hash_hex: String = self.call("getblockhash", &[json!(height)]).await?;
+        let hash_hex: String = match self.call("getblockhash", &[json!(height)]).await {
+            Ok(hash_hex) => hash_hex,
+            Err(e) if e.is_height_out_of_range() => return Err(RpcError::HeightOutOfRange(height)),
+            Err(e) => return Err(e),
+        };
         decode_block_hash_from_hex(&hash_hex)
     }
 
+    /// Returns the block hashes at `heights`, in the same order, from a single batch of
+    /// `getblockhash` calls.
+    ///
+    /// Fails with the first failing call's error; a height past the tip yields
+    /// [`RpcError::HeightOutOfRange`] as for [`Self::get_block_hash`].
+    pub async fn get_block_hashes(&self, heights: &[u32]) -> Result<Vec<BlockHash>, RpcError> {
+        if heights.is_empty() {
+            return Ok(Vec::new());
+        }
+        let params: Vec<Vec<Value>> = heights.iter().map(|&h| vec![json!(h)]).collect();
+        let results = self.call_batch::<String>("getblockhash", &params).await?;
+        heights
+            .iter()
+            .zip(results)
+            .map(|(&height, result)| match result {
+                Ok(hash_hex) => decode_block_hash_from_hex(&hash_hex),
+                Err(e) if e.is_height_out_of_range() => Err(RpcError::HeightOutOfRange(height)),
+                Err(e) => Err(e),
+            })
+            .collect()
+    }
+
     /// Returns the raw block bytes for the given hash (`getblock` with `verbosity = 0`).
     pub async fn get_block(&self, hash: &BlockHash) -> Result<Vec<u8>, RpcError> {
         let hash_hex = encode_block_hash_to_hex(hash);
@@ -169,10 +537,69 @@ impl RpcClient {
         Ok(hex::decode(block_hex)?)
     }
 
-    /// Fetches a block and decodes its header using `zcash_primitives`.
+    /// Fetches just the serialized header (`getblockheader` with `verbose = false`) and decodes
+    /// it using `zcash_primitives`.
+    ///
+    /// Nodes that do not implement `getblockheader` get the full block via [`Self::get_block`]
+    /// instead, whose leading bytes are the header.
     pub async fn get_block_header(&self, hash: &BlockHash) -> Result<BlockHeader, RpcError> {
-        let raw_block = self.get_block(hash).await?;
-        BlockHeader::read(&raw_block[..]).map_err(|e| RpcError::DecodeHeader(e.to_string()))
+        let hash_hex = encode_block_hash_to_hex(hash);
+        let raw = match self
+            .call::<String>("getblockheader", &[json!(hash_hex), json!(false)])
+            .await
+        {
+            Ok(header_hex) => hex::decode(header_hex)?,
+            Err(e) if e.rpc_code() == Some(RpcErrorCode::MethodNotFound) => {
+                self.get_block(hash).await?
+            }
+            Err(e) => return Err(e),
+        };
+        BlockHeader::read(&raw[..]).map_err(|e| RpcError::DecodeHeader(e.to_string()))
+    }
+
+    /// Fetches the header via `getblockheader <hash> true`, for providers that only serve the
+    /// verbose JSON form, and rebuilds the serialized header from its fields.
+    ///
+    /// The rebuilt header must hash to both the response's `hash` field and the requested
+    /// `hash`, so a mis-decoded (or misreported) field yields [`RpcError::DecodeHeader`].
+    pub async fn get_block_header_verbose(
+        &self,
+        hash: &BlockHash,
+    ) -> Result<BlockHeader, RpcError> {
+        let hash_hex = encode_block_hash_to_hex(hash);
+        let verbose: VerboseHeader = self
+            .call("getblockheader", &[json!(hash_hex), json!(true)])
+            .await?;
+        let header = verbose.into_header()?;
+        if header.hash() != *hash {
+            return Err(RpcError::DecodeHeader(format!(
+                "node returned header {} for {hash_hex}",
+                header.hash()
+            )));
+        }
+        Ok(header)
+    }
+
+    /// Fetches the block via `getblock <hash> 1` and returns its header together with the
+    /// height and confirmations the node reports, which tell cheaply whether the block is still
+    /// on the best chain.
+    ///
+    /// The header is rebuilt and checked as in [`Self::get_block_header_verbose`].
+    pub async fn get_block_verbose(&self, hash: &BlockHash) -> Result<BlockMeta, RpcError> {
+        let hash_hex = encode_block_hash_to_hex(hash);
+        let block: VerboseBlock = self.call("getblock", &[json!(hash_hex), json!(1)]).await?;
+        let header = block.header.into_header()?;
+        if header.hash() != *hash {
+            return Err(RpcError::DecodeHeader(format!(
+                "node returned block {} for {hash_hex}",
+                header.hash()
+            )));
+        }
+        Ok(BlockMeta {
+            height: block.height,
+            confirmations: block.confirmations,
+            header,
+        })
     }
 
     /// Convenience helper: fetches the header at a given height.
@@ -180,6 +607,40 @@ impl RpcClient {
         let hash = self.get_block_hash(height).await?;
         self.get_block_header(&hash).await
     }
+
+    /// Returns the node's tip height (`getblockcount`) and the header at that height.
+    ///
+    /// The header is looked up by height rather than via `getbestblockhash`, so the pair stays
+    /// consistent if a block arrives between the two calls.
+    pub async fn get_best_block_header(&self) -> Result<(u32, BlockHeader), RpcError> {
+        let count = self.get_block_count().await?;
+        let height = u32::try_from(count)
+            .map_err(|_| RpcError::Client(format!("block count {count} does not fit in u32")))?;
+        let header = self.get_block_header_by_height(height).await?;
+        Ok((height, header))
+    }
+}
+
+impl HeaderSource for RpcClient {
+    async fn header_by_height(&self, h: u32) -> Result<BlockHeader, RpcError> {
+        self.get_block_header_by_height(h).await
+    }
+
+    async fn block_hash(&self, h: u32) -> Result<BlockHash, RpcError> {
+        self.get_block_hash(h).await
+    }
+
+    async fn block_count(&self) -> Result<u64, RpcError> {
+        self.get_block_count().await
+    }
+
+    async fn confirmations(&self, hash: &BlockHash) -> Result<Option<i64>, RpcError> {
+        Ok(Some(self.get_block_verbose(hash).await?.confirmations))
+    }
+
+    async fn network(&self) -> Result<Network, RpcError> {
+        self.get_network().await
+    }
 }
 
 fn decode_block_hash_from_hex(s: &str) -> Result<BlockHash, RpcError> {
@@ -189,6 +650,25 @@ fn decode_block_hash_from_hex(s: &str) -> Result<BlockHash, RpcError> {
         .ok_or_else(|| RpcError::DecodeHeader("block hash must be 32 bytes".to_string()))
 }
 
+fn decode_uint256_from_hex(s: &str) -> Result<[u8; 32], RpcError> {
+    decode_block_hash_from_hex(s).map(|hash| hash.0)
+}
+
+/// Bitcoin-style `CompactSize` length prefix.
+fn write_compact_size(out: &mut Vec<u8>, len: usize) {
+    match len {
+        0..0xfd => out.push(len as u8),
+        0xfd..=0xffff => {
+            out.push(0xfd);
+            out.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+        _ => {
+            out.push(0xfe);
+            out.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+    }
+}
+
 fn encode_block_hash_to_hex(hash: &BlockHash) -> String {
     let mut bytes = hash.0;
     bytes.reverse();
@@ -0,0 +1,124 @@
+//! A snapshot of where sync stands, the store's tip and the difficulty context for it, in one
+//! file that can be backed up and restored with the header store.
+use core::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use zcash_crypto::DifficultyContext;
+use zcash_primitives::block::BlockHash;
+
+/// First bytes of every state file.
+const MAGIC: &[u8; 4] = b"ZLCS";
+
+/// The format [`SyncState::save`] writes. Bump it whenever the layout after the version byte
+/// changes, so an older binary rejects the file instead of misreading it.
+pub const STATE_VERSION: u8 = 1;
+
+/// Errors saving or loading a [`SyncState`].
+#[derive(Debug)]
+pub enum SyncStateError {
+    Io(io::Error),
+    /// The file does not start with the state file magic.
+    NotAStateFile,
+    /// Written in a format this build does not read.
+    UnsupportedVersion(u8),
+    /// The payload is truncated, or otherwise not a valid state of the current version.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for SyncStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncStateError::Io(e) => write!(f, "sync state I/O: {e}"),
+            SyncStateError::NotAStateFile => write!(f, "not a sync state file"),
+            SyncStateError::UnsupportedVersion(v) => write!(
+                f,
+                "sync state format version {v} is not supported (expected {STATE_VERSION})"
+            ),
+            SyncStateError::Malformed(what) => write!(f, "malformed sync state: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for SyncStateError {}
+
+impl From<io::Error> for SyncStateError {
+    fn from(e: io::Error) -> Self {
+        SyncStateError::Io(e)
+    }
+}
+
+/// The store's tip and the difficulty context describing the headers up to it.
+///
+/// Layout: the magic `ZLCS`, a version byte ([`STATE_VERSION`]), `tip_height` as a
+/// little-endian `u32`, the 32 bytes of `tip_hash`, then [`DifficultyContext::to_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncState {
+    pub tip_height: u32,
+    /// Hash of the header at `tip_height`, so a state that no longer matches the store (e.g.
+    /// after a reorg or a store restored from another backup) can be told apart.
+    pub tip_hash: BlockHash,
+    /// Context whose `tip_height` is `tip_height`, ready to verify the next header.
+    pub context: DifficultyContext,
+}
+
+impl SyncState {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::from(*MAGIC);
+        out.push(STATE_VERSION);
+        out.extend_from_slice(&self.tip_height.to_le_bytes());
+        out.extend_from_slice(&self.tip_hash.0);
+        out.extend_from_slice(&self.context.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SyncStateError> {
+        let rest = bytes
+            .strip_prefix(MAGIC)
+            .ok_or(SyncStateError::NotAStateFile)?;
+        let (&version, rest) = rest
+            .split_first()
+            .ok_or(SyncStateError::Malformed("missing version"))?;
+        if version != STATE_VERSION {
+            return Err(SyncStateError::UnsupportedVersion(version));
+        }
+        let (tip, rest) = rest
+            .split_first_chunk::<4>()
+            .ok_or(SyncStateError::Malformed("missing tip height"))?;
+        let tip_height = u32::from_le_bytes(*tip);
+        let (tip_hash, rest) = rest
+            .split_first_chunk::<32>()
+            .ok_or(SyncStateError::Malformed("missing tip hash"))?;
+        let context = DifficultyContext::from_bytes(rest)
+            .ok_or(SyncStateError::Malformed("invalid difficulty context"))?;
+        if context.tip_height != tip_height {
+            return Err(SyncStateError::Malformed(
+                "context is not at the tip height",
+            ));
+        }
+        Ok(SyncState {
+            tip_height,
+            tip_hash: BlockHash(*tip_hash),
+            context,
+        })
+    }
+
+    /// Writes the state to `path`, replacing any previous one atomically: the bytes go to a
+    /// temporary file next to it, which is synced and then renamed over `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SyncStateError> {
+        let path = path.as_ref();
+        let mut tmp = PathBuf::from(path);
+        tmp.as_mut_os_string().push(".tmp");
+
+        let mut file = File::create(&tmp)?;
+        file.write_all(&self.to_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SyncStateError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
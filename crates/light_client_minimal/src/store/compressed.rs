@@ -0,0 +1,328 @@
+//! Archival store keeping headers in zstd-compressed, block-framed files.
+//!
+//! Layout for a store at `path`:
+//! - `path`: concatenated zstd frames, each holding up to [`FRAME_RECORDS`] records encoded as
+//!   `height: u32 LE | len: u32 LE | header bytes`;
+//! - `path.idx`: one fixed-size [`IndexEntry`] per frame (offset, length and height range), so
+//!   `get` only decompresses the frame that can contain the requested height;
+//! - `path.pending`: a plain [`FileStore`] holding records not yet sealed into a frame.
+//!
+//! A frame is sealed by appending it to the data file, then its index entry, then truncating
+//! the pending file. A crash in between leaves either unindexed trailing bytes (ignored) or
+//! records present in both a frame and the pending file (the pending copy wins), so no
+//! acknowledged write is lost. As with [`FileStore`], the most recent record for a height wins.
+use std::collections::{BTreeMap, btree_map};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use super::file::FileStore;
+use super::{HeaderIter, Store};
+
+/// Number of records compressed together into one frame.
+pub const FRAME_RECORDS: usize = 256;
+
+const ZSTD_LEVEL: i32 = 19;
+
+/// Sidecar index entry describing one sealed frame.
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    offset: u64,
+    len: u32,
+    min_height: u32,
+    max_height: u32,
+    /// Height of the last record written to the frame, used for `tip`.
+    last_height: u32,
+}
+
+impl IndexEntry {
+    const SIZE: usize = 24;
+
+    fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut out = [0u8; Self::SIZE];
+        out[0..8].copy_from_slice(&self.offset.to_le_bytes());
+        out[8..12].copy_from_slice(&self.len.to_le_bytes());
+        out[12..16].copy_from_slice(&self.min_height.to_le_bytes());
+        out[16..20].copy_from_slice(&self.max_height.to_le_bytes());
+        out[20..24].copy_from_slice(&self.last_height.to_le_bytes());
+        out
+    }
+
+    fn from_bytes(b: &[u8]) -> Self {
+        let u32_at = |i: usize| u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+        let mut offset = [0u8; 8];
+        offset.copy_from_slice(&b[0..8]);
+        IndexEntry {
+            offset: u64::from_le_bytes(offset),
+            len: u32_at(8),
+            min_height: u32_at(12),
+            max_height: u32_at(16),
+            last_height: u32_at(20),
+        }
+    }
+}
+
+pub struct CompressedFileStore {
+    data_path: PathBuf,
+    index_path: PathBuf,
+    pending: FileStore,
+}
+
+impl CompressedFileStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let data_path = path.as_ref().to_path_buf();
+        let pending = FileStore::new(sidecar(&data_path, "pending"))?;
+        let index_path = sidecar(&data_path, "idx");
+        for p in [&data_path, &index_path] {
+            if !p.exists() {
+                File::create(p)?;
+            }
+        }
+        Ok(CompressedFileStore {
+            data_path,
+            index_path,
+            pending,
+        })
+    }
+
+    /// Seals any pending records into a frame, e.g. before archiving the files.
+    pub fn flush(&self) -> io::Result<()> {
+        let records = self.pending.last_n(usize::MAX)?;
+        if records.is_empty() {
+            return Ok(());
+        }
+        self.seal(&records)
+    }
+
+    fn seal(&self, records: &[(u32, String)]) -> io::Result<()> {
+        let mut raw = Vec::new();
+        for (height, header_hex) in records {
+            let bytes = hex::decode(header_hex).map_err(|e| io::Error::other(e.to_string()))?;
+            raw.extend_from_slice(&height.to_le_bytes());
+            raw.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            raw.extend_from_slice(&bytes);
+        }
+        let frame = zstd::bulk::compress(&raw, ZSTD_LEVEL)?;
+
+        let mut data = OpenOptions::new().append(true).open(&self.data_path)?;
+        let offset = data.seek(SeekFrom::End(0))?;
+        data.write_all(&frame)?;
+        data.sync_data()?;
+
+        let entry = IndexEntry {
+            offset,
+            len: frame.len() as u32,
+            min_height: records.iter().map(|(h, _)| *h).min().unwrap_or(0),
+            max_height: records.iter().map(|(h, _)| *h).max().unwrap_or(0),
+            last_height: records.last().map(|(h, _)| *h).unwrap_or(0),
+        };
+        let mut index = OpenOptions::new().append(true).open(&self.index_path)?;
+        index.write_all(&entry.to_bytes())?;
+        index.sync_data()?;
+
+        self.pending.truncate()
+    }
+
+    /// Looks `height` up in the sealed frames only, newest frame first.
+    fn get_sealed(&self, height: u32) -> io::Result<Option<String>> {
+        for entry in self.read_index()?.iter().rev() {
+            if entry.min_height <= height && height <= entry.max_height {
+                let found = read_frame(&self.data_path, entry)?
+                    .into_iter()
+                    .rev()
+                    .find(|(h, _)| *h == height);
+                if let Some((_, hex)) = found {
+                    return Ok(Some(hex));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn read_index(&self) -> io::Result<Vec<IndexEntry>> {
+        let bytes = std::fs::read(&self.index_path)?;
+        Ok(bytes
+            .chunks_exact(IndexEntry::SIZE)
+            .map(IndexEntry::from_bytes)
+            .collect())
+    }
+}
+
+fn read_frame(data_path: &Path, entry: &IndexEntry) -> io::Result<Vec<(u32, String)>> {
+    let raw = read_raw_frame(data_path, entry)?;
+    Ok(frame_records(&raw)?
+        .into_iter()
+        .map(|(height, bytes)| (height, hex::encode(bytes)))
+        .collect())
+}
+
+/// Decompressed frame contents, still in the `(height, len, bytes)` record layout.
+fn read_raw_frame(data_path: &Path, entry: &IndexEntry) -> io::Result<Vec<u8>> {
+    let mut data = File::open(data_path)?;
+    data.seek(SeekFrom::Start(entry.offset))?;
+    let mut frame = vec![0u8; entry.len as usize];
+    data.read_exact(&mut frame)?;
+    zstd::stream::decode_all(&frame[..])
+}
+
+/// Splits a decompressed frame into its `(height, header bytes)` records.
+fn frame_records(raw: &[u8]) -> io::Result<Vec<(u32, &[u8])>> {
+    let mut records = Vec::new();
+    let mut rest = raw;
+    while rest.len() >= 8 {
+        let height = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]);
+        let len = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let Some(bytes) = rest.get(8..8 + len) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "truncated record in compressed frame",
+            ));
+        };
+        records.push((height, bytes));
+        rest = &rest[8 + len..];
+    }
+    Ok(records)
+}
+
+fn sidecar(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(ext);
+    PathBuf::from(name)
+}
+
+impl Store for CompressedFileStore {
+    fn put(&self, height: u32, header_hex: &str) -> io::Result<()> {
+        hex::decode(header_hex).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let stored = match self.pending.written_at(height)? {
+            Some(hex) => Some(hex),
+            None => self.get_sealed(height)?,
+        };
+        if stored.as_deref() == Some(header_hex) {
+            return Ok(());
+        }
+        self.pending.put(height, header_hex)?;
+        if self.pending.len()? >= FRAME_RECORDS {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, height: u32) -> io::Result<Option<String>> {
+        if let Some(hex) = self.pending.get(height)? {
+            return Ok(Some(hex));
+        }
+        self.get_sealed(height)
+    }
+
+    /// Checks the pending tail, then only the frames whose height range covers `height`, without
+    /// hex-encoding their records.
+    fn contains(&self, height: u32) -> io::Result<bool> {
+        if self.pending.contains(height)? {
+            return Ok(true);
+        }
+        for entry in self.read_index()? {
+            if entry.min_height <= height && height <= entry.max_height {
+                let raw = read_raw_frame(&self.data_path, &entry)?;
+                if frame_records(&raw)?.iter().any(|(h, _)| *h == height) {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    fn tip(&self) -> io::Result<Option<u32>> {
+        if let Some(tip) = self.pending.tip()? {
+            return Ok(Some(tip));
+        }
+        Ok(self.read_index()?.last().map(|e| e.last_height))
+    }
+
+    fn last_n(&self, n: usize) -> io::Result<Vec<(u32, String)>> {
+        // Newest data first: the pending tail, then frames from the most recent back,
+        // keeping only the first (latest) record seen for each height.
+        let mut recs: BTreeMap<u32, String> =
+            self.pending.last_n(usize::MAX)?.into_iter().collect();
+        for entry in self.read_index()?.iter().rev() {
+            if recs.len() >= n {
+                break;
+            }
+            for (height, hex) in read_frame(&self.data_path, entry)?.into_iter().rev() {
+                recs.entry(height).or_insert(hex);
+            }
+        }
+        let skip = recs.len().saturating_sub(n);
+        Ok(recs.into_iter().skip(skip).collect())
+    }
+
+    /// Indexes which frame (or the pending tail) holds each height's latest record, then
+    /// decompresses frames one at a time as iteration reaches them; at most one frame's headers
+    /// and the pending tail are held in memory.
+    fn iter_headers(&self) -> io::Result<HeaderIter> {
+        let index = self.read_index()?;
+        let mut sources = BTreeMap::new();
+        for (i, entry) in index.iter().enumerate() {
+            let raw = read_raw_frame(&self.data_path, entry)?;
+            for (height, _) in frame_records(&raw)? {
+                sources.insert(height, Some(i));
+            }
+        }
+        let pending: BTreeMap<u32, String> = self.pending.last_n(usize::MAX)?.into_iter().collect();
+        for height in pending.keys() {
+            sources.insert(*height, None);
+        }
+        Ok(Box::new(Headers {
+            data_path: self.data_path.clone(),
+            index,
+            sources: sources.into_iter(),
+            pending,
+            frame: None,
+        }))
+    }
+}
+
+/// Iterator behind [`CompressedFileStore::iter_headers`].
+struct Headers {
+    data_path: PathBuf,
+    index: Vec<IndexEntry>,
+    /// Frame holding the latest record for each height, or `None` for the pending tail.
+    sources: btree_map::IntoIter<u32, Option<usize>>,
+    pending: BTreeMap<u32, String>,
+    /// The most recently decompressed frame, by index position; records are taken out as
+    /// they are yielded.
+    frame: Option<(usize, BTreeMap<u32, String>)>,
+}
+
+impl Headers {
+    fn take(&mut self, height: u32, source: Option<usize>) -> io::Result<Option<String>> {
+        let Some(i) = source else {
+            return Ok(self.pending.remove(&height));
+        };
+        if self.frame.as_ref().is_none_or(|(cached, _)| *cached != i) {
+            // Collecting keeps the last record for a height written twice within the frame.
+            let recs = read_frame(&self.data_path, &self.index[i])?;
+            self.frame = Some((i, recs.into_iter().collect()));
+        }
+        Ok(self
+            .frame
+            .as_mut()
+            .and_then(|(_, recs)| recs.remove(&height)))
+    }
+}
+
+impl Iterator for Headers {
+    type Item = io::Result<(u32, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (height, source) = self.sources.next()?;
+            match self.take(height, source) {
+                Ok(Some(header_hex)) => return Some(Ok((height, header_hex))),
+                // Only possible if the files changed since `iter_headers` indexed them.
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
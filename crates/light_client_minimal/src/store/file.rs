@@ -1,19 +1,47 @@
+use std::collections::{BTreeMap, btree_map};
 use std::fs::{File, OpenOptions, create_dir_all};
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
 use serde::{Deserialize, Serialize};
 
-use super::Store;
+use super::{HeaderIter, Store, header_hash_hex};
 
 #[derive(Serialize, Deserialize)]
 struct Record {
     height: u32,
     header_hex: String,
+    /// RPC-style block hash, written since the field was introduced; older records
+    /// without it have the hash recomputed from `header_hex` when needed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hash_hex: Option<String>,
 }
 
+/// Just the height of a [`Record`]; the other fields are skipped without being allocated.
+#[derive(Deserialize)]
+struct RecordHeight {
+    height: u32,
+}
+
+/// Headers as JSON lines in a single append-only file.
+///
+/// A `FileStore` can be shared between tasks or threads (e.g. behind an `Arc`): every write goes
+/// through one append handle behind a mutex, so concurrent `put`s land as whole lines, one after
+/// the other. Readers take no lock and re-read the file, so while a batch of `put`s is in
+/// progress they may see some of its records but not yet the rest. Separate `FileStore`s opened
+/// on the same path do not coordinate with each other.
 pub struct FileStore {
     path: PathBuf,
+    writer: Mutex<Writer>,
+}
+
+/// The append handle, together with the record it last appended.
+struct Writer {
+    file: File,
+    /// `(height, header_hex)` of the last line in the file, read once on open and kept up to
+    /// date by `put`, so the common case of writing the next height needs no scan.
+    last: Option<(u32, String)>,
 }
 
 impl FileStore {
@@ -24,23 +52,123 @@ impl FileStore {
         {
             create_dir_all(dir)?;
         }
-        if !p.exists() {
-            File::create(&p)?;
+        let file = OpenOptions::new().create(true).append(true).open(&p)?;
+        let last = Self::last_record(&p)?;
+        Ok(FileStore {
+            path: p,
+            writer: Mutex::new(Writer { file, last }),
+        })
+    }
+
+    /// `(height, header_hex)` of the last record in the file at `path`.
+    fn last_record(path: &Path) -> io::Result<Option<(u32, String)>> {
+        let mut last = None;
+        for line in BufReader::new(File::open(path)?).lines() {
+            if let Ok(rec) = serde_json::from_str::<Record>(&line?) {
+                last = Some((rec.height, rec.header_hex));
+            }
+        }
+        Ok(last)
+    }
+
+    /// The append handle. A panic while it was held cannot have left a partial line behind
+    /// (records are written with a single `write_all`), so a poisoned lock is simply reused.
+    fn lock_writer(&self) -> MutexGuard<'_, Writer> {
+        self.writer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn append_record(file: &mut File, rec: &Record) -> io::Result<()> {
+        let mut line = serde_json::to_string(rec).map_err(|e| io::Error::other(e.to_string()))?;
+        line.push('\n');
+        file.write_all(line.as_bytes())
+    }
+
+    /// Collapses the log to the last record written for each height, ordered by height.
+    ///
+    /// A height is rewritten after a reorg; since the file is append-only, later wins.
+    fn latest_records(&self) -> io::Result<BTreeMap<u32, String>> {
+        Ok(self
+            .latest_full_records()?
+            .into_iter()
+            .map(|(h, rec)| (h, rec.header_hex))
+            .collect())
+    }
+
+    fn latest_full_records(&self) -> io::Result<BTreeMap<u32, Record>> {
+        let mut recs = BTreeMap::new();
+        for line in self.read_lines()? {
+            let l = line?;
+            if l.trim().is_empty() {
+                continue;
+            }
+            if let Ok(rec) = serde_json::from_str::<Record>(&l) {
+                recs.insert(rec.height, rec);
+            }
+        }
+        Ok(recs)
+    }
+
+    /// Byte offset of the last line written for each height, ordered by height.
+    ///
+    /// The same records [`Self::latest_full_records`] keeps, without holding on to their headers.
+    fn latest_offsets(&self) -> io::Result<BTreeMap<u32, u64>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut offsets = BTreeMap::new();
+        let mut line = String::new();
+        let mut pos = 0;
+        loop {
+            line.clear();
+            let n = reader.read_line(&mut line)?;
+            if n == 0 {
+                break;
+            }
+            if let Ok(rec) = serde_json::from_str::<Record>(&line) {
+                offsets.insert(rec.height, pos);
+            }
+            pos += n as u64;
         }
-        Ok(FileStore { path: p })
+        Ok(offsets)
     }
 
-    fn append_record(&self, rec: &Record) -> io::Result<()> {
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)?;
-        let line = serde_json::to_string(rec).map_err(|e| io::Error::other(e.to_string()))?;
-        file.write_all(line.as_bytes())?;
-        file.write_all(b"\n")?;
+    /// Latest header stored at `height`, as seen by the idempotency check in `put`.
+    ///
+    /// Lines are matched on the compact `"height":N,` prefix that [`Self::append_record`] writes
+    /// before being parsed, which keeps the scan cheap; a record in any other layout is simply
+    /// not seen here and gets a (harmless) duplicate appended.
+    pub(crate) fn written_at(&self, height: u32) -> io::Result<Option<String>> {
+        let needle = format!("{{\"height\":{height},");
+        let mut latest = None;
+        for line in self.read_lines()? {
+            let l = line?;
+            if !l.starts_with(&needle) {
+                continue;
+            }
+            if let Ok(rec) = serde_json::from_str::<Record>(&l) {
+                latest = Some(rec.header_hex);
+            }
+        }
+        Ok(latest)
+    }
+
+    /// Drops all records, leaving an empty file.
+    pub(crate) fn truncate(&self) -> io::Result<()> {
+        let mut writer = self.lock_writer();
+        writer.file.set_len(0)?;
+        writer.last = None;
         Ok(())
     }
 
+    /// Number of stored records, counted without parsing them.
+    pub(crate) fn len(&self) -> io::Result<usize> {
+        let mut n = 0;
+        for line in self.read_lines()? {
+            if !line?.trim().is_empty() {
+                n += 1;
+            }
+        }
+        Ok(n)
+    }
+
     fn read_lines(&self) -> io::Result<impl Iterator<Item = io::Result<String>>> {
         let f = File::open(&self.path)?;
         Ok(BufReader::new(f).lines())
@@ -49,10 +177,27 @@ impl FileStore {
 
 impl Store for FileStore {
     fn put(&self, height: u32, header_hex: &str) -> io::Result<()> {
-        self.append_record(&Record {
+        // Held across the check so two `put`s of the same header cannot both append it.
+        let mut writer = self.lock_writer();
+        let stored = match &writer.last {
+            // Past the last record written: syncing forward, nothing to compare against.
+            Some((last, _)) if height > *last => None,
+            Some((last, hex)) if height == *last => Some(hex.clone()),
+            // Only a retry or a reorg rewrites an earlier height; those still scan the file.
+            Some(_) => self.written_at(height)?,
+            None => None,
+        };
+        if stored.as_deref() == Some(header_hex) {
+            return Ok(());
+        }
+        let rec = Record {
             height,
             header_hex: header_hex.to_string(),
-        })
+            hash_hex: header_hash_hex(header_hex),
+        };
+        Self::append_record(&mut writer.file, &rec)?;
+        writer.last = Some((height, rec.header_hex));
+        Ok(())
     }
 
     fn get(&self, height: u32) -> io::Result<Option<String>> {
@@ -71,6 +216,18 @@ impl Store for FileStore {
         Ok(found)
     }
 
+    /// Stops at the first record for `height` and never materializes its `header_hex`.
+    fn contains(&self, height: u32) -> io::Result<bool> {
+        for line in self.read_lines()? {
+            if let Ok(rec) = serde_json::from_str::<RecordHeight>(&line?)
+                && rec.height == height
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     fn tip(&self) -> io::Result<Option<u32>> {
         let mut tip: Option<u32> = None;
         for line in self.read_lines()? {
@@ -86,19 +243,59 @@ impl Store for FileStore {
     }
 
     fn last_n(&self, n: usize) -> io::Result<Vec<(u32, String)>> {
-        let mut recs: Vec<(u32, String)> = Vec::new();
-        for line in self.read_lines()? {
-            let l = line?;
-            if l.trim().is_empty() {
-                continue;
-            }
-            if let Ok(rec) = serde_json::from_str::<Record>(&l) {
-                recs.push((rec.height, rec.header_hex));
+        let recs = self.latest_records()?;
+        let skip = recs.len().saturating_sub(n);
+        Ok(recs.into_iter().skip(skip).collect())
+    }
+
+    /// Indexes the byte offset of each height's latest line, then reads the records back one at
+    /// a time in height order; only the index is held in memory, never the headers.
+    fn iter_headers(&self) -> io::Result<HeaderIter> {
+        let offsets = self.latest_offsets()?;
+        Ok(Box::new(Headers {
+            reader: BufReader::new(File::open(&self.path)?),
+            pos: 0,
+            offsets: offsets.into_iter(),
+        }))
+    }
+
+    fn get_by_hash(&self, hash_hex: &str) -> io::Result<Option<(u32, String)>> {
+        for (height, rec) in self.latest_full_records()? {
+            let stored = rec.hash_hex.or_else(|| header_hash_hex(&rec.header_hex));
+            if stored.is_some_and(|h| h.eq_ignore_ascii_case(hash_hex)) {
+                return Ok(Some((height, rec.header_hex)));
             }
         }
-        if recs.len() > n {
-            recs.drain(0..(recs.len() - n));
+        Ok(None)
+    }
+}
+
+/// Iterator behind [`FileStore::iter_headers`], reading each record at its indexed offset.
+struct Headers {
+    reader: BufReader<File>,
+    /// Offset the reader is positioned at, so records laid out in height order need no seek.
+    pos: u64,
+    offsets: btree_map::IntoIter<u32, u64>,
+}
+
+impl Headers {
+    fn read_at(&mut self, offset: u64) -> io::Result<String> {
+        if offset != self.pos {
+            self.reader.seek(SeekFrom::Start(offset))?;
         }
-        Ok(recs)
+        let mut line = String::new();
+        self.pos = offset + self.reader.read_line(&mut line)? as u64;
+        let rec: Record = serde_json::from_str(&line)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(rec.header_hex)
+    }
+}
+
+impl Iterator for Headers {
+    type Item = io::Result<(u32, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (height, offset) = self.offsets.next()?;
+        Some(self.read_at(offset).map(|header_hex| (height, header_hex)))
     }
 }
@@ -0,0 +1,21 @@
+//! Copying every stored header from one backend to another.
+use std::io;
+
+use super::Store;
+
+/// Copies every record of `src` into `dst` in ascending height order and returns how many
+/// were copied.
+///
+/// Records are streamed through [`Store::iter_headers`] one at a time. Both backends stream too:
+/// they index where each height's latest record lives and read records back as they are
+/// yielded, so neither `migrate` nor the source holds the whole store in memory.
+/// `put` is idempotent, so rerunning an interrupted migration into the same `dst` is safe.
+pub fn migrate(src: &dyn Store, dst: &dyn Store) -> io::Result<u32> {
+    let mut migrated = 0;
+    for rec in src.iter_headers()? {
+        let (height, header_hex) = rec?;
+        dst.put(height, &header_hex)?;
+        migrated += 1;
+    }
+    Ok(migrated)
+}
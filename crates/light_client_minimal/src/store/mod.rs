@@ -1,14 +1,65 @@
 //! Simple persistence layer storing headers as hex-encoded bytes in a JSONL file.
 //!
-//! Each line is a JSON object: `{ "height": u32, "header_hex": String }`.
+//! Each line is a JSON object: `{ "height": u32, "header_hex": String, "hash_hex": String }`,
+//! where `hash_hex` may be absent in records written by older versions.
+//! `put` skips records identical to the stored one, so retries leave no duplicates.
 //! `tip()` returns the last seen height; `get(height)` scans the file for the last record.
+//! `last_n` and `iter_headers` likewise see only the latest record per height, in height order.
+//! [`compressed::CompressedFileStore`] offers the same interface over zstd-compressed frames
+//! for archival syncs.
 use std::io;
 
+use crate::codec::decode_header;
+
+/// Boxed iterator over stored `(height, header_hex)` records.
+pub type HeaderIter = Box<dyn Iterator<Item = io::Result<(u32, String)>>>;
+
 pub trait Store {
+    /// Records `header_hex` at `height`.
+    ///
+    /// Idempotent: re-putting the header already stored at `height` (e.g. when a sync step is
+    /// retried) writes nothing. A different header for a stored height logically replaces it,
+    /// as after a reorg.
     fn put(&self, height: u32, header_hex: &str) -> io::Result<()>;
     fn get(&self, height: u32) -> io::Result<Option<String>>;
+
+    /// Whether a header is stored at `height`.
+    ///
+    /// The default fetches it with [`Store::get`]; implementations override it to test presence
+    /// without reading the header itself.
+    fn contains(&self, height: u32) -> io::Result<bool> {
+        self.get(height).map(|found| found.is_some())
+    }
     fn tip(&self) -> io::Result<Option<u32>>;
     fn last_n(&self, n: usize) -> io::Result<Vec<(u32, String)>>;
+    /// Walks every stored `(height, header_hex)` record in ascending height order.
+    fn iter_headers(&self) -> io::Result<HeaderIter>;
+
+    /// Finds the stored header whose RPC-style (byte-reversed) hash hex is `hash_hex`.
+    ///
+    /// Only the latest record per height is considered, so blocks replaced by a reorg
+    /// are not returned. The default implementation re-hashes every stored header.
+    fn get_by_hash(&self, hash_hex: &str) -> io::Result<Option<(u32, String)>> {
+        for rec in self.iter_headers()? {
+            let (height, header_hex) = rec?;
+            if header_hash_hex(&header_hex).is_some_and(|h| h.eq_ignore_ascii_case(hash_hex)) {
+                return Ok(Some((height, header_hex)));
+            }
+        }
+        Ok(None)
+    }
 }
 
+/// RPC-style hash of a hex-encoded header, or `None` if it does not decode.
+fn header_hash_hex(header_hex: &str) -> Option<String> {
+    let header = decode_header(header_hex).ok()?;
+    Some(header.hash().to_string())
+}
+
+pub mod compressed;
+mod export;
 pub mod file;
+mod migrate;
+
+pub use export::export_json;
+pub use migrate::migrate;
@@ -1,10 +1,30 @@
 use core::fmt;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::Relaxed;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::net::rpc::{RpcClient, RpcError};
+use crate::archive::ArchiveStore;
+use crate::codec::{CodecError, decode_header, encode_header};
+use crate::net::HeaderSource;
+use crate::net::notify::TipNotifier;
+use crate::net::rpc::RpcError;
+use crate::state::{SyncState, SyncStateError};
 use crate::store::Store;
-use tracing::{debug, info};
-use zcash_crypto::{DifficultyContext, verify_pow_in_cairo, verify_pow_with_context};
-use zcash_primitives::block::BlockHeader;
+use async_stream::try_stream;
+use futures::future::try_join_all;
+use futures::{Stream, StreamExt};
+use tracing::{Instrument, debug, debug_span, info, warn};
+use zcash_crypto::difficulty::context::expected_nbits;
+use zcash_crypto::{
+    DifficultyContext, Network, PowError, VerifyFlags, cairo_proof_path, check_pow_flags,
+    verify_pow, verify_pow_in_cairo, verify_pow_with_context,
+};
+use zcash_primitives::block::{BlockHash, BlockHeader};
 
 /// Errors that can occur when verifying a header fetched via RPC.
 #[derive(Debug)]
@@ -15,6 +35,18 @@ pub enum VerifyHeaderError {
     InsufficientContext {
         height: u32,
     },
+    /// A header read back from the store failed verification.
+    Stored {
+        height: u32,
+        source: VerifyPowError,
+    },
+    /// The store holds no header that the node's chain also has.
+    NoCommonAncestor,
+    /// The node disconnected the last header synced, at `height`, from its best chain; resume
+    /// from [`find_fork_point`].
+    Disconnected {
+        height: u32,
+    },
 }
 
 impl fmt::Display for VerifyHeaderError {
@@ -26,6 +58,17 @@ impl fmt::Display for VerifyHeaderError {
                 f,
                 "insufficient context to verify difficulty at height {height}"
             ),
+            VerifyHeaderError::Stored { height, source } => write!(
+                f,
+                "stored header at height {height} failed verification: {source:?}"
+            ),
+            VerifyHeaderError::NoCommonAncestor => {
+                write!(f, "stored headers share no ancestor with the node's chain")
+            }
+            VerifyHeaderError::Disconnected { height } => write!(
+                f,
+                "block {height} was disconnected from the node's best chain"
+            ),
         }
     }
 }
@@ -43,162 +86,943 @@ impl From<zcash_crypto::PowError> for VerifyPowError {
 }
 
 /// Fetches the header at `height`, builds minimal difficulty context, and verifies.
-pub async fn verify_header(rpc: &RpcClient, height: u32) -> Result<(), VerifyHeaderError> {
-    const CONTEXT_BLOCKS: u32 = 28;
-    if height < CONTEXT_BLOCKS {
-        return Err(VerifyHeaderError::InsufficientContext { height });
-    }
+///
+/// This builds a one-shot context; use [`verify_header_with_ctx`] to verify
+/// successive heights without refetching the context each time.
+pub async fn verify_header<R: HeaderSource>(rpc: &R, height: u32) -> Result<(), VerifyHeaderError> {
+    let network = rpc.network().await.map_err(VerifyHeaderError::Rpc)?;
+    let mut ctx = DifficultyContext::with_network(height.saturating_sub(1), network);
+    verify_header_with_ctx(rpc, height, &mut ctx).await
+}
+
+/// Fetches and verifies the header at `height` against an existing context.
+///
+/// The context is (re)built from `rpc` unless it already holds a full window ending at
+/// `height - 1`, e.g. when it is fresh or was last used for another height. On success the
+/// context is advanced to `height`, so verifying consecutive heights reuses it without further
+/// fetches.
+pub async fn verify_header_with_ctx<R: HeaderSource>(
+    rpc: &R,
+    height: u32,
+    ctx: &mut DifficultyContext,
+) -> Result<(), VerifyHeaderError> {
+    ensure_ctx_ready(rpc, height, ctx).await?;
 
     let header = rpc
-        .get_block_header_by_height(height)
+        .header_by_height(height)
         .await
         .map_err(VerifyHeaderError::Rpc)?;
 
-    let start = height - CONTEXT_BLOCKS;
-    let mut ctx = DifficultyContext::new(height - 1);
+    verify_pow_with_context(&header, height, ctx)
+        .map_err(|e| VerifyHeaderError::Pow(VerifyPowError::from(e)))
+}
+
+/// Rebuilds a context that is not [ready](DifficultyContext::is_ready), or not at `height - 1`,
+/// from the headers preceding `height`, fetched from `rpc`.
+async fn ensure_ctx_ready<R: HeaderSource>(
+    rpc: &R,
+    height: u32,
+    ctx: &mut DifficultyContext,
+) -> Result<(), VerifyHeaderError> {
+    let required = DifficultyContext::REQUIRED_WINDOW as u32;
+    if height < required {
+        return Err(VerifyHeaderError::InsufficientContext { height });
+    }
+
+    if !ctx.is_ready() || ctx.tip_height != height - 1 {
+        *ctx = DifficultyContext::with_network(height - 1, ctx.network());
+        for (h, prev_header) in fetch_headers(rpc, height - required..height).await? {
+            ctx.push_header(h, prev_header.time, prev_header.bits);
+        }
+    }
+    Ok(())
+}
+
+/// Maximum number of context headers requested from the node at once.
+pub const CONTEXT_FETCH_CONCURRENCY: usize = 8;
 
-    for h in start..height {
-        let prev_header = rpc
-            .get_block_header_by_height(h)
+/// Fetches the headers at `heights` concurrently, at most [`CONTEXT_FETCH_CONCURRENCY`] at a
+/// time, and returns them in the order given.
+async fn fetch_headers<R: HeaderSource>(
+    rpc: &R,
+    heights: impl IntoIterator<Item = u32>,
+) -> Result<Vec<(u32, BlockHeader)>, VerifyHeaderError> {
+    let heights: Vec<u32> = heights.into_iter().collect();
+    let mut headers = Vec::with_capacity(heights.len());
+    for batch in heights.chunks(CONTEXT_FETCH_CONCURRENCY) {
+        let fetched = try_join_all(batch.iter().map(|&h| rpc.header_by_height(h)))
             .await
             .map_err(VerifyHeaderError::Rpc)?;
-        ctx.push_header(h, prev_header.time, prev_header.bits);
+        headers.extend(batch.iter().copied().zip(fetched));
     }
+    Ok(headers)
+}
 
-    verify_pow_with_context(&header, height, &mut ctx)
-        .map_err(|e| VerifyHeaderError::Pow(VerifyPowError::from(e)))
+/// Outcome of checking a single header, as printed by the `verify` subcommand.
+#[derive(Debug)]
+pub struct HeaderReport {
+    pub height: Option<u32>,
+    pub hash: BlockHash,
+    /// `nBits` carried by the header.
+    pub header_bits: u32,
+    /// `nBits` computed from the preceding headers, when context was available.
+    pub expected_bits: Option<u32>,
+    pub result: Result<(), VerifyHeaderError>,
 }
 
-fn header_to_hex(header: &BlockHeader) -> Result<String, VerifyHeaderError> {
-    let mut buf = Vec::new();
-    // BlockHeader::write is expected to be available in zcash_primitives.
-    header
-        .write(&mut buf)
-        .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("serialize header: {e}"))))?;
-    Ok(hex::encode(buf))
+impl HeaderReport {
+    pub fn passed(&self) -> bool {
+        self.result.is_ok()
+    }
 }
 
-fn header_from_hex(s: &str) -> Result<BlockHeader, VerifyHeaderError> {
-    let bytes = hex::decode(s)
-        .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("hex decode: {e}"))))?;
-    BlockHeader::read(&bytes[..])
-        .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("decode header: {e}"))))
+impl fmt::Display for HeaderReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.height {
+            Some(h) => writeln!(f, "Block {h} ({})", self.hash)?,
+            None => writeln!(f, "Block {}", self.hash)?,
+        }
+        writeln!(f, "  nBits (header):   {:#010x}", self.header_bits)?;
+        match self.expected_bits {
+            Some(bits) => writeln!(f, "  nBits (expected): {bits:#010x}")?,
+            None => writeln!(f, "  nBits (expected): n/a (no context)")?,
+        }
+        match &self.result {
+            Ok(()) => write!(f, "  result: PASS"),
+            Err(e) => write!(f, "  result: FAIL ({e})"),
+        }
+    }
 }
 
-async fn build_ctx_from_store_or_rpc<S: Store>(
-    rpc: &RpcClient,
-    store: &S,
-    effective_start: u32,
+/// Compact, height-less description of a header for log lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderSummary {
+    pub hash: BlockHash,
+    pub prev_hash: BlockHash,
+    pub time: u32,
+    pub bits: u32,
+    /// Length of the Equihash solution in bytes (1344 for mainnet parameters).
+    pub solution_len: usize,
+}
+
+impl HeaderSummary {
+    /// Hash in RPC (byte-reversed) hex, as block explorers show it.
+    pub fn hash_hex(&self) -> String {
+        self.hash.to_string()
+    }
+}
+
+impl From<&BlockHeader> for HeaderSummary {
+    fn from(header: &BlockHeader) -> Self {
+        HeaderSummary {
+            hash: header.hash(),
+            prev_hash: header.prev_block,
+            time: header.time,
+            bits: header.bits,
+            solution_len: header.solution.len(),
+        }
+    }
+}
+
+impl fmt::Display for HeaderSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "hash={} prev={} time={} bits={:#010x} solution={}B",
+            self.hash, self.prev_hash, self.time, self.bits, self.solution_len
+        )
+    }
+}
+
+/// Fetches the header at `height` with its difficulty context and reports on it.
+///
+/// RPC failures are returned as errors; verification failures are recorded in the
+/// report's `result`.
+pub async fn report_header_at<R: HeaderSource>(
+    rpc: &R,
+    height: u32,
+) -> Result<HeaderReport, VerifyHeaderError> {
+    let mut ctx = ctx_before(rpc, height).await?;
+    let header = rpc
+        .header_by_height(height)
+        .await
+        .map_err(VerifyHeaderError::Rpc)?;
+    Ok(report_in_context(&header, height, &mut ctx))
+}
+
+/// Decodes a raw serialized header and reports on it as the header at `height`, with the
+/// difficulty context fetched from the node.
+///
+/// Unlike [`report_header_at`] the header itself does not come from the node, so this checks
+/// a candidate (or tampered) header against the node's chain up to `height - 1`.
+pub async fn report_header_bytes_at<R: HeaderSource>(
+    rpc: &R,
+    raw: &[u8],
+    height: u32,
+) -> Result<HeaderReport, VerifyHeaderError> {
+    let header = decode_report_header(raw)?;
+    let mut ctx = ctx_before(rpc, height).await?;
+    Ok(report_in_context(&header, height, &mut ctx))
+}
+
+async fn ctx_before<R: HeaderSource>(
+    rpc: &R,
+    height: u32,
 ) -> Result<DifficultyContext, VerifyHeaderError> {
-    const CONTEXT_BLOCKS: usize = 28;
-    let mut ctx = DifficultyContext::new(effective_start - 1);
-
-    // Try to load as much context as possible from the store.
-    let stored = store
-        .last_n(CONTEXT_BLOCKS)
-        .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("store read: {e}"))))?;
-    if !stored.is_empty() {
-        // Ensure ascending order by height.
-        let mut stored_sorted = stored.clone();
-        stored_sorted.sort_by_key(|(h, _)| *h);
-        let m = stored_sorted.len();
-        // If we have insufficient context, fetch missing older headers via RPC first.
-        if m < CONTEXT_BLOCKS {
-            let need = CONTEXT_BLOCKS - m;
-            let earliest = stored_sorted.first().map(|(h, _)| *h).unwrap();
-            let start = earliest.saturating_sub(need as u32);
-            for h in start..earliest {
-                let hdr = rpc
-                    .get_block_header_by_height(h)
-                    .await
-                    .map_err(VerifyHeaderError::Rpc)?;
-                ctx.push_header(h, hdr.time, hdr.bits);
-            }
+    let network = rpc.network().await.map_err(VerifyHeaderError::Rpc)?;
+    let mut ctx = DifficultyContext::with_network(height.saturating_sub(1), network);
+    ensure_ctx_ready(rpc, height, &mut ctx).await?;
+    Ok(ctx)
+}
+
+fn decode_report_header(raw: &[u8]) -> Result<BlockHeader, VerifyHeaderError> {
+    BlockHeader::read(raw)
+        .map_err(|e| VerifyHeaderError::Pow(VerifyPowError::from(PowError::Decode(e.to_string()))))
+}
+
+fn report_in_context(
+    header: &BlockHeader,
+    height: u32,
+    ctx: &mut DifficultyContext,
+) -> HeaderReport {
+    let expected_bits = expected_nbits(ctx, height).ok();
+    let result = verify_pow_with_context(header, height, ctx)
+        .map_err(|e| VerifyHeaderError::Pow(VerifyPowError::from(e)));
+
+    HeaderReport {
+        height: Some(height),
+        hash: header.hash(),
+        header_bits: header.bits,
+        expected_bits,
+        result,
+    }
+}
+
+/// Decodes a raw serialized header and reports on its context-free PoW checks.
+pub fn report_header_bytes(raw: &[u8]) -> Result<HeaderReport, VerifyHeaderError> {
+    let header = decode_report_header(raw)?;
+
+    let result = verify_pow(&header).map_err(|e| VerifyHeaderError::Pow(VerifyPowError::from(e)));
+
+    Ok(HeaderReport {
+        height: None,
+        hash: header.hash(),
+        header_bits: header.bits,
+        expected_bits: None,
+        result,
+    })
+}
+
+/// Re-verifies every header in `store` offline, assuming mainnet.
+///
+/// See [`reverify_store_for_network`].
+pub fn reverify_store<S: Store>(store: &S) -> Result<usize, VerifyHeaderError> {
+    reverify_store_for_network(store, Network::Mainnet)
+}
+
+/// Re-verifies every header in `store` in height order, without contacting a node.
+///
+/// The difficulty context is rebuilt from the stored headers themselves: until it holds a
+/// full window (and again after any gap in heights) headers get the context-free checks of
+/// [`verify_pow`], afterwards the full [`verify_pow_with_context`]. Returns the number of
+/// headers checked, or [`VerifyHeaderError::Stored`] for the first one that fails.
+pub fn reverify_store_for_network<S: Store>(
+    store: &S,
+    network: Network,
+) -> Result<usize, VerifyHeaderError> {
+    let mut ctx = DifficultyContext::with_network(0, network);
+    let mut checked = 0;
+    for rec in store.iter_headers().map_err(store_err)? {
+        let (height, header_hex) = rec.map_err(store_err)?;
+        let stored_err = |e: PowError| VerifyHeaderError::Stored {
+            height,
+            source: VerifyPowError::from(e),
+        };
+
+        let header =
+            decode_header(&header_hex).map_err(|e| stored_err(PowError::Decode(e.to_string())))?;
+
+        if ctx.is_empty() || ctx.tip_height.checked_add(1) != Some(height) {
+            ctx = DifficultyContext::with_network(height.saturating_sub(1), network);
         }
-        // Now append the stored headers in ascending order.
-        for (h, hex) in &stored_sorted {
-            let hdr = header_from_hex(hex)?;
-            ctx.push_header(*h, hdr.time, hdr.bits);
+        if ctx.is_ready() {
+            verify_pow_with_context(&header, height, &mut ctx).map_err(stored_err)?;
+        } else {
+            verify_pow(&header).map_err(stored_err)?;
+            ctx.push_header(height, header.time, header.bits);
         }
-        return Ok(ctx);
+        checked += 1;
     }
+    Ok(checked)
+}
 
-    // No stored context available; build entirely from RPC.
-    let context_start = effective_start - CONTEXT_BLOCKS as u32;
-    for h in context_start..effective_start {
-        let header = rpc
-            .get_block_header_by_height(h)
-            .await
-            .map_err(VerifyHeaderError::Rpc)?;
-        ctx.push_header(h, header.time, header.bits);
+/// Runs the context-free [`verify_pow`] on every stored header in `start..=end`, spread over
+/// `workers` threads (at least one), and returns each height's outcome; an empty map when
+/// `start > end`.
+///
+/// Unlike [`reverify_store`] this does not stop at the first failure, and checks no
+/// difficulty context: each header is verified on its own, in no particular order. Run
+/// [`reverify_store`] afterwards for the sequential contextual checks.
+///
+/// Workers read their headers one height at a time with [`Store::get`], so only the headers
+/// being verified are held in memory, whatever the size of the store or the range.
+pub fn reverify_range_parallel<S: Store + Sync>(
+    store: &S,
+    start: u32,
+    end: u32,
+    workers: usize,
+) -> Result<BTreeMap<u32, Result<(), VerifyPowError>>, VerifyHeaderError> {
+    if start > end {
+        return Ok(BTreeMap::new());
     }
-    Ok(ctx)
+    let verify = |header_hex: &str| -> Result<(), VerifyPowError> {
+        let header = decode_header(header_hex).map_err(|e| PowError::Decode(e.to_string()))?;
+        Ok(verify_pow(&header)?)
+    };
+    let heights = u64::from(end - start) + 1;
+    // Offset from `start` of the next height to check; `u64` so it cannot wrap past `end`.
+    let next = AtomicU64::new(0);
+    let pool_size = workers.clamp(1, usize::try_from(heights).unwrap_or(usize::MAX));
+    let done = thread::scope(|scope| {
+        let pool: Vec<_> = (0..pool_size)
+            .map(|_| {
+                scope.spawn(|| -> io::Result<Vec<_>> {
+                    let mut done = Vec::new();
+                    loop {
+                        let offset = next.fetch_add(1, Relaxed);
+                        if offset >= heights {
+                            return Ok(done);
+                        }
+                        let height = start + offset as u32;
+                        if let Some(header_hex) = store.get(height)? {
+                            done.push((height, verify(&header_hex)));
+                        }
+                    }
+                })
+            })
+            .collect();
+        pool.into_iter()
+            .map(|worker| worker.join().expect("verify worker panicked"))
+            .collect::<io::Result<Vec<_>>>()
+    });
+    Ok(done.map_err(store_err)?.into_iter().flatten().collect())
 }
 
-/// Continuously verifies headers starting at `start_height`, persisting each verified header.
-pub async fn sync_chain<S: Store>(
-    rpc: &RpcClient,
+/// Highest height at which `store` and `source` hold the same header, i.e. where to resume
+/// after the store's tip was reorged away.
+///
+/// Probes back from the store's tip at doubling distances (tip, tip-1, tip-2, tip-4, ...) until
+/// a height matches, then binary-searches between it and the closest mismatch, so a fork `d`
+/// blocks deep costs `O(log d)` lookups. A height missing on either side counts as a mismatch.
+/// Fails with [`VerifyHeaderError::NoCommonAncestor`] if the store is empty or even its lowest
+/// header differs.
+pub async fn find_fork_point<R: HeaderSource, S: Store>(
+    source: &R,
     store: &S,
-    start_height: u32,
+) -> Result<u32, VerifyHeaderError> {
+    let tip = store.tip().map_err(store_err)?;
+    let first = store.iter_headers().map_err(store_err)?.next();
+    let (Some(tip), Some(first)) = (tip, first) else {
+        return Err(VerifyHeaderError::NoCommonAncestor);
+    };
+    let (lowest, _) = first.map_err(store_err)?;
+
+    if hashes_agree(source, store, tip).await? {
+        return Ok(tip);
+    }
+    let mut mismatch = tip;
+    let mut step = 1u32;
+    let mut matched = loop {
+        if mismatch <= lowest {
+            return Err(VerifyHeaderError::NoCommonAncestor);
+        }
+        let probe = tip.saturating_sub(step).max(lowest);
+        if hashes_agree(source, store, probe).await? {
+            break probe;
+        }
+        mismatch = probe;
+        step = step.saturating_mul(2);
+    };
+
+    while mismatch - matched > 1 {
+        let mid = matched + (mismatch - matched) / 2;
+        if hashes_agree(source, store, mid).await? {
+            matched = mid;
+        } else {
+            mismatch = mid;
+        }
+    }
+    Ok(matched)
+}
+
+async fn hashes_agree<R: HeaderSource, S: Store>(
+    source: &R,
+    store: &S,
+    height: u32,
+) -> Result<bool, VerifyHeaderError> {
+    let stored = store.get(height).map_err(store_err)?;
+    let Some(header_hex) = stored else {
+        return Ok(false);
+    };
+    let stored_hash = decode_header(&header_hex).map_err(codec_err)?.hash();
+    match source.block_hash(height).await {
+        Ok(hash) => Ok(hash == stored_hash),
+        Err(RpcError::HeightOutOfRange(_)) => Ok(false),
+        Err(e) => Err(VerifyHeaderError::Rpc(e)),
+    }
+}
+
+/// Header (de)serialization failures are local, so they surface as client errors.
+fn codec_err(e: CodecError) -> VerifyHeaderError {
+    VerifyHeaderError::Rpc(RpcError::Client(e.to_string()))
+}
+
+/// Failures reading the local store, surfaced as client errors like [`codec_err`].
+fn store_err(e: io::Error) -> VerifyHeaderError {
+    VerifyHeaderError::Rpc(RpcError::Client(format!("store read: {e}")))
+}
+
+/// The header stored at `height`, if there is one that needs no further work: with `prove`, its
+/// proof file must exist as well.
+fn stored_header<S: Store>(
+    store: &S,
+    height: u32,
     prove: bool,
-) -> Result<(), VerifyHeaderError> {
-    const CONTEXT_BLOCKS: u32 = 28;
-    if start_height < CONTEXT_BLOCKS {
-        return Err(VerifyHeaderError::InsufficientContext {
-            height: start_height,
-        });
+) -> Result<Option<BlockHeader>, VerifyHeaderError> {
+    if prove && !cairo_proof_path(height).exists() {
+        return Ok(None);
     }
+    let stored = store.get(height).map_err(store_err)?;
+    stored
+        .map(|hex| decode_header(&hex).map_err(codec_err))
+        .transpose()
+}
 
-    // Determine effective start height from persistence, if available.
-    let effective_start = match store
-        .tip()
-        .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("store tip: {e}"))))?
-    {
-        Some(tip) => match tip.checked_add(1) {
-            Some(h) => h,
-            None => return Ok(()),
-        },
-        None => start_height,
+/// The context saved at `path`, moved to `effective_start - 1`: rewound if the state is ahead
+/// of it, or rolled forward over the store's newest headers if behind.
+///
+/// `None` (and the caller rebuilds the context) if there is no usable state: no file, one that
+/// does not load, for another network, whose tip the store does not hold (under the saved hash),
+/// or too far from `effective_start - 1` to be moved there.
+fn restore_ctx<S: Store>(
+    path: &Path,
+    store: &S,
+    effective_start: u32,
+    network: Network,
+) -> Result<Option<DifficultyContext>, VerifyHeaderError> {
+    let state = match SyncState::load(path) {
+        Ok(state) => state,
+        Err(SyncStateError::Io(e)) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            warn!("Ignoring sync state {}: {e}", path.display());
+            return Ok(None);
+        }
     };
+    let mut ctx = state.context;
+    let target = effective_start - 1;
+    if ctx.network() != network {
+        warn!(
+            "Ignoring sync state {}: saved for {:?}",
+            path.display(),
+            ctx.network()
+        );
+        return Ok(None);
+    }
+    let stored_tip = stored_header(store, state.tip_height, false)?;
+    if stored_tip.is_none_or(|header| header.hash() != state.tip_hash) {
+        warn!(
+            "Ignoring sync state {}: the store does not hold its tip {} at {}",
+            path.display(),
+            state.tip_hash,
+            state.tip_height
+        );
+        return Ok(None);
+    }
 
-    // Build initial context using persisted headers where possible, filling gaps via RPC.
-    let mut ctx = build_ctx_from_store_or_rpc(rpc, store, effective_start).await?;
+    if ctx.tip_height >= target {
+        if ctx.rewind_to(target).is_err() {
+            info!(
+                "Sync state at {} cannot be rewound to {target}",
+                ctx.tip_height
+            );
+            return Ok(None);
+        }
+    } else {
+        let missing = (target - ctx.tip_height) as usize;
+        // Rolling forward over more headers than a context retains costs more than a rebuild.
+        if missing > DifficultyContext::RETAINED {
+            info!(
+                "Sync state at {} is too far behind {target}",
+                ctx.tip_height
+            );
+            return Ok(None);
+        }
+        let newest = store.last_n(missing).map_err(store_err)?;
+        let follows_state = newest.len() == missing
+            && (ctx.tip_height + 1..)
+                .zip(&newest)
+                .all(|(h, (stored, _))| h == *stored);
+        if !follows_state {
+            info!(
+                "Sync state at {} is too far behind {target}",
+                ctx.tip_height
+            );
+            return Ok(None);
+        }
+        for (height, header_hex) in newest {
+            let header = decode_header(&header_hex).map_err(codec_err)?;
+            ctx.push_header(height, header.time, header.bits);
+        }
+    }
 
-    let mut height = effective_start;
+    if !ctx.is_ready() {
+        return Ok(None);
+    }
+    info!("Difficulty context restored from {}", path.display());
+    Ok(Some(ctx))
+}
 
-    loop {
-        info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        info!("Block {height}");
-        info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        let header = rpc
-            .get_block_header_by_height(height)
-            .await
-            .map_err(VerifyHeaderError::Rpc)?;
+/// Saves `ctx`, whose tip header hashes to `tip_hash`, as the [`SyncState`] at `path`. A failure
+/// only costs the next startup a context rebuild, so it is logged rather than returned.
+fn save_state(path: &Path, ctx: &DifficultyContext, tip_hash: BlockHash) {
+    let state = SyncState {
+        tip_height: ctx.tip_height,
+        tip_hash,
+        context: ctx.clone(),
+    };
+    match state.save(path) {
+        Ok(()) => debug!(
+            "Saved sync state at {} to {}",
+            ctx.tip_height,
+            path.display()
+        ),
+        Err(e) => warn!("Cannot save sync state to {}: {e}", path.display()),
+    }
+}
+
+/// Difficulty context for verifying `effective_start`, i.e. the window of headers just below it.
+///
+/// The store is the source of truth: a stored header at a window height is used as is, and only
+/// heights it lacks are fetched from `rpc`. Records above `effective_start - 1` (left over from a
+/// reorg, or from a run that crashed mid-write) are ignored.
+async fn build_ctx_from_store_or_rpc<R: HeaderSource, S: Store>(
+    rpc: &R,
+    store: &S,
+    effective_start: u32,
+    network: Network,
+) -> Result<DifficultyContext, VerifyHeaderError> {
+    let mut ctx = DifficultyContext::with_network(effective_start - 1, network);
+    let window = effective_start - DifficultyContext::REQUIRED_WINDOW as u32..effective_start;
+
+    let stored: BTreeMap<u32, String> = store
+        .last_n(DifficultyContext::REQUIRED_WINDOW)
+        .map_err(store_err)?
+        .into_iter()
+        .filter(|(h, _)| window.contains(h))
+        .collect();
+    let missing: Vec<u32> = window.clone().filter(|h| !stored.contains_key(h)).collect();
+    let mut fetched = fetch_headers(rpc, missing).await?.into_iter().peekable();
+
+    for height in window {
+        let header = match stored.get(&height) {
+            Some(hex) => decode_header(hex).map_err(codec_err)?,
+            None => match fetched.next_if(|(h, _)| *h == height) {
+                Some((_, header)) => header,
+                None => unreachable!("height {height} was fetched"),
+            },
+        };
+        ctx.push_header(height, header.time, header.bits);
+    }
+    Ok(ctx)
+}
+
+/// Time spent in each verification phase of one header, as measured by
+/// [`verify_header_timed`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifyTimings {
+    pub equihash: Duration,
+    pub filter: Duration,
+    /// Contextual difficulty and median-time-past.
+    pub context: Duration,
+    /// Cairo execution, plus proving when enabled.
+    pub cairo: Duration,
+}
+
+impl fmt::Display for VerifyTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "equihash={:.1?} filter={:.1?} context={:.1?} cairo={:.1?}",
+            self.equihash, self.filter, self.context, self.cairo
+        )
+    }
+}
+
+/// Runs the checks of [`verify_pow_with_context`] one phase at a time, then the Cairo step, and
+/// reports how long each took.
+///
+/// Fails on the first failing phase, in the same order as [`verify_pow_with_context`]. Unlike it,
+/// `ctx` is left untouched: push the header once it has been committed, as the sync loop does
+/// after the header is persisted. The Rust checks run in a `verify_rust` span and the Cairo step
+/// in `verify_cairo`, both at debug level and tagged with `height`.
+pub fn verify_header_timed(
+    header: &BlockHeader,
+    height: u32,
+    ctx: &DifficultyContext,
+    prove: bool,
+) -> Result<VerifyTimings, VerifyHeaderError> {
+    let (equihash, filter, context) = debug_span!("verify_rust", height).in_scope(|| {
+        let equihash = timed(|| check_pow_flags(header, height, None, VerifyFlags::EQUIHASH))?;
+        let filter = timed(|| check_pow_flags(header, height, Some(ctx), VerifyFlags::FILTER))?;
+        let context = timed(|| {
+            let flags = VerifyFlags::CONTEXT | VerifyFlags::TIMESTAMP;
+            check_pow_flags(header, height, Some(ctx), flags)
+        })?;
+        Ok::<_, VerifyHeaderError>((equihash, filter, context))
+    })?;
+    debug!("Rust PoW verification passed");
+
+    let cairo = debug_span!("verify_cairo", height, prove)
+        .in_scope(|| timed(|| verify_pow_in_cairo(header, height, prove)))?;
+    debug!("Cairo PoW verification passed");
 
-        verify_pow_with_context(&header, height, &mut ctx)
-            .map_err(|e| VerifyHeaderError::Pow(VerifyPowError::from(e)))?;
-        debug!("Rust PoW verification passed");
+    Ok(VerifyTimings {
+        equihash,
+        filter,
+        context,
+        cairo,
+    })
+}
+
+fn timed(check: impl FnOnce() -> Result<(), PowError>) -> Result<Duration, VerifyHeaderError> {
+    let start = Instant::now();
+    check().map_err(|e| VerifyHeaderError::Pow(VerifyPowError::from(e)))?;
+    Ok(start.elapsed())
+}
 
-        verify_pow_in_cairo(&header, height, prove)
-            .map_err(|e| VerifyHeaderError::Pow(VerifyPowError::from(e)))?;
-        debug!("Cairo PoW verification passed");
+/// Options controlling [`sync_chain`].
+#[derive(Debug, Clone)]
+pub struct SyncOptions {
+    /// Generate STWO proofs for each verified block.
+    pub prove: bool,
+    /// Return once the node's tip is reached instead of waiting for new blocks.
+    pub exit_at_tip: bool,
+    /// Initial delay before re-polling a node that has no block at the next height yet.
+    pub poll_interval: Duration,
+    /// Upper bound for the poll delay, which doubles while the tip stays unchanged.
+    pub max_poll_interval: Duration,
+    /// Wait at the tip for this to announce a new block instead of polling. The node is still
+    /// asked again after `max_poll_interval` without a notification, in case one was missed.
+    pub tip_notifier: Option<Arc<dyn TipNotifier>>,
+    /// [`SyncState`] snapshot file. At startup a state saved from this store seeds the difficulty
+    /// context (rolled forward or back to the store's tip) instead of rebuilding it; while syncing
+    /// it is rewritten every `state_every` headers and whenever the node's tip is reached.
+    pub state: Option<PathBuf>,
+    /// Headers between two saves of `state`.
+    pub state_every: u32,
+    /// Start at `start_height` even if the store already goes past it, and pass over every block
+    /// the store holds (with `prove`, only if its proof file exists too) without fetching,
+    /// verifying or proving it again.
+    pub skip_existing: bool,
+    /// With `prove`, also record each proof against its header in this
+    /// [`ArchiveStore`](crate::archive::ArchiveStore) file.
+    pub archive: Option<PathBuf>,
+}
 
-        let header_hex = header_to_hex(&header)?;
-        store
-            .put(height, &header_hex)
-            .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("store header: {e}"))))?;
+impl Default for SyncOptions {
+    fn default() -> Self {
+        SyncOptions {
+            prove: false,
+            exit_at_tip: false,
+            poll_interval: Duration::from_secs(5),
+            max_poll_interval: Duration::from_secs(60),
+            tip_notifier: None,
+            state: None,
+            state_every: 100,
+            skip_existing: false,
+            archive: None,
+        }
+    }
+}
+
+/// Continuously verifies headers starting at `start_height`, persisting each verified header.
+///
+/// Once the node's tip is reached the loop either returns (`exit_at_tip`) or keeps polling
+/// for new blocks with an exponential backoff. Headers usually come from an
+/// [`RpcClient`](crate::net::rpc::RpcClient), but any [`HeaderSource`] will do.
+///
+/// The loop installs no signal handler: to stop it between blocks (e.g. on Ctrl-C), use
+/// [`sync_chain_until`]. Returns the height of the last header in the store, or `None` if the
+/// store is still empty.
+///
+/// Each block passes through `fetch`, `verify_rust`, `verify_cairo` and `persist` spans
+/// (debug level, tagged with `height`), so a subscriber that reports span timings can attribute
+/// per-block latency to a phase.
+pub async fn sync_chain<R: HeaderSource, S: Store>(
+    rpc: &R,
+    store: &S,
+    start_height: u32,
+    opts: &SyncOptions,
+) -> Result<Option<u32>, VerifyHeaderError> {
+    sync_chain_until(rpc, store, start_height, opts, std::future::pending()).await
+}
+
+/// [`sync_chain`], also stopping once `shutdown` completes.
+///
+/// `shutdown` is only acted on between blocks (or while fetching or waiting for the next one):
+/// a block that has been fetched is always verified and persisted first, so the store never
+/// ends part-way through a block. Returns the height of the last header in the store, or `None`
+/// if the store is still empty.
+///
+/// This is a consumer of [`sync_chain_stream`] that stores (and, with `archive`, records the
+/// proof of) each header it yields.
+pub async fn sync_chain_until<R: HeaderSource, S: Store>(
+    rpc: &R,
+    store: &S,
+    start_height: u32,
+    opts: &SyncOptions,
+    shutdown: impl Future<Output = ()>,
+) -> Result<Option<u32>, VerifyHeaderError> {
+    let prove = opts.prove;
+    let archive = opts
+        .archive
+        .as_ref()
+        .filter(|_| prove)
+        .map(ArchiveStore::new)
+        .transpose()
+        .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("open archive: {e}"))))?;
+
+    let mut last_persisted = store
+        .tip()
+        .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("store tip: {e}"))))?;
+    let mut headers = std::pin::pin!(sync_chain_stream(rpc, store, start_height, opts));
+    let mut shutdown = std::pin::pin!(shutdown);
+
+    loop {
+        // Biased so a pending shutdown wins over starting another block.
+        let next = tokio::select! {
+            biased;
+            () = &mut shutdown => {
+                match last_persisted {
+                    Some(height) => info!("Shutdown requested; stopping after block {height}"),
+                    None => info!("Shutdown requested; stopping before the first block"),
+                }
+                break;
+            }
+            next = headers.next() => next,
+        };
+        let Some((height, header)) = next.transpose()? else {
+            break;
+        };
+
+        debug_span!("persist", height).in_scope(|| {
+            let header_hex = encode_header(&header).map_err(codec_err)?;
+            store.put(height, &header_hex).map_err(|e| {
+                VerifyHeaderError::Rpc(RpcError::Client(format!("store header: {e}")))
+            })?;
+            if let Some(archive) = &archive {
+                archive
+                    .put_proof(height, &header_hex, &cairo_proof_path(height))
+                    .map_err(|e| {
+                        VerifyHeaderError::Rpc(RpcError::Client(format!("archive proof: {e}")))
+                    })?;
+            }
+            Ok(())
+        })?;
+        last_persisted = Some(height);
 
         if prove {
             info!("✓ Block {height} verified, proven and stored");
         } else {
             info!("✓ Block {height} verified and stored");
         }
+    }
 
-        height = match height.checked_add(1) {
-            Some(next) => next,
-            None => break,
+    Ok(last_persisted)
+}
+
+/// [`sync_chain_stream`] with the default [`SyncOptions`]: every header from `start` (or after
+/// the store's tip) is verified, in Rust and Cairo without proving, and yielded, following the
+/// node's tip indefinitely.
+pub fn header_stream<'a, R: HeaderSource, S: Store>(
+    source: &'a R,
+    store: &'a S,
+    start: u32,
+) -> impl Stream<Item = Result<(u32, BlockHeader), VerifyHeaderError>> + use<'a, R, S> {
+    sync_chain_stream(source, store, start, &SyncOptions::default())
+}
+
+/// Verifies headers from `start_height` on like [`sync_chain`], yielding each one with its
+/// height instead of storing it.
+///
+/// `store` is only read: sync resumes after its tip (with `skip_existing`, at `start_height` with
+/// stored blocks passed over unyielded), and its headers seed the difficulty context. What
+/// happens to a yielded header is up to the caller; the context only advances past it when the
+/// next item is requested, so the stream can be resumed later from a store the caller kept up
+/// to date.
+///
+/// Options other than `archive` apply as for [`sync_chain`]. The stream ends at the node's tip
+/// with `exit_at_tip` (otherwise it polls for new blocks indefinitely), and after the first error.
+pub fn sync_chain_stream<'a, R: HeaderSource, S: Store>(
+    rpc: &'a R,
+    store: &'a S,
+    start_height: u32,
+    opts: &SyncOptions,
+) -> impl Stream<Item = Result<(u32, BlockHeader), VerifyHeaderError>> + use<'a, R, S> {
+    let SyncOptions {
+        prove,
+        exit_at_tip,
+        poll_interval,
+        max_poll_interval,
+        skip_existing,
+        ..
+    } = *opts;
+    let tip_notifier = opts.tip_notifier.clone();
+    let state_path = opts.state.clone();
+    let state_every = opts.state_every.max(1);
+
+    try_stream! {
+        if start_height < DifficultyContext::REQUIRED_WINDOW as u32 {
+            Err(VerifyHeaderError::InsufficientContext {
+                height: start_height,
+            })?;
+        }
+
+        // Determine effective start height from persistence, if available.
+        let tip = store
+            .tip()
+            .map_err(|e| VerifyHeaderError::Rpc(RpcError::Client(format!("store tip: {e}"))))?;
+        let effective_start = match tip {
+            Some(tip) if skip_existing => Some(tip.saturating_add(1).min(start_height)),
+            Some(tip) => tip.checked_add(1),
+            None => Some(start_height),
         };
-    }
 
-    Ok(())
+        if let Some(effective_start) = effective_start {
+            // Difficulty limits differ per network, so ask the node which chain it follows.
+            let network = rpc.network().await.map_err(VerifyHeaderError::Rpc)?;
+            info!("Syncing {network:?}");
+
+            let restored = match &state_path {
+                Some(path) => restore_ctx(path, store, effective_start, network)?,
+                None => None,
+            };
+            // Otherwise build it from persisted headers where possible, filling gaps via RPC.
+            let mut ctx = match restored {
+                Some(ctx) => ctx,
+                None => build_ctx_from_store_or_rpc(rpc, store, effective_start, network).await?,
+            };
+            let mut unsaved = 0u32;
+
+            let node_tip = rpc.block_count().await.map_err(VerifyHeaderError::Rpc)?;
+            info!(
+                "Node tip at {node_tip}; {} blocks behind",
+                node_tip.saturating_sub(u64::from(ctx.tip_height))
+            );
+
+            let mut height = effective_start;
+            let mut poll_delay = poll_interval;
+            // Hash of the header at `ctx.tip_height` once one has been yielded or skipped: saved
+            // with the state, and checked for a reorg whenever the loop waits at the tip.
+            let mut last_hash: Option<BlockHash> = None;
+
+            loop {
+                let stored = if skip_existing {
+                    stored_header(store, height, prove)?
+                } else {
+                    None
+                };
+                if let Some(header) = stored {
+                    info!("skipping already-verified block {height}");
+                    ctx.push_header(height, header.time, header.bits);
+                    last_hash = Some(header.hash());
+                    height = match height.checked_add(1) {
+                        Some(next) => next,
+                        None => break,
+                    };
+                    continue;
+                }
+
+                let fetched = rpc
+                    .header_by_height(height)
+                    .instrument(debug_span!("fetch", height))
+                    .await;
+                let header = match fetched {
+                    Ok(header) => header,
+                    Err(RpcError::HeightOutOfRange(_)) => {
+                        if let (Some(path), Some(hash)) =
+                            (state_path.as_deref().filter(|_| unsaved > 0), last_hash)
+                        {
+                            save_state(path, &ctx, hash);
+                            unsaved = 0;
+                        }
+                        if exit_at_tip {
+                            info!("Reached chain tip at height {}", height.saturating_sub(1));
+                            break;
+                        }
+                        if let Some(hash) = &last_hash {
+                            let confirmations =
+                                rpc.confirmations(hash).await.map_err(VerifyHeaderError::Rpc)?;
+                            if confirmations == Some(-1) {
+                                Err(VerifyHeaderError::Disconnected {
+                                    height: height.saturating_sub(1),
+                                })?;
+                            }
+                        }
+                        if let Some(notifier) = &tip_notifier {
+                            debug!("Block {height} not available yet; waiting for a notification");
+                            tokio::select! {
+                                () = notifier.notified() => {}
+                                () = tokio::time::sleep(max_poll_interval) => {}
+                            }
+                            continue;
+                        }
+                        debug!("Block {height} not available yet; polling again in {poll_delay:?}");
+                        tokio::time::sleep(poll_delay).await;
+                        poll_delay = (poll_delay * 2).min(max_poll_interval);
+                        continue;
+                    }
+                    Err(e) => Err(VerifyHeaderError::Rpc(e))?,
+                };
+                poll_delay = poll_interval;
+
+                // A header that does not build on the last one synced means that one was reorged
+                // away, and its replacement already extended, since it was fetched.
+                if let Some(hash) = last_hash
+                    && header.prev_block != hash
+                {
+                    Err(VerifyHeaderError::Disconnected {
+                        height: height.saturating_sub(1),
+                    })?;
+                }
+
+                info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+                info!("Block {height} {}", HeaderSummary::from(&header));
+                info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+                let timings = verify_header_timed(&header, height, &ctx, prove)?;
+                debug!("h={height} {timings}");
+
+                // The context only advances once the caller asks for the next header, i.e. after
+                // it has dealt with (for `sync_chain`: persisted) this one.
+                let (time, bits) = (header.time, header.bits);
+                last_hash = Some(header.hash());
+                yield (height, header);
+                ctx.push_header(height, time, bits);
+
+                unsaved += 1;
+                if let (Some(path), Some(hash)) =
+                    (state_path.as_deref().filter(|_| unsaved >= state_every), last_hash)
+                {
+                    save_state(path, &ctx, hash);
+                    unsaved = 0;
+                }
+
+                height = match height.checked_add(1) {
+                    Some(next) => next,
+                    None => break,
+                };
+            }
+        }
+    }
 }
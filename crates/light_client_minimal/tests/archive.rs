@@ -0,0 +1,74 @@
+mod common;
+
+use std::path::Path;
+
+use common::{MemorySource, decode, fixture_headers, require_cairo_program, temp_path};
+use light_client_minimal::archive::ArchiveStore;
+use light_client_minimal::store::Store;
+use light_client_minimal::store::file::FileStore;
+use light_client_minimal::sync::{SyncOptions, sync_chain};
+use zcash_crypto::cairo_proof_path;
+
+#[test]
+fn proofs_are_linked_to_their_headers() -> Result<(), Box<dyn std::error::Error>> {
+    let path = temp_path("archive-linked.jsonl");
+    let archive = ArchiveStore::new(&path)?;
+    let headers = &fixture_headers()[..2];
+    for (height, header_hex) in headers {
+        archive.put_proof(*height, header_hex, &cairo_proof_path(*height))?;
+    }
+
+    let records = archive.records()?;
+    assert_eq!(records.len(), 2);
+    for (rec, (height, header_hex)) in records.iter().zip(headers) {
+        assert_eq!(rec.height, *height);
+        assert_eq!(&rec.header_hex, header_hex);
+        assert_eq!(rec.proof_path, cairo_proof_path(*height));
+        assert!(rec.verified_at > 0);
+    }
+    // Consecutive records form a chain.
+    assert_eq!(
+        decode(&records[1].header_hex).prev_block,
+        decode(&records[0].header_hex).hash()
+    );
+    assert_eq!(archive.tip()?, Some(headers[1].0));
+    assert_eq!(archive.get(headers[0].0)?.as_ref(), Some(&records[0]));
+
+    // Reproving a height replaces its record on read.
+    archive.put_proof(headers[0].0, &headers[0].1, Path::new("elsewhere.json"))?;
+    assert_eq!(archive.records()?.len(), 2);
+    assert_eq!(
+        archive.get(headers[0].0)?.unwrap().proof_path,
+        Path::new("elsewhere.json")
+    );
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn proving_sync_archives_each_block() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let source = MemorySource::with_tip(3_000_031);
+    let store_path = temp_path("archive-sync-store.jsonl");
+    let archive_path = temp_path("archive-sync-proofs.jsonl");
+    let store = FileStore::new(&store_path)?;
+    let opts = SyncOptions {
+        prove: true,
+        exit_at_tip: true,
+        archive: Some(archive_path.clone()),
+        ..SyncOptions::default()
+    };
+
+    sync_chain(&source, &store, 3_000_030, &opts).await?;
+
+    let records = ArchiveStore::new(&archive_path)?.records()?;
+    assert_eq!(records.len(), 2);
+    for rec in &records {
+        assert_eq!(store.get(rec.height)?.as_ref(), Some(&rec.header_hex));
+        assert_eq!(rec.proof_path, cairo_proof_path(rec.height));
+    }
+    std::fs::remove_file(store_path)?;
+    std::fs::remove_file(archive_path)?;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::{decode, fixture_headers, temp_path};
+use light_client_minimal::codec::{CodecError, decode_header, encode_header};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::file::FileStore;
+
+#[test]
+fn stored_records_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let path = temp_path("codec-roundtrip.jsonl");
+    let store = FileStore::new(&path)?;
+    let fixtures = fixture_headers();
+    for pair in fixtures.windows(2).step_by(24) {
+        let [(height, header_hex), (_, child_hex)] = pair else {
+            unreachable!()
+        };
+        store.put(*height, header_hex)?;
+
+        let header = decode_header(&store.get(*height)?.unwrap())?;
+        // The child commits to this header's hash.
+        assert_eq!(header.hash(), decode(child_hex).prev_block);
+        assert_eq!(&encode_header(&header)?, header_hex);
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn malformed_hex_is_reported_by_stage() {
+    let (_, header_hex) = &fixture_headers()[0];
+
+    assert!(matches!(decode_header("zz"), Err(CodecError::Hex(_))));
+    assert!(matches!(
+        decode_header(&header_hex[..header_hex.len() - 2]),
+        Err(CodecError::Decode(_))
+    ));
+}
@@ -0,0 +1,240 @@
+//! Mock `zcashd` node serving the real mainnet headers committed in `data/headers.jsonl`.
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use light_client_minimal::net::HeaderSource;
+use light_client_minimal::net::rpc::RpcError;
+use mockito::{Matcher, ServerGuard};
+use serde_json::{Value, json};
+use zcash_primitives::block::{BlockHash, BlockHeader};
+
+const HEADERS_JSONL: &str = include_str!("../../../../data/headers.jsonl");
+
+/// Returns the committed `(height, header_hex)` fixtures in ascending height order.
+pub fn fixture_headers() -> Vec<(u32, String)> {
+    let mut headers = BTreeMap::new();
+    for line in HEADERS_JSONL.lines().filter(|l| !l.trim().is_empty()) {
+        let rec: Value = serde_json::from_str(line).unwrap();
+        let height = rec["height"].as_u64().unwrap() as u32;
+        let header_hex = rec["header_hex"].as_str().unwrap().to_string();
+        headers.insert(height, header_hex);
+    }
+    headers.into_iter().collect()
+}
+
+/// A per-process path under the system temp dir, removed first if it already exists.
+pub fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Fails the calling test unless the compiled Cairo program, which every synced header is
+/// run through, has been built. Tests calling it are `#[ignore]`d so a plain `cargo test` does
+/// not need the Cairo toolchain; run them with `--ignored` after `make build-cairo`, with
+/// `CAIRO_PROGRAM_PATH` pointing at `cairo/build/main.json` since tests run from the crate
+/// directory.
+pub fn require_cairo_program() {
+    let program = zcash_crypto::cairo_program_path();
+    assert!(
+        program.exists(),
+        "{} not found (run `make build-cairo` and set CAIRO_PROGRAM_PATH)",
+        program.display()
+    );
+}
+
+/// Decodes a fixture header.
+pub fn decode(header_hex: &str) -> BlockHeader {
+    BlockHeader::read(&hex::decode(header_hex).unwrap()[..]).unwrap()
+}
+
+/// RPC-style (byte-reversed) hex of a header's hash.
+pub fn hash_hex(header: &BlockHeader) -> String {
+    let mut bytes = header.hash().0;
+    bytes.reverse();
+    hex::encode(bytes)
+}
+
+/// RPC-style (byte-reversed) hex of a `uint256` field.
+fn rpc_hex(bytes: &[u8; 32]) -> String {
+    let mut bytes = *bytes;
+    bytes.reverse();
+    hex::encode(bytes)
+}
+
+/// `getblock <hash> 1` for a fixture header at `height` on a chain whose tip is `tip`: the
+/// header fields, plus `-1` confirmations if `height` is past the tip.
+pub fn verbose_block(header_hex: &str, height: u32, tip: u32) -> Value {
+    let header = decode(header_hex);
+    let confirmations = if height <= tip {
+        i64::from(tip - height) + 1
+    } else {
+        -1
+    };
+    json!({
+        "hash": hash_hex(&header),
+        "height": height,
+        "confirmations": confirmations,
+        "version": header.version,
+        "previousblockhash": rpc_hex(&header.prev_block.0),
+        "merkleroot": rpc_hex(&header.merkle_root),
+        "blockcommitments": rpc_hex(&header.final_sapling_root),
+        "time": header.time,
+        "bits": format!("{:08x}", header.bits),
+        "nonce": rpc_hex(&header.nonce),
+        "solution": hex::encode(&header.solution),
+        "tx": [],
+    })
+}
+
+/// A mockito server answering the JSON-RPC calls made by `RpcClient`.
+pub struct MockNode {
+    pub server: ServerGuard,
+    calls: Arc<AtomicUsize>,
+    tip: Arc<AtomicU32>,
+}
+
+impl MockNode {
+    /// Starts a mainnet node whose chain is exactly the committed fixtures.
+    pub async fn start() -> Self {
+        let last = fixture_headers().last().unwrap().0;
+        Self::start_with_tip(last).await
+    }
+
+    /// Starts a mainnet node that only reports fixtures up to `tip`; see [`MockNode::set_tip`].
+    pub async fn start_with_tip(tip: u32) -> Self {
+        let mut by_height = HashMap::new();
+        let mut by_hash = HashMap::new();
+        for (height, header_hex) in fixture_headers() {
+            let hash = hash_hex(&decode(&header_hex));
+            by_height.insert(height, hash.clone());
+            by_hash.insert(hash, (height, header_hex));
+        }
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let tip = Arc::new(AtomicU32::new(tip));
+        let node_tip = tip.clone();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(move |req| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let body: Value = serde_json::from_slice(req.body().unwrap()).unwrap();
+                let params = &body["params"];
+                let tip = node_tip.load(Ordering::SeqCst);
+                let outcome = match body["method"].as_str().unwrap() {
+                    "getblockchaininfo" => Ok(json!({ "chain": "main", "blocks": tip })),
+                    "getblockcount" => Ok(json!(tip)),
+                    "getbestblockhash" => Ok(json!(by_height[&tip])),
+                    "getblockhash" => {
+                        let height = params[0].as_u64().unwrap() as u32;
+                        by_height
+                            .get(&height)
+                            .filter(|_| height <= tip)
+                            .map(|h| json!(h))
+                            .ok_or((-8, "Block height out of range"))
+                    }
+                    // Blocks past the tip were mined, then disconnected by `set_tip`.
+                    "getblock" if params[1] == json!(1) => {
+                        let hash = params[0].as_str().unwrap();
+                        by_hash
+                            .get(hash)
+                            .map(|(height, h)| verbose_block(h, *height, tip))
+                            .ok_or((-5, "Block not found"))
+                    }
+                    "getblock" | "getblockheader" => {
+                        let hash = params[0].as_str().unwrap();
+                        by_hash
+                            .get(hash)
+                            .map(|(_, h)| json!(h))
+                            .ok_or((-5, "Block not found"))
+                    }
+                    _ => Err((-32601, "Method not found")),
+                };
+                let response = match outcome {
+                    Ok(result) => json!({ "result": result, "error": null, "id": body["id"] }),
+                    Err((code, message)) => json!({
+                        "result": null,
+                        "error": { "code": code, "message": message },
+                        "id": body["id"],
+                    }),
+                };
+                response.to_string().into_bytes()
+            })
+            .create_async()
+            .await;
+
+        MockNode { server, calls, tip }
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Extends (or rewinds) the chain the node reports, simulating newly mined blocks.
+    pub fn set_tip(&self, tip: u32) {
+        self.tip.store(tip, Ordering::SeqCst);
+    }
+
+    /// Total number of JSON-RPC requests served so far.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+/// An in-memory mainnet chain over the committed fixtures, with no HTTP involved.
+pub struct MemorySource {
+    headers: BTreeMap<u32, String>,
+    tip: AtomicU32,
+    fetches: AtomicUsize,
+}
+
+impl MemorySource {
+    /// A chain whose tip is `tip`; heights past it are [`RpcError::HeightOutOfRange`].
+    pub fn with_tip(tip: u32) -> Self {
+        MemorySource {
+            headers: fixture_headers().into_iter().collect(),
+            tip: AtomicU32::new(tip),
+            fetches: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn set_tip(&self, tip: u32) {
+        self.tip.store(tip, Ordering::SeqCst);
+    }
+
+    /// Number of `header_by_height` calls served so far.
+    pub fn fetches(&self) -> usize {
+        self.fetches.load(Ordering::SeqCst)
+    }
+
+    fn header_hex(&self, h: u32) -> Result<&str, RpcError> {
+        self.headers
+            .get(&h)
+            .filter(|_| h <= self.tip.load(Ordering::SeqCst))
+            .map(String::as_str)
+            .ok_or(RpcError::HeightOutOfRange(h))
+    }
+}
+
+impl HeaderSource for MemorySource {
+    async fn header_by_height(&self, h: u32) -> Result<BlockHeader, RpcError> {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        self.header_hex(h).map(decode)
+    }
+
+    async fn block_hash(&self, h: u32) -> Result<BlockHash, RpcError> {
+        self.header_hex(h)
+            .map(|header_hex| decode(header_hex).hash())
+    }
+
+    async fn block_count(&self) -> Result<u64, RpcError> {
+        Ok(self.tip.load(Ordering::SeqCst).into())
+    }
+}
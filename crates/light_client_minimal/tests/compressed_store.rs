@@ -0,0 +1,86 @@
+mod common;
+
+use common::{fixture_headers, temp_path};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::compressed::{CompressedFileStore, FRAME_RECORDS};
+use light_client_minimal::store::file::FileStore;
+
+const RECORDS: u32 = 1000;
+
+/// 1000 records cycling through the real fixture headers.
+fn records() -> Vec<(u32, String)> {
+    let fixtures = fixture_headers();
+    (0..RECORDS)
+        .map(|i| {
+            let (_, header_hex) = &fixtures[i as usize % fixtures.len()];
+            (3_000_000 + i, header_hex.clone())
+        })
+        .collect()
+}
+
+fn remove_store_files(path: &std::path::Path) {
+    for ext in ["", ".idx", ".pending"] {
+        let mut p = path.as_os_str().to_owned();
+        p.push(ext);
+        let _ = std::fs::remove_file(p);
+    }
+}
+
+#[test]
+fn compressed_store_round_trips_and_is_smaller() -> Result<(), Box<dyn std::error::Error>> {
+    let records = records();
+    let path = temp_path("compressed-store.zst");
+    remove_store_files(&path);
+    let jsonl_path = temp_path("compressed-store-baseline.jsonl");
+
+    let store = CompressedFileStore::new(&path)?;
+    let baseline = FileStore::new(&jsonl_path)?;
+    for (height, header_hex) in &records {
+        store.put(*height, header_hex)?;
+        baseline.put(*height, header_hex)?;
+    }
+
+    // Reads are served from both sealed frames and the pending tail.
+    assert_eq!(store.tip()?, Some(3_000_000 + RECORDS - 1));
+    // Every `get` from a sealed frame decompresses it, so sample across frames and the tail.
+    for (height, header_hex) in records.iter().step_by(37).chain(records.last()) {
+        assert_eq!(store.get(*height)?.as_ref(), Some(header_hex));
+    }
+    assert_eq!(store.get(3_000_000 + RECORDS)?, None);
+    assert_eq!(store.last_n(300)?, records[records.len() - 300..].to_vec());
+
+    // Reopening sees the same data, including after sealing the tail.
+    store.flush()?;
+    let reopened = CompressedFileStore::new(&path)?;
+    assert_eq!(reopened.tip()?, Some(3_000_000 + RECORDS - 1));
+    assert_eq!(reopened.last_n(RECORDS as usize)?, records);
+
+    let compressed_size = std::fs::metadata(&path)?.len();
+    let jsonl_size = std::fs::metadata(&jsonl_path)?.len();
+    assert!(
+        compressed_size * 2 < jsonl_size,
+        "compressed {compressed_size} bytes vs JSONL {jsonl_size} bytes"
+    );
+
+    remove_store_files(&path);
+    std::fs::remove_file(jsonl_path)?;
+    Ok(())
+}
+
+#[test]
+fn later_put_overrides_sealed_record() -> Result<(), Box<dyn std::error::Error>> {
+    let records = records();
+    let path = temp_path("compressed-store-override.zst");
+    remove_store_files(&path);
+
+    let store = CompressedFileStore::new(&path)?;
+    for (height, header_hex) in &records[..FRAME_RECORDS] {
+        store.put(*height, header_hex)?;
+    }
+    let replacement = &records[FRAME_RECORDS].1;
+    store.put(3_000_000, replacement)?;
+    assert_eq!(store.get(3_000_000)?.as_ref(), Some(replacement));
+
+    remove_store_files(&path);
+    Ok(())
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use common::{decode, fixture_headers, hash_hex};
+use light_client_minimal::net::rpc::RpcClient;
+use light_client_minimal::sync::{CONTEXT_FETCH_CONCURRENCY, verify_header};
+use mockito::Matcher;
+use serde_json::{Value, json};
+
+/// Requests currently being answered, and the most seen at once.
+#[derive(Default)]
+struct InFlight {
+    now: AtomicUsize,
+    max: AtomicUsize,
+}
+
+/// A node whose `getblockhash` answers take `delay`, so overlapping requests are observable.
+async fn slow_node(delay: Duration, in_flight: Arc<InFlight>) -> mockito::ServerGuard {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(
+            json!({ "method": "getblockchaininfo" }),
+        ))
+        .with_body(json!({ "result": { "chain": "main" }, "error": null, "id": 0 }).to_string())
+        .create_async()
+        .await;
+
+    let headers = fixture_headers();
+    let by_hash: Vec<(String, String)> = headers
+        .iter()
+        .map(|(_, header_hex)| (hash_hex(&decode(header_hex)), header_hex.clone()))
+        .collect();
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({ "method": "getblockheader" })))
+        .with_body_from_request(move |req| {
+            let body: Value = serde_json::from_slice(req.body().unwrap()).unwrap();
+            let hash = body["params"][0].as_str().unwrap();
+            let (_, header_hex) = by_hash.iter().find(|(h, _)| h == hash).unwrap();
+            json!({ "result": header_hex, "error": null, "id": body["id"] })
+                .to_string()
+                .into_bytes()
+        })
+        .create_async()
+        .await;
+
+    // The chunked body is written from its own thread, so a sleeping answer does not hold up
+    // the mock server's other connections.
+    for (height, header_hex) in headers {
+        let hash = hash_hex(&decode(&header_hex));
+        let in_flight = in_flight.clone();
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(
+                json!({ "method": "getblockhash", "params": [height] }),
+            ))
+            .with_chunked_body(move |w| {
+                let now = in_flight.now.fetch_add(1, Ordering::SeqCst) + 1;
+                in_flight.max.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(delay);
+                in_flight.now.fetch_sub(1, Ordering::SeqCst);
+                let body = json!({ "result": hash, "error": null, "id": 0 });
+                w.write_all(body.to_string().as_bytes())
+            })
+            .create_async()
+            .await;
+    }
+    server
+}
+
+#[tokio::test]
+async fn context_headers_are_fetched_concurrently_and_in_order()
+-> Result<(), Box<dyn std::error::Error>> {
+    let in_flight = Arc::new(InFlight::default());
+    let server = slow_node(Duration::from_millis(50), in_flight.clone()).await;
+    let client = RpcClient::new(&server.url())?;
+
+    // Passing the contextual difficulty check requires the window to be pushed by height.
+    verify_header(&client, 3_000_100).await?;
+
+    let max = in_flight.max.load(Ordering::SeqCst);
+    assert!(max > 1, "context headers were fetched one at a time");
+    assert!(
+        max <= CONTEXT_FETCH_CONCURRENCY,
+        "{max} requests in flight, limit is {CONTEXT_FETCH_CONCURRENCY}"
+    );
+    Ok(())
+}
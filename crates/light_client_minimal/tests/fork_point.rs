@@ -0,0 +1,74 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::{MemorySource, fixture_headers, temp_path};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::file::FileStore;
+use light_client_minimal::sync::{VerifyHeaderError, find_fork_point};
+
+/// Offset of the 32-byte nonce in a serialized header, after version, the three
+/// 32-byte hashes, time and bits.
+const NONCE_OFFSET: usize = 4 + 32 * 3 + 4 + 4;
+
+/// Stores the fixtures from 3_000_010 to 3_000_120, with every header above `fork` replaced
+/// by one the node does not have.
+fn diverging_store(name: &str, fork: u32) -> Result<(PathBuf, FileStore), std::io::Error> {
+    let path = temp_path(name);
+    let store = FileStore::new(&path)?;
+    for (height, header_hex) in fixture_headers() {
+        if !(3_000_010..=3_000_120).contains(&height) {
+            continue;
+        }
+        if height > fork {
+            let mut raw = hex::decode(header_hex).unwrap();
+            raw[NONCE_OFFSET] ^= 0x01;
+            store.put(height, &hex::encode(raw))?;
+        } else {
+            store.put(height, &header_hex)?;
+        }
+    }
+    Ok((path, store))
+}
+
+#[tokio::test]
+async fn finds_the_last_shared_height() -> Result<(), Box<dyn std::error::Error>> {
+    let source = MemorySource::with_tip(3_000_143);
+    // At the tip, one below it, between probes, on a probe, and at the lowest stored height.
+    for fork in [3_000_120, 3_000_119, 3_000_105, 3_000_088, 3_000_010] {
+        let (path, store) = diverging_store(&format!("fork-point-{fork}.jsonl"), fork)?;
+        assert_eq!(find_fork_point(&source, &store).await?, fork);
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn headers_past_the_node_tip_do_not_match() -> Result<(), Box<dyn std::error::Error>> {
+    // The node has not seen the store's last headers (yet), e.g. after switching nodes.
+    let source = MemorySource::with_tip(3_000_077);
+    let (path, store) = diverging_store("fork-point-behind.jsonl", 3_000_120)?;
+    assert_eq!(find_fork_point(&source, &store).await?, 3_000_077);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn no_shared_header_is_an_error() -> Result<(), Box<dyn std::error::Error>> {
+    let source = MemorySource::with_tip(3_000_143);
+    let (path, store) = diverging_store("fork-point-none.jsonl", 3_000_009)?;
+    assert!(matches!(
+        find_fork_point(&source, &store).await,
+        Err(VerifyHeaderError::NoCommonAncestor)
+    ));
+    std::fs::remove_file(path)?;
+
+    let path = temp_path("fork-point-empty.jsonl");
+    let empty = FileStore::new(&path)?;
+    assert!(matches!(
+        find_fork_point(&source, &empty).await,
+        Err(VerifyHeaderError::NoCommonAncestor)
+    ));
+    std::fs::remove_file(path)?;
+    Ok(())
+}
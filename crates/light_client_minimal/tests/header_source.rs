@@ -0,0 +1,41 @@
+mod common;
+
+use common::MemorySource;
+use light_client_minimal::net::HeaderSource;
+use light_client_minimal::net::rpc::RpcError;
+use light_client_minimal::sync::{VerifyHeaderError, report_header_at, verify_header};
+
+#[tokio::test]
+async fn memory_source_verifies_like_a_node() -> Result<(), Box<dyn std::error::Error>> {
+    let source = MemorySource::with_tip(3_000_143);
+
+    verify_header(&source, 3_000_100).await?;
+    // The 28-header window plus the header itself, with no HTTP round-trips.
+    assert_eq!(source.fetches(), 29);
+
+    let report = report_header_at(&source, 3_000_143).await?;
+    assert!(report.passed(), "{report}");
+    assert_eq!(report.expected_bits, Some(report.header_bits));
+    Ok(())
+}
+
+#[tokio::test]
+async fn heights_past_the_tip_are_out_of_range() -> Result<(), Box<dyn std::error::Error>> {
+    let source = MemorySource::with_tip(3_000_050);
+    assert_eq!(source.block_count().await?, 3_000_050);
+
+    assert!(matches!(
+        verify_header(&source, 3_000_051).await,
+        Err(VerifyHeaderError::Rpc(RpcError::HeightOutOfRange(
+            3_000_051
+        )))
+    ));
+
+    source.set_tip(3_000_051);
+    verify_header(&source, 3_000_051).await?;
+    assert_eq!(
+        source.block_hash(3_000_051).await?,
+        source.header_by_height(3_000_051).await?.hash()
+    );
+    Ok(())
+}
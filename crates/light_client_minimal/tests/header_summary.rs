@@ -0,0 +1,26 @@
+mod common;
+
+use common::{decode, fixture_headers, hash_hex};
+use light_client_minimal::sync::HeaderSummary;
+
+#[test]
+fn display_includes_hash_and_bits_hex() {
+    let (_, header_hex) = &fixture_headers()[0];
+    let header = decode(header_hex);
+    let summary = HeaderSummary::from(&header);
+
+    assert_eq!(summary.hash_hex(), hash_hex(&header));
+    assert_eq!(summary.solution_len, 1344);
+
+    let line = summary.to_string();
+    assert!(!line.contains('\n'));
+    assert!(
+        line.contains(&format!("hash={}", hash_hex(&header))),
+        "{line}"
+    );
+    assert!(
+        line.contains(&format!("bits={:#010x}", header.bits)),
+        "{line}"
+    );
+    assert!(line.contains("solution=1344B"), "{line}");
+}
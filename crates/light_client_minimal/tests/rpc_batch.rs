@@ -0,0 +1,99 @@
+use light_client_minimal::net::rpc::{RpcClient, RpcError};
+use mockito::Matcher;
+use serde_json::{Value, json};
+use zcash_primitives::block::BlockHash;
+
+const HEIGHTS: [u32; 3] = [3_000_000, 3_000_001, 3_000_002];
+
+/// A distinct hash per position, as the node would print it (RPC byte order).
+fn rpc_hash_hex(i: usize) -> String {
+    let mut bytes = [0u8; 32];
+    bytes[0] = 0xa0 + i as u8;
+    bytes[31] = 0x0b;
+    hex::encode(bytes)
+}
+
+/// Starts a mock node that expects one `getblockhash` batch for [`HEIGHTS`] and answers it
+/// with `responses`.
+async fn node_answering(responses: Value) -> mockito::ServerGuard {
+    let requests: Vec<Value> = HEIGHTS
+        .iter()
+        .enumerate()
+        .map(|(i, height)| {
+            json!({ "jsonrpc": "1.0", "id": i, "method": "getblockhash", "params": [height] })
+        })
+        .collect();
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::Json(Value::Array(requests)))
+        .with_header("content-type", "application/json")
+        .with_body(responses.to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn batch_keeps_height_order_and_reverses_each_hash() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Answered out of order: responses are matched back to their heights by id.
+    let server = node_answering(json!([
+        { "result": rpc_hash_hex(2), "error": null, "id": 2 },
+        { "result": rpc_hash_hex(0), "error": null, "id": 0 },
+        { "result": rpc_hash_hex(1), "error": null, "id": 1 },
+    ]))
+    .await;
+    let client = RpcClient::new(&server.url())?;
+
+    let hashes = client.get_block_hashes(&HEIGHTS).await?;
+
+    let expected: Vec<BlockHash> = (0..HEIGHTS.len())
+        .map(|i| {
+            let mut bytes: [u8; 32] = hex::decode(rpc_hash_hex(i)).unwrap().try_into().unwrap();
+            bytes.reverse();
+            BlockHash(bytes)
+        })
+        .collect();
+    assert_eq!(hashes, expected);
+    assert_eq!(hashes[1].0[31], 0xa1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_reports_the_height_past_the_tip() -> Result<(), Box<dyn std::error::Error>> {
+    let server = node_answering(json!([
+        { "result": rpc_hash_hex(0), "error": null, "id": 0 },
+        { "result": rpc_hash_hex(1), "error": null, "id": 1 },
+        {
+            "result": null,
+            "error": { "code": -8, "message": "Block height out of range" },
+            "id": 2,
+        },
+    ]))
+    .await;
+    let client = RpcClient::new(&server.url())?;
+
+    match client.get_block_hashes(&HEIGHTS).await {
+        Err(RpcError::HeightOutOfRange(height)) => assert_eq!(height, 3_000_002),
+        other => panic!("expected HeightOutOfRange, got {other:?}"),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn unanswered_call_fails_the_batch() -> Result<(), Box<dyn std::error::Error>> {
+    let server = node_answering(json!([
+        { "result": rpc_hash_hex(0), "error": null, "id": 0 },
+        { "result": rpc_hash_hex(2), "error": null, "id": 2 },
+    ]))
+    .await;
+    let client = RpcClient::new(&server.url())?;
+
+    assert!(matches!(
+        client.get_block_hashes(&HEIGHTS).await,
+        Err(RpcError::Client(_))
+    ));
+    Ok(())
+}
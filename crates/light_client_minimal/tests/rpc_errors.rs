@@ -0,0 +1,139 @@
+mod common;
+
+use common::MockNode;
+use light_client_minimal::net::rpc::{RpcClient, RpcError, RpcErrorCode};
+use zcash_primitives::block::BlockHash;
+
+const KNOWN: [(i64, RpcErrorCode); 13] = [
+    (-32600, RpcErrorCode::InvalidRequest),
+    (-32601, RpcErrorCode::MethodNotFound),
+    (-32602, RpcErrorCode::InvalidParams),
+    (-32603, RpcErrorCode::InternalError),
+    (-32700, RpcErrorCode::ParseError),
+    (-1, RpcErrorCode::MiscError),
+    (-3, RpcErrorCode::TypeError),
+    (-5, RpcErrorCode::InvalidAddressOrKey),
+    (-7, RpcErrorCode::OutOfMemory),
+    (-8, RpcErrorCode::InvalidParameter),
+    (-20, RpcErrorCode::DatabaseError),
+    (-22, RpcErrorCode::DeserializationError),
+    (-28, RpcErrorCode::InWarmup),
+];
+
+fn rpc_error(code: i64) -> RpcError {
+    RpcError::Rpc {
+        code,
+        message: String::new(),
+    }
+}
+
+#[test]
+fn each_code_maps_to_its_variant() {
+    for (code, variant) in KNOWN {
+        assert_eq!(RpcErrorCode::from(code), variant);
+        assert_eq!(variant.code(), code);
+        assert_eq!(rpc_error(code).rpc_code(), Some(variant));
+        assert_eq!(rpc_error(code).is_height_out_of_range(), code == -8);
+    }
+    assert_eq!(RpcErrorCode::from(-42), RpcErrorCode::Other(-42));
+    assert_eq!(RpcErrorCode::Other(-42).code(), -42);
+}
+
+#[test]
+fn transport_errors_have_no_rpc_code() {
+    assert_eq!(RpcError::NonHttpUrl.rpc_code(), None);
+    assert_eq!(RpcError::Client("timeout".into()).rpc_code(), None);
+    assert_eq!(RpcError::Timeout.rpc_code(), None);
+    assert_eq!(RpcError::Connect("refused".into()).rpc_code(), None);
+}
+
+#[tokio::test]
+async fn node_errors_are_told_apart() -> Result<(), Box<dyn std::error::Error>> {
+    let node = MockNode::start_with_tip(3_000_010).await;
+    let client = RpcClient::new(&node.url())?;
+
+    let past_tip = client.get_block_hash(3_000_011).await.unwrap_err();
+    assert!(matches!(past_tip, RpcError::HeightOutOfRange(3_000_011)));
+    assert_eq!(past_tip.rpc_code(), Some(RpcErrorCode::InvalidParameter));
+    assert!(past_tip.is_height_out_of_range());
+
+    let unknown = client.get_block(&BlockHash([0x11; 32])).await.unwrap_err();
+    assert_eq!(unknown.rpc_code(), Some(RpcErrorCode::InvalidAddressOrKey));
+    assert!(!unknown.is_height_out_of_range());
+    Ok(())
+}
+
+#[tokio::test]
+async fn code_minus_8_becomes_height_out_of_range() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/")
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "result": null,
+                "error": { "code": -8, "message": "Block height out of range" },
+                "id": "light-client-minimal",
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let client = RpcClient::new(&server.url())?;
+
+    match client.get_block_hash(5_000_000).await {
+        Err(RpcError::HeightOutOfRange(height)) => assert_eq!(height, 5_000_000),
+        other => panic!("expected HeightOutOfRange, got {other:?}"),
+    }
+    // Header lookups by height go through `getblockhash` and surface it unchanged.
+    assert!(matches!(
+        client.get_block_header_by_height(5_000_000).await,
+        Err(RpcError::HeightOutOfRange(5_000_000))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn stalled_node_is_a_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    // The kernel completes the handshake for an un-accepted listener, so the request is sent
+    // and no response ever arrives.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}/", listener.local_addr()?);
+    let err = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_millis(200))
+        .build()?
+        .post(&url)
+        .send()
+        .await
+        .unwrap_err();
+
+    let err = RpcError::from(err);
+    assert!(matches!(err, RpcError::Timeout), "got {err:?}");
+    assert_eq!(err.rpc_code(), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_gives_up_on_a_stalled_node() -> Result<(), Box<dyn std::error::Error>> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let client = RpcClient::new(&format!("http://{}/", listener.local_addr()?))?
+        .with_timeout(std::time::Duration::from_millis(200));
+
+    let err = client.get_block_count().await.unwrap_err();
+    assert!(matches!(err, RpcError::Timeout), "got {err:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn refused_connection_is_a_connect_error() -> Result<(), Box<dyn std::error::Error>> {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .port();
+    let client = RpcClient::new(&format!("http://127.0.0.1:{port}"))?;
+
+    match client.get_block_count().await {
+        Err(RpcError::Connect(msg)) => assert!(!msg.is_empty()),
+        other => panic!("expected Connect, got {other:?}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{MockNode, decode, fixture_headers, hash_hex};
+use light_client_minimal::net::rpc::{RpcClient, RpcError};
+use mockito::Matcher;
+use serde_json::json;
+
+const LIMIT: usize = 16 * 1024;
+
+/// A `getblock` result far larger than [`LIMIT`].
+fn oversized_block_response() -> String {
+    json!({ "result": "00".repeat(4 * LIMIT), "error": null, "id": "light-client-minimal" })
+        .to_string()
+}
+
+fn assert_too_large(result: Result<Vec<u8>, RpcError>) {
+    match result {
+        Err(RpcError::Client(msg)) => assert!(msg.contains("exceeds"), "{msg}"),
+        other => panic!("expected the size guard to trigger, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn oversized_response_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let hash = decode(&fixture_headers()[0].1).hash();
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({ "method": "getblock" })))
+        .with_body(oversized_block_response())
+        .create_async()
+        .await;
+
+    let client = RpcClient::new(&server.url())?.with_max_response_bytes(LIMIT);
+    assert_too_large(client.get_block(&hash).await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn oversized_chunked_response_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let hash = decode(&fixture_headers()[0].1).hash();
+    let mut server = mockito::Server::new_async().await;
+    // No Content-Length, so the guard has to trip while streaming.
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({ "method": "getblock" })))
+        .with_chunked_body(|w| w.write_all(oversized_block_response().as_bytes()))
+        .create_async()
+        .await;
+
+    let client = RpcClient::new(&server.url())?.with_max_response_bytes(LIMIT);
+    assert_too_large(client.get_block(&hash).await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn headers_fit_within_a_small_limit() -> Result<(), Box<dyn std::error::Error>> {
+    let (height, header_hex) = &fixture_headers()[0];
+    let node = MockNode::start().await;
+    let client = RpcClient::new(&node.url())?.with_max_response_bytes(LIMIT);
+
+    let header = client.get_block_header_by_height(*height).await?;
+    assert_eq!(hash_hex(&header), hash_hex(&decode(header_hex)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn falls_back_to_getblock_without_getblockheader() -> Result<(), Box<dyn std::error::Error>> {
+    let header_hex = &fixture_headers()[0].1;
+    let hash = decode(header_hex).hash();
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({ "method": "getblockheader" })))
+        .with_body(
+            json!({
+                "result": null,
+                "error": { "code": -32601, "message": "Method not found" },
+                "id": "light-client-minimal",
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({ "method": "getblock" })))
+        .with_body(json!({ "result": header_hex, "error": null, "id": "x" }).to_string())
+        .create_async()
+        .await;
+
+    let client = RpcClient::new(&server.url())?;
+    assert_eq!(client.get_block_header(&hash).await?.hash(), hash);
+    Ok(())
+}
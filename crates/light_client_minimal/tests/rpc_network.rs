@@ -0,0 +1,51 @@
+use light_client_minimal::net::rpc::{RpcClient, RpcError};
+use mockito::Matcher;
+use serde_json::json;
+use zcash_crypto::Network;
+
+/// Starts a mock node whose `getblockchaininfo` reports the given `chain`.
+async fn mock_chain(chain: &str) -> mockito::ServerGuard {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(
+            json!({ "method": "getblockchaininfo" }),
+        ))
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "result": { "chain": chain, "blocks": 100, "headers": 100 },
+                "error": null,
+                "id": "light-client-minimal",
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn get_network_detects_each_chain() -> Result<(), Box<dyn std::error::Error>> {
+    for (chain, expected) in [
+        ("main", Network::Mainnet),
+        ("test", Network::Testnet),
+        ("regtest", Network::Regtest),
+    ] {
+        let server = mock_chain(chain).await;
+        let client = RpcClient::new(&server.url())?;
+        assert_eq!(client.get_network().await?, expected);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_network_rejects_unknown_chain() -> Result<(), Box<dyn std::error::Error>> {
+    let server = mock_chain("signet").await;
+    let client = RpcClient::new(&server.url())?;
+    match client.get_network().await {
+        Err(RpcError::UnknownNetwork(chain)) => assert_eq!(chain, "signet"),
+        other => panic!("expected UnknownNetwork, got {other:?}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use common::{MockNode, decode, fixture_headers};
+use light_client_minimal::net::rpc::RpcClient;
+
+#[tokio::test]
+async fn best_block_header_matches_node_tip() -> Result<(), Box<dyn std::error::Error>> {
+    let node = MockNode::start_with_tip(3_000_100).await;
+    let client = RpcClient::new(&node.url())?;
+
+    let (height, header) = client.get_best_block_header().await?;
+    assert_eq!(height, 3_000_100);
+    let expected = fixture_headers()
+        .into_iter()
+        .find(|(h, _)| *h == height)
+        .map(|(_, hex)| decode(&hex))
+        .unwrap();
+    assert_eq!(header.hash(), expected.hash());
+
+    // New blocks move the reported tip along.
+    node.set_tip(3_000_101);
+    assert_eq!(client.get_best_block_header().await?.0, 3_000_101);
+    Ok(())
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::{MockNode, decode, fixture_headers, verbose_block};
+use light_client_minimal::net::rpc::{RpcClient, RpcError};
+use serde_json::{Value, json};
+use zcash_primitives::block::BlockHash;
+
+/// `getblockheader <hash> true` for mainnet block 3000100, trimmed to the header fields.
+const VERBOSE_JSON: &str = include_str!("../../../data/getblockheader_verbose_3000100.json");
+
+fn payload() -> Value {
+    serde_json::from_str(VERBOSE_JSON).unwrap()
+}
+
+fn payload_hash(payload: &Value) -> BlockHash {
+    let mut bytes = hex::decode(payload["hash"].as_str().unwrap()).unwrap();
+    bytes.reverse();
+    BlockHash::try_from_slice(&bytes).unwrap()
+}
+
+/// A node answering every request with `result`.
+async fn node_returning(result: &Value) -> mockito::ServerGuard {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/")
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "result": result, "error": null, "id": "light-client-minimal" }).to_string(),
+        )
+        .create_async()
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn verbose_header_is_rebuilt_byte_for_byte() -> Result<(), Box<dyn std::error::Error>> {
+    let payload = payload();
+    let server = node_returning(&payload).await;
+    let client = RpcClient::new(&server.url())?;
+
+    let header = client
+        .get_block_header_verbose(&payload_hash(&payload))
+        .await?;
+
+    assert_eq!(header.hash(), payload_hash(&payload));
+    let (_, header_hex) = fixture_headers()
+        .into_iter()
+        .find(|(h, _)| *h == 3_000_100)
+        .unwrap();
+    assert_eq!(header.hash(), decode(&header_hex).hash());
+    let mut raw = Vec::new();
+    header.write(&mut raw)?;
+    assert_eq!(hex::encode(raw), header_hex);
+    Ok(())
+}
+
+#[tokio::test]
+async fn finalsaplingroot_is_used_without_blockcommitments()
+-> Result<(), Box<dyn std::error::Error>> {
+    let mut payload = payload();
+    let commitments = payload
+        .as_object_mut()
+        .unwrap()
+        .remove("blockcommitments")
+        .unwrap();
+    payload["finalsaplingroot"] = commitments;
+    let server = node_returning(&payload).await;
+    let client = RpcClient::new(&server.url())?;
+
+    let header = client
+        .get_block_header_verbose(&payload_hash(&payload))
+        .await?;
+    assert_eq!(header.hash(), payload_hash(&payload));
+    Ok(())
+}
+
+#[tokio::test]
+async fn misreported_field_is_a_decode_error() -> Result<(), Box<dyn std::error::Error>> {
+    let mut payload = payload();
+    payload["time"] = json!(payload["time"].as_u64().unwrap() + 1);
+    let server = node_returning(&payload).await;
+    let client = RpcClient::new(&server.url())?;
+
+    match client
+        .get_block_header_verbose(&payload_hash(&payload))
+        .await
+    {
+        Err(RpcError::DecodeHeader(msg)) => assert!(msg.contains("does not hash"), "{msg}"),
+        other => panic!("expected DecodeHeader, got {other:?}"),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn getblock_verbose_reports_height_and_confirmations()
+-> Result<(), Box<dyn std::error::Error>> {
+    let (height, header_hex) = fixture_headers()
+        .into_iter()
+        .find(|(h, _)| *h == 3_000_100)
+        .unwrap();
+    let server = node_returning(&verbose_block(&header_hex, height, 3_000_105)).await;
+    let client = RpcClient::new(&server.url())?;
+    let hash = decode(&header_hex).hash();
+
+    let meta = client.get_block_verbose(&hash).await?;
+    assert_eq!(meta.height, 3_000_100);
+    assert_eq!(meta.confirmations, 6);
+    assert_eq!(meta.header.hash(), hash);
+    Ok(())
+}
+
+#[tokio::test]
+async fn disconnected_block_has_negative_confirmations() -> Result<(), Box<dyn std::error::Error>> {
+    let node = MockNode::start_with_tip(3_000_100).await;
+    let client = RpcClient::new(&node.url())?;
+    let hash = client.get_block_hash(3_000_100).await?;
+    assert_eq!(client.get_block_verbose(&hash).await?.confirmations, 1);
+
+    node.set_tip(3_000_099);
+    let meta = client.get_block_verbose(&hash).await?;
+    assert_eq!((meta.height, meta.confirmations), (3_000_100, -1));
+    Ok(())
+}
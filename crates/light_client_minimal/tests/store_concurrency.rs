@@ -0,0 +1,49 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use common::{fixture_headers, temp_path};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::file::FileStore;
+use serde_json::Value;
+
+const TASKS: u32 = 8;
+const PER_TASK: u32 = 100;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_puts_write_whole_lines() -> Result<(), Box<dyn std::error::Error>> {
+    let path = temp_path("store-concurrent.jsonl");
+    let store = Arc::new(FileStore::new(&path)?);
+    let fixtures = Arc::new(fixture_headers());
+
+    let writers: Vec<_> = (0..TASKS)
+        .map(|task| {
+            let (store, fixtures) = (Arc::clone(&store), Arc::clone(&fixtures));
+            tokio::task::spawn_blocking(move || {
+                for i in 0..PER_TASK {
+                    let header_hex = &fixtures[(i as usize) % fixtures.len()].1;
+                    store.put(task * PER_TASK + i, header_hex)?;
+                }
+                Ok::<_, std::io::Error>(())
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.await??;
+    }
+
+    let contents = std::fs::read_to_string(&path)?;
+    let mut heights = BTreeSet::new();
+    for line in contents.lines() {
+        let rec: Value = serde_json::from_str(line)?;
+        assert!(rec["header_hex"].is_string(), "malformed record: {line}");
+        heights.insert(rec["height"].as_u64().expect("numeric height"));
+    }
+    assert_eq!(contents.lines().count(), (TASKS * PER_TASK) as usize);
+    assert_eq!(heights.len(), (TASKS * PER_TASK) as usize);
+    assert_eq!(store.iter_headers()?.count(), (TASKS * PER_TASK) as usize);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
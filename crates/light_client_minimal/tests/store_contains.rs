@@ -0,0 +1,98 @@
+mod common;
+
+use std::io;
+
+use common::{fixture_headers, temp_path};
+use light_client_minimal::store::compressed::{CompressedFileStore, FRAME_RECORDS};
+use light_client_minimal::store::file::FileStore;
+use light_client_minimal::store::{HeaderIter, Store};
+
+/// Heights spanned from 3_000_000: every other one fills a sealed compressed frame and leaves
+/// some in the pending tail.
+const SPAN: u32 = 2 * FRAME_RECORDS as u32 + 40;
+
+/// Stores every other height from 3_000_000.
+fn put_even_heights(store: &impl Store) -> io::Result<()> {
+    let fixtures = fixture_headers();
+    for i in (0..SPAN).step_by(2) {
+        let (_, header_hex) = &fixtures[i as usize % fixtures.len()];
+        store.put(3_000_000 + i, header_hex)?;
+    }
+    Ok(())
+}
+
+fn assert_contains_even_heights(store: &impl Store) -> io::Result<()> {
+    for i in 0..SPAN {
+        let height = 3_000_000 + i;
+        assert_eq!(store.contains(height)?, i % 2 == 0, "height {height}");
+    }
+    for height in [0, 2_999_998, 3_000_000 + SPAN, u32::MAX] {
+        assert!(!store.contains(height)?, "height {height}");
+    }
+    Ok(())
+}
+
+#[test]
+fn file_store_contains_only_stored_heights() -> Result<(), Box<dyn std::error::Error>> {
+    let path = temp_path("store-contains.jsonl");
+    let store = FileStore::new(&path)?;
+    assert!(!store.contains(3_000_000)?);
+
+    put_even_heights(&store)?;
+    assert_contains_even_heights(&store)?;
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn compressed_store_contains_sealed_and_pending_heights() -> Result<(), Box<dyn std::error::Error>>
+{
+    let path = temp_path("store-contains.zst");
+    let store = CompressedFileStore::new(&path)?;
+
+    // Gaps inside a sealed frame's height range must not count as stored.
+    put_even_heights(&store)?;
+    assert_contains_even_heights(&store)?;
+    for ext in ["", ".idx", ".pending"] {
+        let mut p = path.as_os_str().to_owned();
+        p.push(ext);
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+/// A store relying on the default `contains`.
+struct GetOnly(FileStore);
+
+impl Store for GetOnly {
+    fn put(&self, height: u32, header_hex: &str) -> io::Result<()> {
+        self.0.put(height, header_hex)
+    }
+    fn get(&self, height: u32) -> io::Result<Option<String>> {
+        self.0.get(height)
+    }
+    fn tip(&self) -> io::Result<Option<u32>> {
+        self.0.tip()
+    }
+    fn last_n(&self, n: usize) -> io::Result<Vec<(u32, String)>> {
+        self.0.last_n(n)
+    }
+    fn iter_headers(&self) -> io::Result<HeaderIter> {
+        self.0.iter_headers()
+    }
+}
+
+#[test]
+fn default_contains_agrees_with_get() -> Result<(), Box<dyn std::error::Error>> {
+    let path = temp_path("store-contains-default.jsonl");
+    let store = GetOnly(FileStore::new(&path)?);
+    let fixtures = fixture_headers();
+    for (height, header_hex) in fixtures.iter().step_by(2) {
+        store.put(*height, header_hex)?;
+    }
+    for (i, (height, _)) in fixtures.iter().enumerate() {
+        assert_eq!(store.contains(*height)?, i % 2 == 0, "height {height}");
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}
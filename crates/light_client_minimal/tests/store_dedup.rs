@@ -0,0 +1,84 @@
+mod common;
+
+use common::{fixture_headers, temp_path};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::compressed::CompressedFileStore;
+use light_client_minimal::store::file::FileStore;
+
+#[test]
+fn rewritten_height_reads_back_latest_record() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let (first, second) = (&fixtures[0].1, &fixtures[1].1);
+    let path = temp_path("store-dedup.jsonl");
+    let store = FileStore::new(&path)?;
+
+    store.put(99, first)?;
+    store.put(100, first)?;
+    // A reorg replaces the block at height 100.
+    store.put(100, second)?;
+
+    assert_eq!(store.get(100)?.as_ref(), Some(second));
+    assert_eq!(
+        store.last_n(2)?,
+        vec![(99, first.clone()), (100, second.clone())]
+    );
+    assert_eq!(store.last_n(1)?, vec![(100, second.clone())]);
+    assert_eq!(store.iter_headers()?.count(), 2);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn retried_put_leaves_single_record() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let path = temp_path("store-retry.jsonl");
+    let store = FileStore::new(&path)?;
+
+    store.put(100, &fixtures[0].1)?;
+    store.put(101, &fixtures[1].1)?;
+    // A retried sync step writes a height that is already stored.
+    store.put(100, &fixtures[0].1)?;
+    store.put(101, &fixtures[1].1)?;
+
+    assert_eq!(std::fs::read_to_string(&path)?.lines().count(), 2);
+    assert_eq!(store.tip()?, Some(101));
+    assert_eq!(store.get(100)?.as_ref(), Some(&fixtures[0].1));
+    assert_eq!(store.iter_headers()?.count(), 2);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn reopened_store_skips_retried_tip() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let path = temp_path("store-reopen.jsonl");
+    FileStore::new(&path)?.put(100, &fixtures[0].1)?;
+
+    // A restarted sync retries the block it wrote last before stopping.
+    let store = FileStore::new(&path)?;
+    store.put(100, &fixtures[0].1)?;
+    store.put(101, &fixtures[1].1)?;
+
+    assert_eq!(std::fs::read_to_string(&path)?.lines().count(), 2);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn compressed_store_retried_put_is_idempotent() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let path = temp_path("store-retry.zst");
+    let store = CompressedFileStore::new(&path)?;
+
+    store.put(100, &fixtures[0].1)?;
+    store.flush()?;
+    // Already sealed into a frame; the retry must not add a pending copy.
+    store.put(100, &fixtures[0].1)?;
+
+    let pending = std::fs::read_to_string(format!("{}.pending", path.display()))?;
+    assert!(pending.trim().is_empty());
+    assert_eq!(store.last_n(10)?, vec![(100, fixtures[0].1.clone())]);
+    Ok(())
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::{decode, fixture_headers, hash_hex, temp_path};
+use light_client_minimal::store::file::FileStore;
+use light_client_minimal::store::{Store, export_json};
+use serde_json::Value;
+
+#[test]
+fn export_json_writes_decoded_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let path = temp_path("store-export.jsonl");
+    let store = FileStore::new(&path)?;
+    for (height, header_hex) in &fixtures[..5] {
+        store.put(*height, header_hex)?;
+    }
+
+    let mut out = Vec::new();
+    export_json(&store, fixtures[1].0, fixtures[3].0, &mut out)?;
+    let exported: Value = serde_json::from_slice(&out)?;
+
+    let exported = exported.as_array().expect("top-level array");
+    assert_eq!(exported.len(), 3);
+    for (obj, (height, header_hex)) in exported.iter().zip(&fixtures[1..4]) {
+        let header = decode(header_hex);
+        let mut keys: Vec<_> = obj.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            ["bits", "hash", "height", "target", "time", "version"]
+        );
+
+        assert_eq!(obj["height"], *height);
+        assert_eq!(obj["hash"], hash_hex(&header));
+        assert_eq!(obj["version"], header.version);
+        assert_eq!(obj["time"], header.time);
+        assert_eq!(obj["bits"], format!("{:08x}", header.bits));
+
+        // Big-endian target: the exponent byte of nBits places the mantissa.
+        let target = obj["target"].as_str().unwrap();
+        assert_eq!(target.len(), 64);
+        let mantissa = format!("{:06x}", header.bits & 0x00ff_ffff);
+        let leading_zero_bytes = 32 - (header.bits >> 24) as usize;
+        assert_eq!(
+            &target[..2 * leading_zero_bytes],
+            "0".repeat(2 * leading_zero_bytes)
+        );
+        assert!(target[2 * leading_zero_bytes..].starts_with(&mantissa));
+    }
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
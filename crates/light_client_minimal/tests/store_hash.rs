@@ -0,0 +1,70 @@
+mod common;
+
+use std::io::Write;
+
+use common::{decode, fixture_headers, hash_hex, temp_path};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::compressed::CompressedFileStore;
+use light_client_minimal::store::file::FileStore;
+
+#[test]
+fn file_store_finds_header_by_hash() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let path = temp_path("store-hash.jsonl");
+    let store = FileStore::new(&path)?;
+    for (height, header_hex) in &fixtures[..3] {
+        store.put(*height, header_hex)?;
+    }
+
+    let (height, header_hex) = &fixtures[1];
+    let hash = hash_hex(&decode(header_hex));
+    assert!(std::fs::read_to_string(&path)?.contains(&format!("\"hash_hex\":\"{hash}\"")));
+    assert_eq!(
+        store.get_by_hash(&hash)?,
+        Some((*height, header_hex.clone()))
+    );
+    assert_eq!(store.get_by_hash(&"00".repeat(32))?, None);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn legacy_records_without_hash_are_rehashed() -> Result<(), Box<dyn std::error::Error>> {
+    let (height, header_hex) = &fixture_headers()[0];
+    let path = temp_path("store-hash-legacy.jsonl");
+    let mut file = std::fs::File::create(&path)?;
+    writeln!(
+        file,
+        "{{\"height\":{height},\"header_hex\":\"{header_hex}\"}}"
+    )?;
+
+    let store = FileStore::new(&path)?;
+    let hash = hash_hex(&decode(header_hex));
+    assert_eq!(
+        store.get_by_hash(&hash)?,
+        Some((*height, header_hex.clone()))
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn compressed_store_finds_header_by_hash() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let path = temp_path("store-hash.zst");
+    let store = CompressedFileStore::new(&path)?;
+    for (height, header_hex) in &fixtures[..3] {
+        store.put(*height, header_hex)?;
+    }
+    store.flush()?;
+
+    let (height, header_hex) = &fixtures[2];
+    let hash = hash_hex(&decode(header_hex)).to_uppercase();
+    assert_eq!(
+        store.get_by_hash(&hash)?,
+        Some((*height, header_hex.clone()))
+    );
+    Ok(())
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{fixture_headers, temp_path};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::compressed::CompressedFileStore;
+use light_client_minimal::store::file::FileStore;
+
+const SHUFFLED: [usize; 5] = [3, 0, 4, 1, 2];
+
+fn assert_ascending(store: &impl Store) -> Result<(), Box<dyn std::error::Error>> {
+    let got = store.iter_headers()?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(got, fixture_headers()[..5].to_vec());
+    Ok(())
+}
+
+#[test]
+fn file_store_iterates_in_height_order() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let path = temp_path("store-iter.jsonl");
+    let store = FileStore::new(&path)?;
+    for i in SHUFFLED {
+        store.put(fixtures[i].0, &fixtures[i].1)?;
+    }
+
+    assert_ascending(&store)?;
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn compressed_store_iterates_in_height_order() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let path = temp_path("store-iter.zst");
+    let store = CompressedFileStore::new(&path)?;
+    for (n, i) in SHUFFLED.into_iter().enumerate() {
+        store.put(fixtures[i].0, &fixtures[i].1)?;
+        // Seal the first records so iteration spans a frame and the pending tail.
+        if n == 2 {
+            store.flush()?;
+        }
+    }
+
+    assert_ascending(&store)?;
+    for ext in ["", ".idx", ".pending"] {
+        let mut p = path.clone().into_os_string();
+        p.push(ext);
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn rewritten_heights_yield_only_the_latest_record() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let (height, _) = fixtures[1];
+    let path = temp_path("store-iter-rewrite.zst");
+    let store = CompressedFileStore::new(&path)?;
+    for (h, hex) in &fixtures[..3] {
+        store.put(*h, hex)?;
+    }
+    store.flush()?;
+    // Rewrite the middle height twice, once into a later frame and once into the pending tail,
+    // so iteration has to pick the newest source over an older frame.
+    store.put(height, &fixtures[4].1)?;
+    store.flush()?;
+    store.put(height, &fixtures[3].1)?;
+
+    let got = store.iter_headers()?.collect::<Result<Vec<_>, _>>()?;
+    let expected = vec![
+        fixtures[0].clone(),
+        (height, fixtures[3].1.clone()),
+        fixtures[2].clone(),
+    ];
+    assert_eq!(got, expected);
+
+    let file_path = temp_path("store-iter-rewrite.jsonl");
+    let file = FileStore::new(&file_path)?;
+    for (h, hex) in &fixtures[..3] {
+        file.put(*h, hex)?;
+    }
+    file.put(height, &fixtures[4].1)?;
+    file.put(height, &fixtures[3].1)?;
+    assert_eq!(
+        file.iter_headers()?.collect::<Result<Vec<_>, _>>()?,
+        expected
+    );
+
+    std::fs::remove_file(file_path)?;
+    for ext in ["", ".idx", ".pending"] {
+        let mut p = path.clone().into_os_string();
+        p.push(ext);
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
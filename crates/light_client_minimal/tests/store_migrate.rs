@@ -0,0 +1,50 @@
+mod common;
+
+use common::{fixture_headers, temp_path};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::compressed::CompressedFileStore;
+use light_client_minimal::store::file::FileStore;
+use light_client_minimal::store::migrate;
+
+const RECORDS: u32 = 500;
+
+#[test]
+fn migrates_file_store_into_compressed_store() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let src_path = temp_path("store-migrate.jsonl");
+    let dst_path = temp_path("store-migrate.zst");
+    let src = FileStore::new(&src_path)?;
+    // A reorged height: only the replacement is migrated.
+    src.put(3_000_000, &fixtures[0].1)?;
+    src.put(3_000_000, &fixtures[1].1)?;
+    for i in 1..RECORDS {
+        let (_, header_hex) = &fixtures[i as usize % fixtures.len()];
+        src.put(3_000_000 + i, header_hex)?;
+    }
+
+    let dst = CompressedFileStore::new(&dst_path)?;
+    assert_eq!(migrate(&src, &dst)?, RECORDS);
+
+    assert_eq!(dst.tip()?, src.tip()?);
+    // `FileStore::get` rescans the file, so spot-check heights here; the full
+    // comparison is the `iter_headers` one below.
+    for height in (3_000_000..3_000_000 + RECORDS).step_by(37) {
+        assert_eq!(dst.get(height)?, src.get(height)?, "height {height}");
+    }
+    assert_eq!(dst.get(3_000_000)?.as_deref(), Some(fixtures[1].1.as_str()));
+
+    // Rerunning (e.g. after an interruption) leaves the destination unchanged.
+    assert_eq!(migrate(&src, &dst)?, RECORDS);
+    assert_eq!(
+        dst.iter_headers()?.collect::<Result<Vec<_>, _>>()?,
+        src.iter_headers()?.collect::<Result<Vec<_>, _>>()?
+    );
+
+    std::fs::remove_file(src_path)?;
+    for ext in ["", ".idx", ".pending"] {
+        let mut p = dst_path.clone().into_os_string();
+        p.push(ext);
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
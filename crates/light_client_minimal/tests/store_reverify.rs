@@ -0,0 +1,79 @@
+mod common;
+
+use common::{fixture_headers, temp_path};
+use light_client_minimal::store::Store;
+use light_client_minimal::store::file::FileStore;
+use light_client_minimal::sync::{VerifyHeaderError, reverify_range_parallel, reverify_store};
+
+/// Offset of the 32-byte nonce in a serialized header, after version, the three
+/// 32-byte hashes, time and bits.
+const NONCE_OFFSET: usize = 4 + 32 * 3 + 4 + 4;
+
+fn store_fixtures(name: &str) -> Result<(std::path::PathBuf, FileStore), std::io::Error> {
+    let path = temp_path(name);
+    let store = FileStore::new(&path)?;
+    for (height, header_hex) in fixture_headers() {
+        store.put(height, &header_hex)?;
+    }
+    Ok((path, store))
+}
+
+#[test]
+fn intact_store_reverifies() -> Result<(), Box<dyn std::error::Error>> {
+    let (path, store) = store_fixtures("reverify-intact.jsonl")?;
+    assert_eq!(reverify_store(&store)?, fixture_headers().len());
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn corrupted_nonce_is_flagged_at_its_height() -> Result<(), Box<dyn std::error::Error>> {
+    let (path, store) = store_fixtures("reverify-corrupt.jsonl")?;
+
+    let bad_height = 3_000_050;
+    let mut raw = hex::decode(store.get(bad_height)?.unwrap())?;
+    raw[NONCE_OFFSET] ^= 0x01;
+    store.put(bad_height, &hex::encode(raw))?;
+
+    match reverify_store(&store) {
+        Err(VerifyHeaderError::Stored { height, .. }) => assert_eq!(height, bad_height),
+        other => panic!("expected a stored-header failure, got {other:?}"),
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn parallel_reverify_checks_each_height_in_range() -> Result<(), Box<dyn std::error::Error>> {
+    let (path, store) = store_fixtures("reverify-parallel.jsonl")?;
+    let (start, end) = (3_000_000, 3_000_099);
+
+    let results = reverify_range_parallel(&store, start, end, 4)?;
+    assert_eq!(results.len(), 100);
+    assert_eq!(
+        results.keys().copied().collect::<Vec<_>>(),
+        (start..=end).collect::<Vec<_>>()
+    );
+    for (height, result) in &results {
+        assert!(result.is_ok(), "height {height}: {result:?}");
+    }
+
+    // A bad header fails on its own, without stopping the others.
+    let bad_height = 3_000_050;
+    let mut raw = hex::decode(store.get(bad_height)?.unwrap())?;
+    raw[NONCE_OFFSET] ^= 0x01;
+    store.put(bad_height, &hex::encode(raw))?;
+
+    let results = reverify_range_parallel(&store, start, end, 4)?;
+    let failed: Vec<u32> = results
+        .iter()
+        .filter(|(_, result)| result.is_err())
+        .map(|(&height, _)| height)
+        .collect();
+    assert_eq!(failed, [bad_height]);
+    assert_eq!(results.len(), 100);
+    assert!(reverify_range_parallel(&store, end, start, 4)?.is_empty());
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
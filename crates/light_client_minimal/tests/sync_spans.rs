@@ -0,0 +1,57 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use common::{MockNode, require_cairo_program, temp_path};
+use light_client_minimal::net::rpc::RpcClient;
+use light_client_minimal::store::file::FileStore;
+use light_client_minimal::sync::{SyncOptions, sync_chain};
+use tracing::span;
+use tracing_subscriber::Registry;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+const PHASES: [&str; 4] = ["fetch", "verify_rust", "verify_cairo", "persist"];
+
+/// Records the name of every sync phase span as it is entered.
+#[derive(Clone, Default)]
+struct EnteredSpans(Arc<Mutex<Vec<&'static str>>>);
+
+impl<S: tracing::Subscriber + for<'a> LookupSpan<'a>> Layer<S> for EnteredSpans {
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let name = ctx.span(id).unwrap().name();
+        if PHASES.contains(&name) {
+            self.0.lock().unwrap().push(name);
+        }
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn sync_enters_phase_spans_in_order() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let entered = EnteredSpans::default();
+    let _guard = tracing::subscriber::set_default(Registry::default().with(entered.clone()));
+
+    let node = MockNode::start_with_tip(3_000_031).await;
+    let client = RpcClient::new(&node.url())?;
+    let path = temp_path("sync-spans.jsonl");
+    let store = FileStore::new(&path)?;
+    let opts = SyncOptions {
+        exit_at_tip: true,
+        poll_interval: Duration::from_millis(10),
+        ..SyncOptions::default()
+    };
+
+    sync_chain(&client, &store, 3_000_030, &opts).await?;
+
+    // The fetch future may be polled (and its span entered) more than once.
+    let mut names = entered.0.lock().unwrap().clone();
+    names.dedup();
+    let mut expected = [PHASES, PHASES].concat();
+    expected.push("fetch");
+    assert_eq!(names, expected);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::temp_path;
+use light_client_minimal::state::{STATE_VERSION, SyncState, SyncStateError};
+use zcash_crypto::{DifficultyContext, Network};
+use zcash_primitives::block::BlockHash;
+
+fn sample_state() -> SyncState {
+    let mut context = DifficultyContext::with_network(2_999_959, Network::Testnet);
+    for height in 2_999_960..3_000_000 {
+        context.push_header(height, 1_700_000_000 + height * 75, 0x1c0a_3e4f);
+    }
+    SyncState {
+        tip_height: context.tip_height,
+        tip_hash: BlockHash([0x5a; 32]),
+        context,
+    }
+}
+
+#[test]
+fn state_round_trips_through_a_file() -> Result<(), Box<dyn std::error::Error>> {
+    let path = temp_path("sync-state.bin");
+    let state = sample_state();
+
+    state.save(&path)?;
+    let loaded = SyncState::load(&path)?;
+
+    assert_eq!(loaded, state);
+    assert!(loaded.context.is_ready());
+    assert_eq!(loaded.context.total_work(), state.context.total_work());
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn other_formats_are_rejected() {
+    let bytes = sample_state().to_bytes();
+
+    let mut future = bytes.clone();
+    future[4] = STATE_VERSION + 1;
+    let err = SyncState::from_bytes(&future).unwrap_err();
+    assert!(matches!(err, SyncStateError::UnsupportedVersion(v) if v == STATE_VERSION + 1));
+    assert!(err.to_string().contains("not supported"), "{err}");
+
+    assert!(matches!(
+        SyncState::from_bytes(b"{\"height\":1}"),
+        Err(SyncStateError::NotAStateFile)
+    ));
+    assert!(matches!(
+        SyncState::from_bytes(&bytes[..bytes.len() - 3]),
+        Err(SyncStateError::Malformed(_))
+    ));
+}
@@ -0,0 +1,488 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::{MemorySource, MockNode, decode, fixture_headers, require_cairo_program, temp_path};
+use futures::StreamExt;
+use light_client_minimal::net::HeaderSource;
+use light_client_minimal::net::rpc::{RpcClient, RpcError};
+use light_client_minimal::state::SyncState;
+use light_client_minimal::store::Store;
+use light_client_minimal::store::file::FileStore;
+use light_client_minimal::sync::{
+    SyncOptions, VerifyHeaderError, VerifyTimings, header_stream, sync_chain, sync_chain_stream,
+    sync_chain_until, verify_header_timed,
+};
+use tokio::sync::Notify;
+use zcash_crypto::DifficultyContext;
+use zcash_primitives::block::{BlockHash, BlockHeader};
+
+fn fast_polling(exit_at_tip: bool) -> SyncOptions {
+    SyncOptions {
+        exit_at_tip,
+        poll_interval: Duration::from_millis(10),
+        max_poll_interval: Duration::from_millis(40),
+        ..SyncOptions::default()
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn sync_stops_at_tip_when_requested() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let node = MockNode::start_with_tip(3_000_033).await;
+    let client = RpcClient::new(&node.url())?;
+    let path = temp_path("sync-exit-at-tip.jsonl");
+    let store = FileStore::new(&path)?;
+
+    let last = sync_chain(&client, &store, 3_000_030, &fast_polling(true)).await?;
+
+    assert_eq!(last, Some(3_000_033));
+    assert_eq!(store.tip()?, Some(3_000_033));
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn sync_follows_an_advancing_tip() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let node = MockNode::start_with_tip(3_000_031).await;
+    let client = RpcClient::new(&node.url())?;
+    let path = temp_path("sync-follow-tip.jsonl");
+    let store = FileStore::new(&path)?;
+    let observer = FileStore::new(&path)?;
+
+    let opts = fast_polling(false);
+    let watch = async {
+        // Let the loop hit the tip and start polling before new blocks "arrive".
+        while observer.tip().unwrap() != Some(3_000_031) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        node.set_tip(3_000_034);
+        while observer.tip().unwrap() != Some(3_000_034) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    };
+
+    tokio::select! {
+        res = sync_chain(&client, &store, 3_000_030, &opts) => {
+            panic!("follow mode returned early: {res:?}")
+        }
+        _ = tokio::time::timeout(Duration::from_secs(60), watch) => {}
+    }
+
+    assert_eq!(store.tip()?, Some(3_000_034));
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn sync_stops_when_its_tip_is_disconnected() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let node = MockNode::start_with_tip(3_000_031).await;
+    let client = RpcClient::new(&node.url())?;
+    let path = temp_path("sync-disconnected.jsonl");
+    let store = FileStore::new(&path)?;
+    let observer = FileStore::new(&path)?;
+
+    let opts = fast_polling(false);
+    let reorg = async {
+        while observer.tip().unwrap() != Some(3_000_031) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        // The node now reports block 3000031 with -1 confirmations.
+        node.set_tip(3_000_030);
+        std::future::pending::<()>().await;
+    };
+
+    let res = tokio::select! {
+        res = sync_chain(&client, &store, 3_000_030, &opts) => res,
+        _ = tokio::time::timeout(Duration::from_secs(60), reorg) => panic!("sync kept polling"),
+    };
+
+    assert!(matches!(
+        res,
+        Err(VerifyHeaderError::Disconnected { height: 3_000_031 })
+    ));
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn header_on_another_parent_is_a_disconnect() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = fixture_headers();
+    let source = MemorySource::with_tip(3_000_034);
+    let path = temp_path("sync-other-parent.jsonl");
+    let store = FileStore::new(&path)?;
+    for (height, header_hex) in &fixtures[30..33] {
+        store.put(*height, header_hex)?;
+    }
+    // Block 3_000_033 as stored is not the one the node's 3_000_034 builds on, as if it had been
+    // replaced by a reorg after it was synced.
+    let mut stale = hex::decode(&fixtures[33].1)?;
+    stale[4] ^= 1;
+    store.put(3_000_033, &hex::encode(stale))?;
+
+    // The stored blocks are passed over unverified, so no Cairo run is needed to get here.
+    let opts = SyncOptions {
+        skip_existing: true,
+        ..fast_polling(true)
+    };
+    let res = sync_chain(&source, &store, 3_000_030, &opts).await;
+
+    assert!(
+        matches!(
+            res,
+            Err(VerifyHeaderError::Disconnected { height: 3_000_033 })
+        ),
+        "got {res:?}"
+    );
+    assert_eq!(store.tip()?, Some(3_000_033));
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn notification_wakes_the_follow_loop() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let source = MemorySource::with_tip(3_000_031);
+    let path = temp_path("sync-notified.jsonl");
+    let store = FileStore::new(&path)?;
+    let observer = FileStore::new(&path)?;
+
+    let notifier = Arc::new(Notify::new());
+    // Far too slow to pick up the new blocks within the test: only the notification can.
+    let opts = SyncOptions {
+        poll_interval: Duration::from_secs(600),
+        max_poll_interval: Duration::from_secs(600),
+        tip_notifier: Some(notifier.clone()),
+        ..SyncOptions::default()
+    };
+    let watch = async {
+        while observer.tip().unwrap() != Some(3_000_031) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let fetches = source.fetches();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // Waiting for the notifier, not polling the node.
+        assert!(source.fetches() <= fetches + 1);
+
+        source.set_tip(3_000_034);
+        notifier.notify_one();
+        while observer.tip().unwrap() != Some(3_000_034) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    };
+
+    tokio::select! {
+        res = sync_chain(&source, &store, 3_000_030, &opts) => {
+            panic!("follow mode returned early: {res:?}")
+        }
+        res = tokio::time::timeout(Duration::from_secs(60), watch) => res?,
+    }
+
+    assert_eq!(store.tip()?, Some(3_000_034));
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Raises `signal` while block `at` is being fetched, i.e. part-way through that block.
+struct SignalAt<'a> {
+    inner: MemorySource,
+    at: u32,
+    signal: &'a Notify,
+}
+
+impl HeaderSource for SignalAt<'_> {
+    async fn header_by_height(&self, h: u32) -> Result<BlockHeader, RpcError> {
+        if h == self.at {
+            self.signal.notify_one();
+        }
+        self.inner.header_by_height(h).await
+    }
+
+    async fn block_hash(&self, h: u32) -> Result<BlockHash, RpcError> {
+        self.inner.block_hash(h).await
+    }
+
+    async fn block_count(&self) -> Result<u64, RpcError> {
+        self.inner.block_count().await
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn shutdown_finishes_the_current_block() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let signal = Notify::new();
+    let source = SignalAt {
+        inner: MemorySource::with_tip(3_000_143),
+        at: 3_000_035,
+        signal: &signal,
+    };
+    let path = temp_path("sync-shutdown.jsonl");
+    let store = FileStore::new(&path)?;
+    let opts = fast_polling(false);
+
+    let sync = sync_chain_until(&source, &store, 3_000_030, &opts, signal.notified());
+    let last = tokio::time::timeout(Duration::from_secs(60), sync).await??;
+
+    assert_eq!(last, Some(3_000_035));
+    assert_eq!(store.tip()?, Some(3_000_035));
+    for height in 3_000_030..=3_000_035 {
+        assert!(store.get(height)?.is_some(), "block {height} missing");
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn stream_yields_verified_headers_without_storing() -> Result<(), Box<dyn std::error::Error>>
+{
+    require_cairo_program();
+    let source = MemorySource::with_tip(3_000_143);
+    let path = temp_path("sync-stream.jsonl");
+    let store = FileStore::new(&path)?;
+
+    let yielded: Vec<_> = sync_chain_stream(&source, &store, 3_000_030, &fast_polling(false))
+        .take(3)
+        .collect()
+        .await;
+
+    let mut heights = Vec::new();
+    for item in yielded {
+        let (height, header) = item?;
+        assert_eq!(header.hash(), source.block_hash(height).await?);
+        heights.push(height);
+    }
+    assert_eq!(heights, [3_000_030, 3_000_031, 3_000_032]);
+    // Storage is left to the caller.
+    assert_eq!(store.tip()?, None);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn header_stream_advances_its_context() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let node = MockNode::start_with_tip(3_000_143).await;
+    let client = RpcClient::new(&node.url())?;
+    let path = temp_path("header-stream.jsonl");
+    let store = FileStore::new(&path)?;
+
+    // Each item after the first only verifies if the context advanced past its predecessor.
+    let items: Vec<_> = header_stream(&client, &store, 3_000_100)
+        .take(5)
+        .collect()
+        .await;
+    // Without rebuilding it: `getblockhash` + `getblockheader` for the 28-header window and each
+    // of the five headers, plus `getblockchaininfo` and `getblockcount` once.
+    assert_eq!(node.calls(), 2 * (28 + 5) + 2);
+
+    let mut expected = 3_000_100;
+    for item in items {
+        let (height, header) = item?;
+        assert_eq!(height, expected);
+        assert_eq!(header.hash(), client.block_hash(height).await?);
+        expected += 1;
+    }
+    assert_eq!(expected, 3_000_105);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Puts the fixture headers up to and including `tip` into `store`, as an earlier run would have.
+fn store_fixtures_through(store: &FileStore, tip: u32) -> Result<(), Box<dyn std::error::Error>> {
+    for (height, header_hex) in fixture_headers() {
+        if height <= tip {
+            store.put(height, &header_hex)?;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn restart_seeds_the_context_from_the_store() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let source = MemorySource::with_tip(3_000_143);
+    let path = temp_path("sync-restart.jsonl");
+    let store = FileStore::new(&path)?;
+    store_fixtures_through(&store, 3_000_049)?;
+
+    let mut stream = Box::pin(sync_chain_stream(
+        &source,
+        &store,
+        3_000_030,
+        &fast_polling(false),
+    ));
+    let (height, _) = stream.next().await.expect("the stream yields")?;
+
+    assert_eq!(height, 3_000_050);
+    // The whole window came from the store: the only header fetched is the one yielded.
+    assert_eq!(source.fetches(), 1);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn crash_before_persisting_resumes_at_the_same_height()
+-> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let source = MemorySource::with_tip(3_000_143);
+    let path = temp_path("sync-crash.jsonl");
+    let store = FileStore::new(&path)?;
+    store_fixtures_through(&store, 3_000_049)?;
+
+    // Verified and yielded, but the process dies before the header reaches the store.
+    {
+        let mut stream = Box::pin(sync_chain_stream(
+            &source,
+            &store,
+            3_000_030,
+            &fast_polling(false),
+        ));
+        let (height, _) = stream.next().await.expect("the stream yields")?;
+        assert_eq!(height, 3_000_050);
+    }
+    assert_eq!(store.tip()?, Some(3_000_049));
+
+    // The restart picks up the lost header again, and the context stays in step with the store.
+    let resumed: Vec<_> = sync_chain_stream(&source, &store, 3_000_030, &fast_polling(false))
+        .take(2)
+        .collect()
+        .await;
+    let heights = resumed
+        .into_iter()
+        .map(|item| item.map(|(height, _)| height))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(heights, [3_000_050, 3_000_051]);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn rerun_with_skip_existing_verifies_nothing() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let source = MemorySource::with_tip(3_000_040);
+    let path = temp_path("sync-skip-existing.jsonl");
+    let store = FileStore::new(&path)?;
+    sync_chain(&source, &store, 3_000_030, &fast_polling(true)).await?;
+    let before = source.fetches();
+
+    let opts = SyncOptions {
+        skip_existing: true,
+        ..fast_polling(true)
+    };
+    let last = sync_chain(&source, &store, 3_000_030, &opts).await?;
+
+    assert_eq!(last, Some(3_000_040));
+    // Only the context window below 3_000_030 and the probe past the tip are fetched: none of
+    // the stored blocks is fetched (let alone verified) again.
+    assert_eq!(source.fetches() - before, 28 + 1);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn saved_state_replaces_the_context_rebuild() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let source = MemorySource::with_tip(3_000_033);
+    let path = temp_path("sync-with-state.jsonl");
+    let state_path = temp_path("sync-with-state.bin");
+    let store = FileStore::new(&path)?;
+    let opts = SyncOptions {
+        state: Some(state_path.clone()),
+        ..fast_polling(true)
+    };
+
+    sync_chain(&source, &store, 3_000_030, &opts).await?;
+    // Saved on reaching the tip.
+    assert_eq!(SyncState::load(&state_path)?.tip_height, 3_000_033);
+
+    source.set_tip(3_000_036);
+    let before = source.fetches();
+    let last = sync_chain(&source, &store, 3_000_030, &opts).await?;
+
+    assert_eq!(last, Some(3_000_036));
+    // The three new headers and the probe past the tip; no 28-header context window.
+    assert_eq!(source.fetches() - before, 3 + 1);
+    assert_eq!(SyncState::load(&state_path)?.tip_height, 3_000_036);
+    std::fs::remove_file(path)?;
+    std::fs::remove_file(state_path)?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+async fn state_not_matching_the_store_tip_is_ignored() -> Result<(), Box<dyn std::error::Error>> {
+    require_cairo_program();
+    let source = MemorySource::with_tip(3_000_033);
+    let path = temp_path("sync-stale-state.jsonl");
+    let state_path = temp_path("sync-stale-state.bin");
+    let store = FileStore::new(&path)?;
+    let opts = SyncOptions {
+        state: Some(state_path.clone()),
+        ..fast_polling(true)
+    };
+    sync_chain(&source, &store, 3_000_030, &opts).await?;
+
+    // As if the store had since been replaced by one on another chain.
+    let mut state = SyncState::load(&state_path)?;
+    state.tip_hash = BlockHash([0; 32]);
+    state.save(&state_path)?;
+    source.set_tip(3_000_036);
+    let before = source.fetches();
+    sync_chain(&source, &store, 3_000_030, &opts).await?;
+
+    // The context is rebuilt instead of taken from the state: the 24 headers of its window
+    // below the stored 3_000_030..=3_000_033 are fetched, then the three new ones and the probe.
+    assert_eq!(source.fetches() - before, 24 + 3 + 1);
+    std::fs::remove_file(path)?;
+    std::fs::remove_file(state_path)?;
+    Ok(())
+}
+
+#[test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+fn timed_verification_measures_every_phase() {
+    require_cairo_program();
+    let headers: Vec<(u32, BlockHeader)> = fixture_headers()
+        .into_iter()
+        .map(|(height, header_hex)| (height, decode(&header_hex)))
+        .collect();
+    let (window, rest) = headers.split_at(DifficultyContext::REQUIRED_WINDOW);
+    let mut ctx = DifficultyContext::new(window[0].0 - 1);
+    for (height, header) in window {
+        ctx.push_header(*height, header.time, header.bits);
+    }
+
+    let (height, header) = &rest[0];
+    let timings = verify_header_timed(header, *height, &ctx, false).unwrap();
+    let VerifyTimings {
+        equihash,
+        filter,
+        context,
+        cairo,
+    } = timings;
+    for (phase, elapsed) in [
+        ("equihash", equihash),
+        ("filter", filter),
+        ("context", context),
+        ("cairo", cairo),
+    ] {
+        assert!(!elapsed.is_zero(), "{phase} was not timed: {timings}");
+    }
+    // Unlike `verify_pow_with_context`, the header is not committed to the context.
+    assert_eq!(ctx.tip_height, *height - 1);
+}
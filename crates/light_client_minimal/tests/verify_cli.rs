@@ -0,0 +1,70 @@
+mod common;
+
+use common::{MockNode, fixture_headers};
+use light_client_minimal::net::rpc::RpcClient;
+use light_client_minimal::sync::{report_header_at, report_header_bytes, report_header_bytes_at};
+
+#[tokio::test]
+async fn verify_height_reports_matching_nbits() -> Result<(), Box<dyn std::error::Error>> {
+    let node = MockNode::start().await;
+    let client = RpcClient::new(&node.url())?;
+
+    let report = report_header_at(&client, 3_000_100).await?;
+
+    assert!(report.passed(), "{report}");
+    assert_eq!(report.height, Some(3_000_100));
+    assert_eq!(report.expected_bits, Some(report.header_bits));
+    assert!(report.to_string().contains("PASS"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn verify_height_without_context_is_an_error() -> Result<(), Box<dyn std::error::Error>> {
+    let node = MockNode::start().await;
+    let client = RpcClient::new(&node.url())?;
+
+    // The mock node only serves 3000000..=3000143, so the 28-header window is missing.
+    assert!(report_header_at(&client, 3_000_010).await.is_err());
+    Ok(())
+}
+
+#[test]
+fn verify_hex_passes_and_detects_tampering() -> Result<(), Box<dyn std::error::Error>> {
+    let (_, header_hex) = &fixture_headers()[0];
+    let mut raw = hex::decode(header_hex)?;
+
+    let report = report_header_bytes(&raw)?;
+    assert!(report.passed(), "{report}");
+    assert_eq!(report.height, None);
+    assert_eq!(report.expected_bits, None);
+
+    // Flip a nonce byte (offset 108..140) so the Equihash solution no longer matches.
+    raw[108] ^= 0x01;
+    let report = report_header_bytes(&raw)?;
+    assert!(!report.passed());
+    assert!(report.to_string().contains("FAIL"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn verify_hex_at_height_uses_node_context() -> Result<(), Box<dyn std::error::Error>> {
+    let node = MockNode::start().await;
+    let client = RpcClient::new(&node.url())?;
+    let (height, header_hex) = fixture_headers()
+        .into_iter()
+        .find(|(h, _)| *h == 3_000_100)
+        .unwrap();
+    let raw = hex::decode(header_hex)?;
+
+    let report = report_header_bytes_at(&client, &raw, height).await?;
+    assert!(report.passed(), "{report}");
+    assert_eq!(report.height, Some(height));
+    assert_eq!(report.expected_bits, Some(report.header_bits));
+
+    // The same header claimed one height later no longer fits the node's chain.
+    let report = report_header_bytes_at(&client, &raw, height + 1).await?;
+    assert!(!report.passed());
+    assert_ne!(report.expected_bits, Some(report.header_bits));
+    assert!(report.to_string().contains("BitsMismatch"), "{report}");
+    Ok(())
+}
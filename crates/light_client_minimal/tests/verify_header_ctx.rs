@@ -0,0 +1,48 @@
+mod common;
+
+use common::MockNode;
+use light_client_minimal::net::rpc::RpcClient;
+use light_client_minimal::sync::{verify_header, verify_header_with_ctx};
+use zcash_crypto::{DifficultyContext, Network};
+
+#[tokio::test]
+async fn shared_context_avoids_refetching() -> Result<(), Box<dyn std::error::Error>> {
+    let heights = [3_000_100, 3_000_101, 3_000_102];
+
+    let node = MockNode::start().await;
+    let client = RpcClient::new(&node.url())?;
+    for h in heights {
+        verify_header(&client, h).await?;
+    }
+    let independent_calls = node.calls();
+
+    let node = MockNode::start().await;
+    let client = RpcClient::new(&node.url())?;
+    let mut ctx = DifficultyContext::with_network(heights[0] - 1, Network::Mainnet);
+    for h in heights {
+        verify_header_with_ctx(&client, h, &mut ctx).await?;
+    }
+    let shared_calls = node.calls();
+
+    assert_eq!(ctx.tip_height, heights[2]);
+    // 28 context headers + 3 target headers, each a getblockhash + getblock pair.
+    assert_eq!(shared_calls, 2 * (28 + 3));
+    assert!(shared_calls * 2 < independent_calls);
+    Ok(())
+}
+
+#[tokio::test]
+async fn context_for_another_height_is_rebuilt() -> Result<(), Box<dyn std::error::Error>> {
+    let node = MockNode::start().await;
+    let client = RpcClient::new(&node.url())?;
+    let mut ctx = DifficultyContext::with_network(3_000_099, Network::Mainnet);
+    verify_header_with_ctx(&client, 3_000_100, &mut ctx).await?;
+    let calls = node.calls();
+
+    // Ready, but at 3_000_100 rather than the 3_000_109 that 3_000_110 builds on.
+    verify_header_with_ctx(&client, 3_000_110, &mut ctx).await?;
+
+    assert_eq!(ctx.tip_height, 3_000_110);
+    assert_eq!(node.calls() - calls, 2 * (28 + 1));
+    Ok(())
+}
@@ -28,6 +28,33 @@ pub enum ProofFormat {
     /// Array of field elements serialized as hex strings.
     /// Compatible with `scarb execute`
     CairoSerde,
+    /// `bincode` encoding of the proof, for storing many proofs.
+    /// Each field element takes its 4 raw bytes instead of up to 10 characters of JSON, and
+    /// hashes 32 bytes instead of a 32-number array, so files are smaller than [`Self::Json`];
+    /// [`ProofStats::bytes`] reports the size each proof was written at.
+    Binary,
+}
+
+impl ProofFormat {
+    /// Extension of the file [`generate_proof`] writes by default in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ProofFormat::Json | ProofFormat::CairoSerde => "json",
+            ProofFormat::Binary => "bin",
+        }
+    }
+}
+
+/// Size and serialization cost of a written proof.
+#[derive(Debug, Clone, Copy)]
+pub struct ProofStats {
+    /// Size of the proof file in bytes.
+    pub bytes: usize,
+    /// Number of field elements in the Cairo-serialized proof.
+    pub field_elements: usize,
+    /// Time spent encoding the proof in its [`ProofFormat`], in milliseconds; excludes counting
+    /// the field elements and writing the file.
+    pub serialize_ms: u128,
 }
 
 #[derive(Debug, Error)]
@@ -38,27 +65,58 @@ pub enum Error {
     Proving(#[from] ProvingError),
     #[error("Serialization failed: {0}")]
     Serializing(#[from] sonic_rs::error::Error),
+    #[error("Binary serialization failed: {0}")]
+    Binary(#[from] bincode::Error),
     #[error("Verification failed: {0}")]
     Verification(#[from] CairoVerificationError),
-    #[error("VM import failed: {0}")]
-    VmImport(#[from] VmImportError),
+    #[error("Trace file {} unusable: {reason}", path.display())]
+    TraceFile { path: PathBuf, reason: String },
+    #[error(
+        "VM import failed (pub: {}, priv: {}): {source}",
+        pub_json.display(),
+        priv_json.display()
+    )]
+    VmImport {
+        pub_json: PathBuf,
+        priv_json: PathBuf,
+        source: VmImportError,
+    },
     #[error("File IO failed: {0}")]
     File(#[from] IoErrorWithPath),
+    #[error("Aggregate proof requires at least one input")]
+    EmptyAggregate,
+    #[error("Proofs in {0:?} format cannot be read back for verification")]
+    UnreadableFormat(ProofFormat),
 }
 
-pub fn generate_proof(
-    pub_json: &Path,
-    priv_json: &Path,
-    verify: Option<bool>,
-    proof_format: Option<ProofFormat>,
-    proof_path: Option<PathBuf>,
-) -> Result<PathBuf, Error> {
-    let _span = span!(Level::INFO, "run").entered();
+/// Fails with [`Error::TraceFile`] naming `path` if it is missing or empty.
+fn check_trace_file(path: &Path) -> Result<(), Error> {
+    let reason = match std::fs::metadata(path) {
+        Ok(meta) if meta.len() > 0 => return Ok(()),
+        Ok(_) => "file is empty".to_string(),
+        Err(e) => e.to_string(),
+    };
+    Err(Error::TraceFile {
+        path: path.to_path_buf(),
+        reason,
+    })
+}
 
-    let vm_output: ProverInput = adapt_vm_output(pub_json, priv_json)?;
+/// Runs `adapt_vm_output`, first checking both inputs so a missing or truncated trace is
+/// reported by path, and labelling adapter failures with the files involved.
+fn import_vm_output(pub_json: &Path, priv_json: &Path) -> Result<ProverInput, Error> {
+    check_trace_file(pub_json)?;
+    check_trace_file(priv_json)?;
+    adapt_vm_output(pub_json, priv_json).map_err(|source| Error::VmImport {
+        pub_json: pub_json.to_path_buf(),
+        priv_json: priv_json.to_path_buf(),
+        source,
+    })
+}
 
-    // Hardcode prover parameters
-    let proof_params = ProverParameters {
+/// Prover parameters shared by single and aggregate proofs.
+fn prover_parameters() -> ProverParameters {
+    ProverParameters {
         channel_hash: ChannelHash::Blake2s,
         pcs_config: PcsConfig {
             // Stay within 500ms on M3.
@@ -74,31 +132,121 @@ pub fn generate_proof(
             },
         },
         preprocessed_trace: PreProcessedTraceVariant::CanonicalWithoutPedersen,
-    };
+    }
+}
+
+pub fn generate_proof(
+    pub_json: &Path,
+    priv_json: &Path,
+    verify: Option<bool>,
+    proof_format: Option<ProofFormat>,
+    proof_path: Option<PathBuf>,
+) -> Result<(PathBuf, ProofStats), Error> {
+    let _span = span!(Level::INFO, "run").entered();
+
+    let vm_output = import_vm_output(pub_json, priv_json)?;
+
+    let proof_params = prover_parameters();
 
     let run_inner_fn = match proof_params.channel_hash {
         ChannelHash::Blake2s => run_inner::<Blake2sMerkleChannel>,
         ChannelHash::Poseidon252 => run_inner::<Poseidon252MerkleChannel>,
     };
 
+    let proof_format = proof_format.unwrap_or(ProofFormat::Json);
     let out_dir = pub_json.parent().unwrap_or_else(|| Path::new("."));
-    let proof_path = proof_path.unwrap_or_else(|| out_dir.join("proof.json"));
+    let proof_path =
+        proof_path.unwrap_or_else(|| out_dir.join(format!("proof.{}", proof_format.extension())));
 
-    run_inner_fn(
+    let stats = run_inner_fn(
         vm_output,
         proof_params.pcs_config,
         proof_params.preprocessed_trace,
         verify.unwrap_or(false),
         proof_path.clone(),
-        proof_format.unwrap_or(ProofFormat::Json),
+        proof_format,
     )?;
 
+    Ok((proof_path, stats))
+}
+
+/// Verifies a proof [`generate_proof`] wrote with the Blake2s channel, as `Json` or `Binary`.
+///
+/// `CairoSerde` output is a flat list of field elements meant for Cairo verifiers, and is
+/// rejected with [`Error::UnreadableFormat`].
+pub fn verify_proof(proof_path: &Path, format: ProofFormat) -> Result<(), Error> {
+    let proof = match format {
+        ProofFormat::Json => sonic_rs::from_str(&std::fs::read_to_string(proof_path)?)?,
+        ProofFormat::Binary => bincode::deserialize(&std::fs::read(proof_path)?)?,
+        ProofFormat::CairoSerde => return Err(Error::UnreadableFormat(format)),
+    };
+    verify_cairo::<Blake2sMerkleChannel>(proof, prover_parameters().preprocessed_trace)?;
+    Ok(())
+}
+
+/// Proves several block executions and writes them as a single aggregate artifact.
+///
+/// `stwo_cairo_prover` does not expose recursive aggregation, so this is a
+/// sequential fold: each `(pub.json, priv.json)` pair is proven in order and the
+/// resulting proofs are written together as one JSON array. The artifact is then
+/// checked in a single call to [`verify_aggregate_proof`]. Storage is one file
+/// instead of one per block, but its size and verification time still grow
+/// linearly with the number of blocks.
+///
+/// Defaults to `aggregate_proof.json` next to the first `pub.json`.
+pub fn generate_aggregate_proof(
+    inputs: &[(&Path, &Path)],
+    proof_path: Option<PathBuf>,
+) -> Result<PathBuf, Error> {
+    let _span = span!(Level::INFO, "aggregate").entered();
+
+    let (first_pub, _) = inputs.first().ok_or(Error::EmptyAggregate)?;
+    let proof_path = proof_path.unwrap_or_else(|| {
+        first_pub
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("aggregate_proof.json")
+    });
+
+    let proof_params = prover_parameters();
+    let mut proofs = Vec::with_capacity(inputs.len());
+    for (pub_json, priv_json) in inputs {
+        let vm_output = import_vm_output(pub_json, priv_json)?;
+        proofs.push(prove_cairo::<Blake2sMerkleChannel>(
+            vm_output,
+            proof_params.pcs_config,
+            proof_params.preprocessed_trace,
+        )?);
+    }
+
+    let mut proof_file = create_file(&proof_path)?;
+    proof_file.write_all(sonic_rs::to_string_pretty(&proofs)?.as_bytes())?;
+
     Ok(proof_path)
 }
 
+/// Verifies every proof in an artifact written by [`generate_aggregate_proof`].
+///
+/// Returns the number of block proofs verified.
+pub fn verify_aggregate_proof(proof_path: &Path) -> Result<usize, Error> {
+    let contents = std::fs::read_to_string(proof_path)?;
+    let proofs: Vec<_> = sonic_rs::from_str(&contents)?;
+    if proofs.is_empty() {
+        return Err(Error::EmptyAggregate);
+    }
+
+    let preprocessed_trace = prover_parameters().preprocessed_trace;
+    let count = proofs.len();
+    for proof in proofs {
+        verify_cairo::<Blake2sMerkleChannel>(proof, preprocessed_trace)?;
+    }
+    Ok(count)
+}
+
 /// Generates proof given the Cairo VM output and prover config/parameters.
 /// Serializes the proof as JSON and write to the output path.
 /// Verifies the proof in case the respective flag is set.
+/// Returns the size statistics of the written proof.
 fn run_inner<MC: MerkleChannel>(
     vm_output: ProverInput,
     pcs_config: PcsConfig,
@@ -106,7 +254,7 @@ fn run_inner<MC: MerkleChannel>(
     verify: bool,
     proof_path: PathBuf,
     proof_format: ProofFormat,
-) -> Result<(), Error>
+) -> Result<ProofStats, Error>
 where
     SimdBackend: BackendForChannel<MC>,
     MC::H: Serialize,
@@ -116,27 +264,41 @@ where
     let mut proof_file = create_file(&proof_path)?;
 
     let span = span!(Level::INFO, "Serialize proof").entered();
-    match proof_format {
-        ProofFormat::Json => {
-            proof_file.write_all(sonic_rs::to_string_pretty(&proof)?.as_bytes())?;
-        }
-        ProofFormat::CairoSerde => {
-            let mut serialized: Vec<starknet_ff::FieldElement> = Vec::new();
-            CairoSerialize::serialize(&proof, &mut serialized);
+    let mut serialized: Vec<starknet_ff::FieldElement> = Vec::new();
+    CairoSerialize::serialize(&proof, &mut serialized);
+    let field_elements = serialized.len();
 
+    let serialize_start = std::time::Instant::now();
+    let contents = match proof_format {
+        ProofFormat::Json => sonic_rs::to_string_pretty(&proof)?.into_bytes(),
+        ProofFormat::CairoSerde => {
             let hex_strings: Vec<String> = serialized
                 .into_iter()
                 .map(|felt| format!("0x{felt:x}"))
                 .collect();
 
-            proof_file.write_all(sonic_rs::to_string_pretty(&hex_strings)?.as_bytes())?;
+            sonic_rs::to_string_pretty(&hex_strings)?.into_bytes()
         }
-    }
+        ProofFormat::Binary => bincode::serialize(&proof)?,
+    };
+    let serialize_ms = serialize_start.elapsed().as_millis();
+    proof_file.write_all(&contents)?;
+    let stats = ProofStats {
+        bytes: contents.len(),
+        field_elements,
+        serialize_ms,
+    };
     span.exit();
+    tracing::info!(
+        "Proof size: {} bytes, {} field elements, serialized in {} ms",
+        stats.bytes,
+        stats.field_elements,
+        stats.serialize_ms
+    );
     if verify {
         verify_cairo::<MC>(proof, preprocessed_trace)?;
         tracing::info!("Proof verified successfully");
     }
 
-    Ok(())
+    Ok(stats)
 }
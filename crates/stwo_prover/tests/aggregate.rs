@@ -0,0 +1,46 @@
+use std::env;
+use std::path::PathBuf;
+
+use stwo_prover::{generate_aggregate_proof, verify_aggregate_proof};
+
+/// Proves two block traces into one aggregate artifact and verifies it once.
+///
+/// This test needs Cairo trace outputs (`pub.json`/`priv.json`), e.g. the
+/// `output/block_{height}` directories written by the light client. To use it:
+/// - set `STWO_TRACE_DIRS` to two comma-separated trace directories;
+/// - run: `cargo test --release -p stwo_prover aggregate_two_blocks -- --ignored`.
+#[test]
+#[ignore = "needs STWO_TRACE_DIRS"]
+fn aggregate_two_blocks() -> Result<(), Box<dyn std::error::Error>> {
+    let dirs: Vec<PathBuf> = env::var("STWO_TRACE_DIRS")?
+        .split(',')
+        .map(PathBuf::from)
+        .collect();
+    assert_eq!(
+        dirs.len(),
+        2,
+        "STWO_TRACE_DIRS must name two trace directories"
+    );
+
+    let files: Vec<(PathBuf, PathBuf)> = dirs
+        .iter()
+        .map(|d| (d.join("pub.json"), d.join("priv.json")))
+        .collect();
+    let inputs: Vec<_> = files
+        .iter()
+        .map(|(p, q)| (p.as_path(), q.as_path()))
+        .collect();
+
+    let out = env::temp_dir().join("zoro_aggregate_proof.json");
+    let proof_path = generate_aggregate_proof(&inputs, Some(out))?;
+    assert_eq!(verify_aggregate_proof(&proof_path)?, 2);
+    Ok(())
+}
+
+#[test]
+fn aggregate_rejects_empty_inputs() {
+    assert!(matches!(
+        generate_aggregate_proof(&[], None),
+        Err(stwo_prover::Error::EmptyAggregate)
+    ));
+}
@@ -0,0 +1,47 @@
+use std::env;
+use std::path::PathBuf;
+
+use stwo_prover::{generate_proof, verify_proof, ProofFormat};
+
+/// Proves one block trace as `Json` and as `Binary`, and verifies both files read back.
+///
+/// Requires `STWO_TRACE_DIRS` to name at least one directory holding Cairo trace
+/// outputs (`pub.json`/`priv.json`), so it only runs with `--ignored`.
+#[test]
+#[ignore = "needs STWO_TRACE_DIRS"]
+fn binary_proof_verifies_like_json() -> Result<(), Box<dyn std::error::Error>> {
+    let dirs = env::var("STWO_TRACE_DIRS")?;
+    let dir = PathBuf::from(dirs.split(',').next().unwrap_or_default());
+
+    let mut sizes = Vec::new();
+    for format in [ProofFormat::Json, ProofFormat::Binary] {
+        let out = env::temp_dir().join(format!("zoro_proof_format.{}", format.extension()));
+        let (proof_path, stats) = generate_proof(
+            &dir.join("pub.json"),
+            &dir.join("priv.json"),
+            Some(false),
+            Some(format),
+            Some(out),
+        )?;
+        verify_proof(&proof_path, format)?;
+        sizes.push(stats.bytes);
+    }
+
+    let (json, binary) = (sizes[0], sizes[1]);
+    assert!(
+        binary < json,
+        "proof size: json {json} bytes, binary {binary} bytes"
+    );
+    Ok(())
+}
+
+#[test]
+fn cairo_serde_proofs_are_not_read_back() {
+    assert!(matches!(
+        verify_proof(
+            &env::temp_dir().join("zoro_missing_proof.json"),
+            ProofFormat::CairoSerde
+        ),
+        Err(stwo_prover::Error::UnreadableFormat(_))
+    ));
+}
@@ -0,0 +1,29 @@
+use std::env;
+use std::path::PathBuf;
+
+use stwo_prover::{generate_proof, ProofFormat};
+
+/// Proves a single block trace and checks the reported proof statistics.
+///
+/// Requires `STWO_TRACE_DIRS` to name at least one directory holding Cairo trace
+/// outputs (`pub.json`/`priv.json`), so it only runs with `--ignored`.
+#[test]
+#[ignore = "needs STWO_TRACE_DIRS"]
+fn proof_stats_are_populated() -> Result<(), Box<dyn std::error::Error>> {
+    let dirs = env::var("STWO_TRACE_DIRS")?;
+    let dir = PathBuf::from(dirs.split(',').next().unwrap_or_default());
+
+    let out = env::temp_dir().join("zoro_proof_stats.json");
+    let (proof_path, stats) = generate_proof(
+        &dir.join("pub.json"),
+        &dir.join("priv.json"),
+        Some(false),
+        Some(ProofFormat::CairoSerde),
+        Some(out),
+    )?;
+
+    assert!(stats.bytes > 0);
+    assert!(stats.field_elements > 0);
+    assert_eq!(std::fs::metadata(proof_path)?.len() as usize, stats.bytes);
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use std::env;
+use std::fs;
+
+use stwo_prover::{generate_proof, Error};
+
+#[test]
+fn missing_priv_json_is_named() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = env::temp_dir().join("zoro_missing_priv");
+    fs::create_dir_all(&dir)?;
+    let pub_json = dir.join("pub.json");
+    let priv_json = dir.join("priv.json");
+    fs::write(&pub_json, "{}")?;
+    let _ = fs::remove_file(&priv_json);
+
+    let err = generate_proof(&pub_json, &priv_json, None, None, None).unwrap_err();
+    assert!(matches!(&err, Error::TraceFile { path, .. } if *path == priv_json));
+    assert!(err.to_string().contains("priv.json"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn empty_pub_json_is_named() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = env::temp_dir().join("zoro_empty_pub");
+    fs::create_dir_all(&dir)?;
+    let pub_json = dir.join("pub.json");
+    let priv_json = dir.join("priv.json");
+    fs::write(&pub_json, "")?;
+    fs::write(&priv_json, "{}")?;
+
+    let err = generate_proof(&pub_json, &priv_json, None, None, None).unwrap_err();
+    assert!(matches!(&err, Error::TraceFile { path, .. } if *path == pub_json));
+    assert!(err.to_string().contains("empty"), "{err}");
+    Ok(())
+}
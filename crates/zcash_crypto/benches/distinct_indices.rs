@@ -0,0 +1,49 @@
+//! Compares duplicate-index detection strategies across Equihash `k`.
+//!
+//! Run with `cargo bench -p zcash_crypto --bench distinct_indices`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use zcash_crypto::equihash::indices_are_distinct;
+
+/// The former check: every merge compared its two siblings' index lists pairwise.
+fn pairwise_per_level(indices: &[u32]) -> bool {
+    let mut width = 1;
+    while width < indices.len() {
+        for pair in indices.chunks_exact(2 * width) {
+            let (a, b) = pair.split_at(width);
+            if a.iter().any(|i| b.contains(i)) {
+                return false;
+            }
+        }
+        width *= 2;
+    }
+    true
+}
+
+fn time_per_call(iters: u32, f: impl Fn() -> bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iters {
+        black_box(f());
+    }
+    start.elapsed() / iters
+}
+
+fn main() {
+    println!(
+        "{:>3} {:>8} {:>14} {:>14}",
+        "k", "indices", "pairwise", "sort+scan"
+    );
+    for k in 3..=9 {
+        // Distinct, spread-out 21-bit indices: the worst case for both checks.
+        let indices: Vec<u32> = (0..1u32 << k)
+            .map(|i| i.wrapping_mul(2_654_435_761) >> 11)
+            .collect();
+        assert!(pairwise_per_level(&indices) && indices_are_distinct(&indices));
+
+        let iters = 20_000 >> k.min(8);
+        let pairwise = time_per_call(iters, || pairwise_per_level(black_box(&indices)));
+        let sorted = time_per_call(iters, || indices_are_distinct(black_box(&indices)));
+        println!("{k:>3} {:>8} {pairwise:>14?} {sorted:>14?}", indices.len());
+    }
+}
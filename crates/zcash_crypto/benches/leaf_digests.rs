@@ -0,0 +1,103 @@
+//! Counts and times the BLAKE2b leaf digests of Equihash verification over the mainnet
+//! fixtures, per leaf (what the verifier does) and once per distinct counter (a digest cache).
+//!
+//! Leaf `i` hashes counter `i / indices_per_hash_output()`, so two leaves share a digest only
+//! when a solution holds both `2c` and `2c + 1`. Indices are spread over 2^21 values, so that
+//! is rare and the cache saves next to nothing.
+//!
+//! Run with `cargo bench -p zcash_crypto --bench leaf_digests`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use blake2b_simd::{Params as Blake2bParams, State as Blake2bState};
+use zcash_crypto::equihash::{Params, indices_from_minimal};
+
+const HEADERS_JSONL: &str = include_str!("../../../data/headers.jsonl");
+
+/// Offset of the Equihash solution: 140-byte powheader plus the 3-byte compactSize.
+const SOLUTION_OFFSET: usize = 143;
+
+/// Base state after absorbing the powheader, as the verifier builds it.
+fn base_state(p: &Params, powheader: &[u8]) -> Blake2bState {
+    let mut personalization = *b"ZcashPoW\0\0\0\0\0\0\0\0";
+    personalization[8..12].copy_from_slice(&p.n().to_le_bytes());
+    personalization[12..16].copy_from_slice(&p.k().to_le_bytes());
+    let mut state = Blake2bParams::new()
+        .hash_length(p.hash_output() as usize)
+        .personal(&personalization)
+        .to_state();
+    state.update(powheader);
+    state
+}
+
+fn digest(state: &Blake2bState, counter: u32) -> [u8; 64] {
+    let mut state = state.clone();
+    state.update(&counter.to_le_bytes());
+    let mut out = [0; 64];
+    let hash = state.finalize();
+    out[..hash.as_bytes().len()].copy_from_slice(hash.as_bytes());
+    out
+}
+
+/// Best of several runs, so the first one's warm-up does not count against either strategy.
+fn time(f: impl Fn()) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let p = Params::new(200, 9).unwrap();
+    let per = p.indices_per_hash_output();
+    let headers: Vec<(Blake2bState, Vec<u32>)> = HEADERS_JSONL
+        .lines()
+        .map(|line| {
+            let header_hex = line.split('"').nth(5).expect("header_hex field");
+            let raw = hex::decode(header_hex).unwrap();
+            let indices = indices_from_minimal(p, &raw[SOLUTION_OFFSET..]).unwrap();
+            (base_state(&p, &raw[..140]), indices)
+        })
+        .collect();
+
+    let leaves: usize = headers.iter().map(|(_, indices)| indices.len()).sum();
+    let distinct: usize = headers
+        .iter()
+        .map(|(_, indices)| {
+            let mut counters: Vec<u32> = indices.iter().map(|i| i / per).collect();
+            counters.sort_unstable();
+            counters.dedup();
+            counters.len()
+        })
+        .sum();
+
+    let per_leaf = time(|| {
+        for (state, indices) in &headers {
+            for &i in indices {
+                black_box(digest(state, i / per));
+            }
+        }
+    });
+    let cached = time(|| {
+        for (state, indices) in &headers {
+            let mut counters: Vec<u32> = indices.iter().map(|i| i / per).collect();
+            counters.sort_unstable();
+            counters.dedup();
+            let cache: Vec<[u8; 64]> = counters.iter().map(|&c| digest(state, c)).collect();
+            for &i in indices {
+                let slot = counters.binary_search(&(i / per)).unwrap();
+                black_box(&cache[slot]);
+            }
+        }
+    });
+
+    let n = headers.len() as u32;
+    println!("{n} headers, {leaves} leaves");
+    println!("{:>10} {:>12} {:>14}", "strategy", "BLAKE2b", "per header");
+    println!("{:>10} {leaves:>12} {:>14?}", "per leaf", per_leaf / n);
+    println!("{:>10} {distinct:>12} {:>14?}", "cached", cached / n);
+}
@@ -0,0 +1,14 @@
+#![no_main]
+//! Arbitrary `(n, k, powheader, solution)` must only ever produce `Ok`/`Err` from either
+//! verifier, never a panic, and both verifiers must agree.
+use libfuzzer_sys::fuzz_target;
+use zcash_crypto::{
+    verify_equihash_solution_streaming_with_params, verify_equihash_solution_with_params,
+};
+
+fuzz_target!(|input: (u32, u32, Vec<u8>, Vec<u8>)| {
+    let (n, k, powheader, solution) = input;
+    let recursive = verify_equihash_solution_with_params(n, k, &powheader, &solution);
+    let streaming = verify_equihash_solution_streaming_with_params(n, k, &powheader, &solution);
+    assert_eq!(recursive.map_err(|e| e.0), streaming.map_err(|e| e.0));
+});
@@ -0,0 +1,43 @@
+#![no_main]
+//! `indices_from_minimal` (and the `expand_array` bit unpacking under it) must never panic on
+//! arbitrary input, and whatever it decodes must re-pack to the exact input bytes. Decoded
+//! solutions are then run through both verifiers, which must agree.
+//!
+//! Unlike `equihash_verify`, `(n, k)` is derived from a small `(k, collision length)` space so
+//! most inputs are well-formed parameters and the fuzzer only has to find the solution length.
+use libfuzzer_sys::fuzz_target;
+use zcash_crypto::equihash::{Params, indices_from_minimal};
+use zcash_crypto::{
+    verify_equihash_solution_streaming_with_params, verify_equihash_solution_with_params,
+};
+
+fuzz_target!(|input: (u8, u8, Vec<u8>)| {
+    let (k, collision_bits, minimal) = input;
+    let k = u32::from(k % 16);
+    let n = u32::from(collision_bits % 32) * (k + 1);
+    let Some(p) = Params::new(n, k) else {
+        return;
+    };
+    let Some(indices) = indices_from_minimal(p, &minimal) else {
+        return;
+    };
+
+    let bit_len = p.collision_bit_length() + 1;
+    assert_eq!(indices.len(), 1 << k);
+    let mut repacked = Vec::with_capacity(minimal.len());
+    let (mut acc, mut acc_bits) = (0u64, 0);
+    for &i in &indices {
+        assert!(u64::from(i) < 1 << bit_len);
+        acc = (acc << bit_len) | u64::from(i);
+        acc_bits += bit_len;
+        while acc_bits >= 8 {
+            acc_bits -= 8;
+            repacked.push((acc >> acc_bits) as u8);
+        }
+    }
+    assert_eq!(repacked, minimal);
+
+    let recursive = verify_equihash_solution_with_params(n, k, b"", &minimal);
+    let streaming = verify_equihash_solution_streaming_with_params(n, k, b"", &minimal);
+    assert_eq!(recursive.map_err(|e| e.0), streaming.map_err(|e| e.0));
+});
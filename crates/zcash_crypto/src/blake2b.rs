@@ -0,0 +1,137 @@
+//! Personalized BLAKE2b for Equihash, behind one small interface.
+//!
+//! `blake2b_simd` (the default `simd` feature) is the default backend. The `portable` feature
+//! swaps in the scalar RustCrypto `blake2` crate for targets where the SIMD crate does not build;
+//! both produce identical digests.
+
+#[cfg(not(any(feature = "simd", feature = "portable")))]
+compile_error!("enable the `simd` or `portable` feature for Equihash's BLAKE2b");
+
+#[cfg(all(feature = "simd", not(feature = "portable")))]
+pub(crate) use blake2b_simd::{Hash, State};
+
+/// Starts a BLAKE2b state with a 16-byte personalization and a `digest_len`-byte output.
+#[cfg(all(feature = "simd", not(feature = "portable")))]
+pub(crate) fn personalized_state(personal: &[u8; 16], digest_len: usize) -> State {
+    blake2b_simd::Params::new()
+        .hash_length(digest_len)
+        .personal(personal)
+        .to_state()
+}
+
+#[cfg(feature = "portable")]
+pub(crate) use portable::{Hash, State};
+
+#[cfg(feature = "portable")]
+pub(crate) fn personalized_state(personal: &[u8; 16], digest_len: usize) -> State {
+    State::new(personal, digest_len)
+}
+
+#[cfg(feature = "portable")]
+mod portable {
+    use blake2::Blake2bVarCore;
+    use blake2::digest::core_api::{Buffer, UpdateCore, VariableOutputCore};
+
+    /// Incremental BLAKE2b over the RustCrypto core.
+    ///
+    /// `Blake2bVar` cannot take a personalization, so this drives the core with its own block
+    /// buffer the way `Blake2bVar` does internally.
+    #[derive(Clone)]
+    pub(crate) struct State {
+        core: Blake2bVarCore,
+        buffer: Buffer<Blake2bVarCore>,
+        digest_len: usize,
+    }
+
+    impl State {
+        pub(super) fn new(personal: &[u8; 16], digest_len: usize) -> Self {
+            State {
+                core: Blake2bVarCore::new_with_params(&[], personal, 0, digest_len),
+                buffer: Buffer::<Blake2bVarCore>::default(),
+                digest_len,
+            }
+        }
+
+        pub(crate) fn update(&mut self, data: &[u8]) -> &mut Self {
+            let core = &mut self.core;
+            self.buffer
+                .digest_blocks(data, |blocks| core.update_blocks(blocks));
+            self
+        }
+
+        pub(crate) fn finalize(&mut self) -> Hash {
+            let mut bytes = [0; 64];
+            let mut out = Default::default();
+            self.core.finalize_variable_core(&mut self.buffer, &mut out);
+            bytes[..self.digest_len].copy_from_slice(&out[..self.digest_len]);
+            Hash {
+                bytes,
+                len: self.digest_len,
+            }
+        }
+    }
+
+    /// A finalized digest, `len` bytes long.
+    pub(crate) struct Hash {
+        bytes: [u8; 64],
+        len: usize,
+    }
+
+    impl Hash {
+        pub(crate) fn as_bytes(&self) -> &[u8] {
+            &self.bytes[..self.len]
+        }
+    }
+}
+
+#[cfg(all(test, feature = "portable"))]
+mod tests {
+    use super::portable;
+
+    /// Equihash's `(200, 9)` personalization: "ZcashPoW" || LE32(200) || LE32(9).
+    const PERSONAL: [u8; 16] = *b"ZcashPoW\xc8\0\0\0\x09\0\0\0";
+
+    #[test]
+    fn portable_matches_blake2b_simd() {
+        // Mainnet-sized powheader: longer than one 128-byte block.
+        let powheader: Vec<u8> = (0..140u8).collect();
+        let simd_base = {
+            let mut state = blake2b_simd::Params::new()
+                .hash_length(50)
+                .personal(&PERSONAL)
+                .to_state();
+            state.update(&powheader);
+            state
+        };
+        let mut portable_base = portable::State::new(&PERSONAL, 50);
+        portable_base.update(&powheader);
+
+        for counter in [0u32, 1, 255, 1 << 20, u32::MAX] {
+            let mut simd = simd_base.clone();
+            simd.update(&counter.to_le_bytes());
+            let mut portable = portable_base.clone();
+            portable.update(&counter.to_le_bytes());
+            assert_eq!(
+                portable.finalize().as_bytes(),
+                simd.finalize().as_bytes(),
+                "counter {counter}"
+            );
+        }
+
+        // Empty input and exactly one block exercise the final-block handling.
+        for len in [0, 128] {
+            let data = vec![0xa5; len];
+            let simd = blake2b_simd::Params::new()
+                .hash_length(50)
+                .personal(&PERSONAL)
+                .hash(&data);
+            let mut portable = portable::State::new(&PERSONAL, 50);
+            portable.update(&data);
+            assert_eq!(
+                portable.finalize().as_bytes(),
+                simd.as_bytes(),
+                "{len} bytes"
+            );
+        }
+    }
+}
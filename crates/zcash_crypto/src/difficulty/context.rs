@@ -1,48 +1,337 @@
+use core::fmt;
+
 use crate::difficulty::filter::DiffError;
-use crate::difficulty::target::{Target, target_from_nbits, target_to_nbits};
+use crate::difficulty::network::Network;
+use crate::difficulty::target::Target;
+use crate::difficulty::work::ChainWork;
+use zcash_primitives::block::BlockHeader;
 
 /// Sliding window of header data needed for contextual difficulty.
 ///
 /// The timestamps and `nBits` values are kept for the most recent headers on
 /// the selected chain, in height order from oldest to newest. This context is
 /// assumed to describe headers up to and including `tip_height`.
+///
+/// Besides the window itself, up to [`MAX_REWIND`](Self::MAX_REWIND) older headers are
+/// retained so that [`rewind_to`](Self::rewind_to) can back out of a shallow reorg.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DifficultyContext {
     /// Height of the tip header described by this context.
     pub tip_height: u32,
+    network: Network,
     times: Vec<u32>,
     bits: Vec<u32>,
+    work: ChainWork,
+    /// Whether older headers have been dropped to stay within `RETAINED`, after which the
+    /// window can no longer be rebuilt below the oldest one held.
+    dropped: bool,
 }
 
 impl DifficultyContext {
+    /// Headers the consensus difficulty rule looks back over: the averaging window plus the
+    /// median-time-past span before it.
+    pub const REQUIRED_WINDOW: usize = POW_MEDIAN_BLOCK_SPAN + POW_AVERAGING_WINDOW;
+
+    /// How many headers a full context can be rewound by and stay ready.
+    pub const MAX_REWIND: usize = 100;
+
+    /// Headers retained in total: the window plus the rewind history below it.
+    pub const RETAINED: usize = Self::REQUIRED_WINDOW + Self::MAX_REWIND;
+
     /// Creates an empty context at the given tip height.
     ///
-    /// Callers are expected to seed this from a checkpoint so that the context
-    /// already includes at least 28 timestamps and 17 `nBits` values before
-    /// verifying contextual difficulty for the next header.
+    /// Callers are expected to seed this from a checkpoint so that the context already includes
+    /// at least [`required_context`](Self::required_context) timestamps and 17 `nBits` values
+    /// before verifying contextual difficulty for the next header.
     pub fn new(tip_height: u32) -> Self {
+        Self::with_network(tip_height, Network::Mainnet)
+    }
+
+    /// Creates an empty context whose threshold is bounded by `network`'s PoW limit.
+    pub fn with_network(tip_height: u32, network: Network) -> Self {
         DifficultyContext {
             tip_height,
+            network,
             times: Vec::new(),
             bits: Vec::new(),
+            work: ChainWork::default(),
+            dropped: false,
         }
     }
 
+    /// Network whose consensus parameters this context applies.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Returns `true` if no headers have been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Number of preceding headers needed before the next `nBits` can be computed: always
+    /// [`Self::REQUIRED_WINDOW`] (28 on every network today).
+    pub fn required_context(&self) -> usize {
+        Self::REQUIRED_WINDOW
+    }
+
+    /// Returns `true` once the window holds enough headers to compute the next `nBits`.
+    pub fn is_ready(&self) -> bool {
+        self.times.len() >= self.required_context() && self.bits.len() >= POW_AVERAGING_WINDOW
+    }
+
+    /// Number of timestamps currently in the window.
+    pub fn times_len(&self) -> usize {
+        self.times.len().min(self.required_context())
+    }
+
+    /// Number of `nBits` values currently in the window.
+    pub fn bits_len(&self) -> usize {
+        self.bits.len().min(POW_AVERAGING_WINDOW)
+    }
+
+    /// Median of the last 11 timestamps in the window, or `None` while it holds fewer.
+    pub fn median_time_past(&self) -> Option<u32> {
+        let len = self.times.len();
+        let recent = self.times.get(len.checked_sub(POW_MEDIAN_BLOCK_SPAN)?..)?;
+        Some(median_11(recent))
+    }
+
+    /// Checks the consensus rule that the next header's `nTime` is strictly greater than the
+    /// [median-time-past](Self::median_time_past) of the headers before it.
+    pub fn verify_timestamp(&self, header_time: u32) -> Result<(), DiffError> {
+        let median_time_past = self
+            .median_time_past()
+            .ok_or(DiffError::InsufficientContext)?;
+        if header_time <= median_time_past {
+            return Err(DiffError::TimeTooOld {
+                median_time_past,
+                found: header_time,
+            });
+        }
+        Ok(())
+    }
+
+    /// Work of every header pushed since this context was created (or last
+    /// [reset](Self::reset_to)), not just those still in the window, less any rewound (see
+    /// [`work_from_nbits`](crate::difficulty::work_from_nbits)).
+    pub fn total_work(&self) -> [u8; 32] {
+        self.work.total_work()
+    }
+
     /// Appends a newly accepted header to the context.
+    ///
+    /// `height` must be `tip_height + 1`; this is not checked, and a gap or repeat silently
+    /// corrupts the window. Meant for seeding from trusted, already ordered headers (e.g. a
+    /// checkpoint); use [`try_push_header`](Self::try_push_header) otherwise.
     pub fn push_header(&mut self, height: u32, n_time: u32, n_bits: u32) {
         self.tip_height = height;
+        self.work.push_nbits(n_bits);
 
+        // Both are trimmed together, so they always cover the same heights.
         self.times.push(n_time);
-        if self.times.len() > POW_MEDIAN_BLOCK_SPAN + POW_AVERAGING_WINDOW {
+        self.bits.push(n_bits);
+        if self.times.len() > Self::RETAINED {
             self.times.remove(0);
+            self.bits.remove(0);
+            self.dropped = true;
         }
+    }
 
-        self.bits.push(n_bits);
-        if self.bits.len() > POW_AVERAGING_WINDOW {
-            self.bits.remove(0);
+    /// Like [`push_header`](Self::push_header), but fails with [`DiffError::HeightMismatch`]
+    /// unless `height` is `tip_height + 1`, leaving the context unchanged.
+    ///
+    /// The first push into an [empty](Self::is_empty) context may be at any height, so a
+    /// context does not need its tip set exactly before seeding.
+    pub fn try_push_header(
+        &mut self,
+        height: u32,
+        n_time: u32,
+        n_bits: u32,
+    ) -> Result<(), DiffError> {
+        let expected = self.tip_height + 1;
+        if !self.is_empty() && height != expected {
+            return Err(DiffError::HeightMismatch {
+                expected,
+                found: height,
+            });
         }
+        self.push_header(height, n_time, n_bits);
+        Ok(())
+    }
+
+    /// Backs the context out to `new_tip`, as if the headers above it had never been pushed.
+    ///
+    /// Fails with [`DiffError::RewindUnavailable`], leaving the context unchanged, if `new_tip`
+    /// is above the tip or so far below it that the window for `new_tip` is no longer held:
+    /// below the [`required_context`](Self::required_context) oldest headers held once older
+    /// headers have been dropped (at most [`MAX_REWIND`](Self::MAX_REWIND) back from a full
+    /// context, across any number of rewinds), or below the first header pushed otherwise.
+    pub fn rewind_to(&mut self, new_tip: u32) -> Result<(), DiffError> {
+        let retained = self.times.len();
+        let floor = if self.dropped {
+            self.required_context()
+        } else {
+            0
+        };
+        let depth = self
+            .tip_height
+            .checked_sub(new_tip)
+            .map(|d| d as usize)
+            .filter(|&d| d <= retained.saturating_sub(floor))
+            .ok_or(DiffError::RewindUnavailable {
+                tip_height: self.tip_height,
+                new_tip,
+            })?;
+
+        self.times.truncate(retained - depth);
+        for n_bits in self.bits.drain(retained - depth..) {
+            self.work.pop_nbits(n_bits);
+        }
+        self.tip_height = new_tip;
+        Ok(())
+    }
+
+    /// Serializes the whole context, rewind history and total work included, for
+    /// [`from_bytes`](Self::from_bytes).
+    ///
+    /// Layout, integers little-endian: tip height (`u32`), network (`u8`: 0 mainnet, 1 testnet,
+    /// 2 regtest), whether older headers were dropped (`u8`: 0 or 1), total work (32 bytes),
+    /// header count (`u32`), then each header's `nTime` and `nBits` (`u32` each), oldest first.
+    /// The layout carries no version; callers that persist it should add their own.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(CONTEXT_BYTES_HEADER + 8 * self.times.len());
+        out.extend_from_slice(&self.tip_height.to_le_bytes());
+        out.push(match self.network {
+            Network::Mainnet => 0,
+            Network::Testnet => 1,
+            Network::Regtest => 2,
+        });
+        out.push(u8::from(self.dropped));
+        out.extend_from_slice(&self.work.total_work());
+        out.extend_from_slice(&(self.times.len() as u32).to_le_bytes());
+        for (time, bits) in self.times.iter().zip(&self.bits) {
+            out.extend_from_slice(&time.to_le_bytes());
+            out.extend_from_slice(&bits.to_le_bytes());
+        }
+        out
+    }
+
+    /// Restores a context serialized by [`to_bytes`](Self::to_bytes).
+    ///
+    /// Returns `None` if `bytes` is not exactly one such context: truncated or followed by
+    /// extra bytes, an unknown network or dropped flag, or more headers than a context retains.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let u32_at = |at: usize| Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+
+        let tip_height = u32_at(0)?;
+        let network = match bytes.get(4)? {
+            0 => Network::Mainnet,
+            1 => Network::Testnet,
+            2 => Network::Regtest,
+            _ => return None,
+        };
+        let dropped = match bytes.get(5)? {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+        let work = ChainWork::from_total_work(bytes.get(6..38)?.try_into().ok()?);
+        let count = u32_at(38)? as usize;
+        if count > Self::RETAINED || bytes.len() != CONTEXT_BYTES_HEADER + 8 * count {
+            return None;
+        }
+
+        let (mut times, mut bits) = (Vec::with_capacity(count), Vec::with_capacity(count));
+        for i in 0..count {
+            let at = CONTEXT_BYTES_HEADER + 8 * i;
+            times.push(u32_at(at)?);
+            bits.push(u32_at(at + 4)?);
+        }
+        Some(DifficultyContext {
+            tip_height,
+            network,
+            times,
+            bits,
+            work,
+            dropped,
+        })
+    }
+
+    /// Rebuilds the context from `headers`, the contiguous run of headers ending at `tip`,
+    /// keeping the network.
+    ///
+    /// Fails with [`DiffError::Discontinuous`], leaving the context unchanged, if a header's
+    /// `prev_block` is not the hash of the one before it. The rebuilt context is ready once
+    /// `headers` holds at least [`REQUIRED_WINDOW`](Self::REQUIRED_WINDOW) of them, and its
+    /// [`total_work`](Self::total_work) covers only `headers`.
+    pub fn reset_to(&mut self, tip: u32, headers: &[BlockHeader]) -> Result<(), DiffError> {
+        let first = tip.wrapping_sub(headers.len() as u32).wrapping_add(1);
+        for (offset, pair) in (1..).zip(headers.windows(2)) {
+            if pair[1].prev_block != pair[0].hash() {
+                return Err(DiffError::Discontinuous {
+                    height: first.wrapping_add(offset),
+                });
+            }
+        }
+
+        let mut ctx = Self::with_network(first.wrapping_sub(1), self.network);
+        for (height, header) in (first..).zip(headers) {
+            ctx.push_header(height, header.time, header.bits);
+        }
+        *self = ctx;
+        Ok(())
     }
 }
 
+/// Seeds a [`DifficultyContext`] one header at a time, checking what
+/// [`push_header`](DifficultyContext::push_header) takes on trust.
+///
+/// Each pushed height must directly follow the previous one, and [`build`](Self::build) only
+/// returns a context that [is ready](DifficultyContext::is_ready), so a short seed fails here
+/// with [`DiffError::InsufficientContext`] rather than at the first header verified against it.
+/// Timestamps are not checked for order: consensus lets them go backward.
+#[derive(Debug, Clone)]
+pub struct DifficultyContextBuilder {
+    ctx: DifficultyContext,
+}
+
+impl DifficultyContextBuilder {
+    /// Starts an empty context for `network`; the first push sets its starting height.
+    pub fn new(network: Network) -> Self {
+        DifficultyContextBuilder {
+            ctx: DifficultyContext::with_network(0, network),
+        }
+    }
+
+    /// Appends a header, failing with [`DiffError::HeightMismatch`] unless `height` follows the
+    /// last one pushed.
+    pub fn push(&mut self, height: u32, n_time: u32, n_bits: u32) -> Result<(), DiffError> {
+        self.ctx.try_push_header(height, n_time, n_bits)
+    }
+
+    /// Whether enough headers have been pushed for [`build`](Self::build) to succeed.
+    pub fn is_ready(&self) -> bool {
+        self.ctx.is_ready()
+    }
+
+    /// Headers still missing from the window, 0 once [ready](Self::is_ready).
+    pub fn missing(&self) -> usize {
+        self.ctx.required_context() - self.ctx.times_len()
+    }
+
+    /// The seeded context, or [`DiffError::InsufficientContext`] while its window is not full.
+    pub fn build(self) -> Result<DifficultyContext, DiffError> {
+        if !self.is_ready() {
+            return Err(DiffError::InsufficientContext);
+        }
+        Ok(self.ctx)
+    }
+}
+
+/// Bytes of [`DifficultyContext::to_bytes`] before the per-header entries.
+const CONTEXT_BYTES_HEADER: usize = 4 + 1 + 1 + 32 + 4;
+
 const POW_AVERAGING_WINDOW: usize = 17;
 const POW_MEDIAN_BLOCK_SPAN: usize = 11;
 const POW_MAX_ADJUST_DOWN_NUM: i64 = 32;
@@ -77,13 +366,9 @@ fn actual_timespan(ctx: &DifficultyContext) -> i64 {
     let past_end = past_start + POW_MEDIAN_BLOCK_SPAN;
     let past_median = median_11(&ctx.times[past_start..past_end]);
 
-    let span = recent_median as i64 - past_median as i64;
-    if span == 0 {
-        // Keep the same difficulty if timestamps are identical.
-        AVERAGING_WINDOW_TIMESPAN
-    } else {
-        span
-    }
+    // Zero or negative when timestamps stall or go backward across the window; like zcashd, the
+    // damping and clamping that follow take it to the minimum timespan, with no special case.
+    recent_median as i64 - past_median as i64
 }
 
 fn actual_timespan_damped(ctx: &DifficultyContext) -> i64 {
@@ -101,58 +386,13 @@ fn clamp_timespan(value: i64) -> i64 {
     }
 }
 
-fn add_target(a: &Target, b: &Target) -> Target {
-    let mut out = [0u8; 32];
-    let mut carry: u16 = 0;
-    for i in 0..32 {
-        let sum = a[i] as u16 + b[i] as u16 + carry;
-        out[i] = sum as u8;
-        carry = sum >> 8;
-    }
-    out
-}
-
-fn div_target_u32(x: &Target, rhs: u32) -> Target {
-    let mut out = [0u8; 32];
-    let mut rem: u64 = 0;
-    for i in (0..32).rev() {
-        let cur = (rem << 8) | x[i] as u64;
-        let q = cur / rhs as u64;
-        rem = cur % rhs as u64;
-        out[i] = q as u8;
-    }
-    out
-}
-
-fn mul_target_u32(x: &Target, rhs: u32) -> Target {
-    let mut out = [0u8; 32];
-    let mut carry: u64 = 0;
-    for i in 0..32 {
-        let cur = x[i] as u64 * rhs as u64 + carry;
-        out[i] = cur as u8;
-        carry = cur >> 8;
-    }
-    out
-}
-
-fn min_target(a: &Target, b: &Target) -> Target {
-    use crate::difficulty::target::cmp_target;
-    if cmp_target(a, b) == core::cmp::Ordering::Greater {
-        *b
-    } else {
-        *a
-    }
-}
-
 fn mean_target(ctx: &DifficultyContext) -> Target {
     let len = ctx.bits.len();
     let start = len.saturating_sub(POW_AVERAGING_WINDOW);
-    let mut acc = [0u8; 32];
-    for &bits in &ctx.bits[start..] {
-        let t = target_from_nbits(bits);
-        acc = add_target(&acc, &t);
-    }
-    div_target_u32(&acc, POW_AVERAGING_WINDOW as u32)
+    let sum = ctx.bits[start..]
+        .iter()
+        .fold(Target::ZERO, |acc, &bits| acc + Target::from_nbits(bits));
+    sum / POW_AVERAGING_WINDOW as u32
 }
 
 fn threshold(ctx: &DifficultyContext) -> Target {
@@ -160,18 +400,13 @@ fn threshold(ctx: &DifficultyContext) -> Target {
     let ats_bounded = clamp_timespan(ats) as u32;
 
     let mean = mean_target(ctx);
-    let scaled = mul_target_u32(
-        &div_target_u32(&mean, AVERAGING_WINDOW_TIMESPAN as u32),
-        ats_bounded,
-    );
-    min_target(&scaled, &crate::difficulty::filter::POW_LIMIT_LE)
+    let scaled = mean / AVERAGING_WINDOW_TIMESPAN as u32 * ats_bounded;
+    scaled.min(ctx.network.pow_limit())
 }
 
 /// Computes the expected `nBits` for the next header height given the context.
 pub fn expected_nbits(ctx: &DifficultyContext, header_height: u32) -> Result<u32, DiffError> {
-    if ctx.times.len() < POW_MEDIAN_BLOCK_SPAN + POW_AVERAGING_WINDOW
-        || ctx.bits.len() < POW_AVERAGING_WINDOW
-    {
+    if !ctx.is_ready() {
         return Err(DiffError::InsufficientContext);
     }
 
@@ -183,7 +418,100 @@ pub fn expected_nbits(ctx: &DifficultyContext, header_height: u32) -> Result<u32
     }
 
     let thr = threshold(ctx);
-    Ok(target_to_nbits(&thr))
+    Ok(thr.to_nbits())
+}
+
+/// Every intermediate of the [`expected_nbits`] computation for one header, next to the header's
+/// own `nBits`; see [`explain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DifficultyExplanation {
+    /// Mean of the targets encoded by the last 17 `nBits`.
+    pub mean_target: Target,
+    /// Difference between the medians of the newest and oldest 11 timestamps of the window.
+    pub actual_timespan: i64,
+    /// `actual_timespan` moved a quarter of the way from the 17 * 75 s target timespan.
+    pub damped_timespan: i64,
+    /// `damped_timespan` bounded to 16% below / 32% above the target timespan.
+    pub clamped_timespan: i64,
+    /// `mean_target` scaled by the clamped timespan, capped at the network's PoW limit.
+    pub threshold_target: Target,
+    /// `threshold_target` in compact form: the `nBits` the header must carry.
+    pub expected_nbits: u32,
+    pub header_nbits: u32,
+}
+
+impl DifficultyExplanation {
+    /// Whether the header's `nBits` is the expected one, i.e. [`verify_difficulty`] passes.
+    pub fn matches(&self) -> bool {
+        self.expected_nbits == self.header_nbits
+    }
+}
+
+impl fmt::Display for DifficultyExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "mean target:      {}", self.mean_target)?;
+        writeln!(
+            f,
+            "timespan:         actual {}s, damped {}s, clamped {}s",
+            self.actual_timespan, self.damped_timespan, self.clamped_timespan
+        )?;
+        writeln!(f, "threshold target: {}", self.threshold_target)?;
+        writeln!(f, "expected nBits:   {:#010x}", self.expected_nbits)?;
+        write!(f, "header nBits:     {:#010x}", self.header_nbits)
+    }
+}
+
+/// Breaks down the [`verify_difficulty`] check of `header_bits` at `header_height`, to see where
+/// a [`DiffError::BitsMismatch`] comes from.
+///
+/// Fails like [`expected_nbits`] when there is nothing to explain: the context is not ready, or
+/// `header_height` does not follow its tip.
+pub fn explain(
+    ctx: &DifficultyContext,
+    header_height: u32,
+    header_bits: u32,
+) -> Result<DifficultyExplanation, DiffError> {
+    let expected_nbits = expected_nbits(ctx, header_height)?;
+    let damped_timespan = actual_timespan_damped(ctx);
+    Ok(DifficultyExplanation {
+        mean_target: mean_target(ctx),
+        actual_timespan: actual_timespan(ctx),
+        damped_timespan,
+        clamped_timespan: clamp_timespan(damped_timespan),
+        threshold_target: threshold(ctx),
+        expected_nbits,
+        header_nbits: header_bits,
+    })
+}
+
+/// Median of the context's last 11 timestamps, or `None` while it holds fewer
+/// (see [`DifficultyContext::median_time_past`]).
+pub fn median_time_past(ctx: &DifficultyContext) -> Option<u32> {
+    ctx.median_time_past()
+}
+
+/// Verifies that `header_time` is strictly after the context's median-time-past
+/// (see [`DifficultyContext::verify_timestamp`]).
+pub fn verify_timestamp(ctx: &DifficultyContext, header_time: u32) -> Result<(), DiffError> {
+    ctx.verify_timestamp(header_time)
+}
+
+/// Default `max_drift` for [`verify_future_time`]: zcashd's `MAX_FUTURE_BLOCK_TIME`, two hours.
+pub const MAX_FUTURE_BLOCK_TIME: u32 = 2 * 60 * 60;
+
+/// Verifies that `header_time` is at most `max_drift` seconds after `now`.
+///
+/// `now` is the caller's notion of the current (network-adjusted) time in seconds since the
+/// Unix epoch; the crate never reads a clock itself.
+pub fn verify_future_time(header_time: u32, now: u32, max_drift: u32) -> Result<(), DiffError> {
+    let limit = now.saturating_add(max_drift);
+    if header_time > limit {
+        return Err(DiffError::TimeTooFarInFuture {
+            time: header_time,
+            limit,
+        });
+    }
+    Ok(())
 }
 
 /// Verifies that the header's `nBits` matches Zcash contextual difficulty.
@@ -201,3 +529,27 @@ pub fn verify_difficulty(
     }
     Ok(())
 }
+
+/// [`verify_difficulty`], except that a `Some` `nbits_override` replaces the computed
+/// [`expected_nbits`] for this height.
+///
+/// Meant for operator-supplied checkpoints around tricky transitions (early chain history,
+/// network upgrades): with an override neither the context nor `header_height` is consulted,
+/// so it also works before the context is ready. A mismatch reports the override as `expected`.
+pub fn verify_difficulty_with_override(
+    ctx: &DifficultyContext,
+    header_height: u32,
+    header_bits: u32,
+    nbits_override: Option<u32>,
+) -> Result<(), DiffError> {
+    let Some(expected) = nbits_override else {
+        return verify_difficulty(ctx, header_height, header_bits);
+    };
+    if header_bits != expected {
+        return Err(DiffError::BitsMismatch {
+            expected,
+            found: header_bits,
+        });
+    }
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use core::fmt;
 
-use crate::difficulty::target::{Target, cmp_target, target_from_nbits};
+use crate::difficulty::network::Network;
+use crate::difficulty::target::Target;
 
 /// Errors that can occur during difficulty verification.
 #[derive(Debug)]
 pub enum DiffError {
-    /// `ToTarget(nBits)` returned zero (invalid compact encoding).
+    /// `ToTarget(nBits)` returned zero or is negative (invalid compact encoding).
     InvalidTarget,
     /// Target derived from `nBits` is above the PoW limit.
     TargetAbovePowLimit,
@@ -17,6 +18,14 @@ pub enum DiffError {
     HeightMismatch { expected: u32, found: u32 },
     /// `nBits` does not match the contextual difficulty adjustment.
     BitsMismatch { expected: u32, found: u32 },
+    /// `nTime` is not after the median-time-past of the preceding 11 headers.
+    TimeTooOld { median_time_past: u32, found: u32 },
+    /// `nTime` is later than the caller's current time plus the allowed drift.
+    TimeTooFarInFuture { time: u32, limit: u32 },
+    /// The context no longer holds the headers needed to rewind to `new_tip`.
+    RewindUnavailable { tip_height: u32, new_tip: u32 },
+    /// The header at `height` does not extend the header before it.
+    Discontinuous { height: u32 },
 }
 
 impl fmt::Display for DiffError {
@@ -36,37 +45,62 @@ impl fmt::Display for DiffError {
                 f,
                 "nBits {found:#x} does not match contextual difficulty {expected:#x}"
             ),
+            DiffError::TimeTooOld {
+                median_time_past,
+                found,
+            } => write!(
+                f,
+                "nTime {found} is not after median-time-past {median_time_past}"
+            ),
+            DiffError::TimeTooFarInFuture { time, limit } => {
+                write!(f, "nTime {time} is after the future-time limit {limit}")
+            }
+            DiffError::RewindUnavailable {
+                tip_height,
+                new_tip,
+            } => write!(
+                f,
+                "cannot rewind context at height {tip_height} to height {new_tip}"
+            ),
+            DiffError::Discontinuous { height } => write!(
+                f,
+                "header at height {height} does not extend the previous header"
+            ),
         }
     }
 }
 
 impl std::error::Error for DiffError {}
 
-/// PoWLimit(mainnet) = 2^243 − 1, encoded as a 256-bit little-endian integer.
-pub(crate) const POW_LIMIT_LE: Target = [
-    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
-    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x07, 0x00,
-];
-
 /// Verifies the difficulty filter `Hash(header) <= ToTarget(nBits)`.
 ///
 /// `header_hash` is the 32-byte SHA256d hash of the full serialized header, in the
 /// same byte order as returned by `BlockHeader::hash().0` / RPC (little-endian for
 /// consensus purposes). `n_bits` is the compact difficulty encoding taken from the
-/// header.
+/// header. The target is bounded by the mainnet PoW limit.
 pub fn verify_difficulty_filter(header_hash: &[u8; 32], n_bits: u32) -> Result<(), DiffError> {
-    let hash_le: Target = *header_hash;
-    let target_le = target_from_nbits(n_bits);
+    verify_difficulty_filter_for_network(header_hash, n_bits, Network::Mainnet)
+}
+
+/// Verifies the difficulty filter against the PoW limit of the given `network`.
+pub fn verify_difficulty_filter_for_network(
+    header_hash: &[u8; 32],
+    n_bits: u32,
+    network: Network,
+) -> Result<(), DiffError> {
+    let hash = Target(*header_hash);
+    let target = Target::try_from_nbits(n_bits).map_err(|_| DiffError::InvalidTarget)?;
 
-    if target_le == [0u8; 32] {
+    // A tiny mantissa can still shift out to zero, which no hash meets either.
+    if target.is_zero() {
         return Err(DiffError::InvalidTarget);
     }
 
-    if cmp_target(&target_le, &POW_LIMIT_LE) == core::cmp::Ordering::Greater {
+    if target > network.pow_limit() {
         return Err(DiffError::TargetAbovePowLimit);
     }
 
-    if cmp_target(&hash_le, &target_le) == core::cmp::Ordering::Greater {
+    if hash > target {
         return Err(DiffError::HashAboveTarget);
     }
 
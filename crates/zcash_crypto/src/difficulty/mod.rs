@@ -1,3 +1,9 @@
 pub mod context;
 pub mod filter;
+pub mod network;
 pub mod target;
+pub mod work;
+
+pub use context::{MAX_FUTURE_BLOCK_TIME, verify_future_time};
+pub use target::nbits_cmp;
+pub use work::{ChainWork, difficulty_ratio, work_from_nbits};
@@ -0,0 +1,33 @@
+use crate::difficulty::target::{POW_LIMIT, Target};
+
+/// Zcash network a header belongs to.
+///
+/// The network selects the consensus PoW limit used by both the difficulty
+/// filter and the contextual difficulty threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+/// PoWLimit(testnet) = 2^251 − 1.
+pub(crate) const TESTNET_POW_LIMIT: Target = Target([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x07,
+]);
+
+/// PoWLimit(regtest) = 0x0f0f…0f.
+pub(crate) const REGTEST_POW_LIMIT: Target = Target([0x0f; 32]);
+
+impl Network {
+    /// Returns the PoW limit for this network.
+    pub fn pow_limit(&self) -> Target {
+        match self {
+            Network::Mainnet => POW_LIMIT,
+            Network::Testnet => TESTNET_POW_LIMIT,
+            Network::Regtest => REGTEST_POW_LIMIT,
+        }
+    }
+}
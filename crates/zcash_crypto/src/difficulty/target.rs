@@ -1,96 +1,218 @@
 use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, Div, Mul};
 
-/// 256-bit little-endian target value.
-pub type Target = [u8; 32];
+/// Unsigned 256-bit integer stored as 32 little-endian bytes.
+///
+/// Used for PoW targets and, since block hashes are compared against targets as
+/// little-endian integers, for header hashes too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Target(pub [u8; 32]);
 
-/// Compare two 256-bit little-endian integers.
-pub fn cmp_target(a: &Target, b: &Target) -> Ordering {
-    for i in (0..32).rev() {
-        match a[i].cmp(&b[i]) {
-            Ordering::Equal => continue,
-            non_eq => return non_eq,
-        }
-    }
-    Ordering::Equal
-}
+/// PoWLimit(mainnet) = 2^243 − 1.
+pub const POW_LIMIT: Target = Target([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x07, 0x00,
+]);
 
-/// Convert compact `nBits` to a 256-bit little-endian target.
-pub fn target_from_nbits(nbits: u32) -> Target {
-    let mant = nbits & 0x007f_ffff;
-    let exp = (nbits >> 24) as u8;
+impl Target {
+    pub const ZERO: Target = Target([0u8; 32]);
+
+    /// Returns `true` for the all-zero target, which no header can meet.
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
 
-    if mant == 0 {
-        return [0u8; 32];
+    /// Convert compact `nBits` to a target.
+    ///
+    /// Encodings zcashd's `SetCompact` flags as negative (sign bit `0x0080_0000` with a nonzero
+    /// mantissa) or overflowing (mantissa shifted past 256 bits) yield [`Target::ZERO`]; see
+    /// [`Target::try_from_nbits`] to tell them apart.
+    pub fn from_nbits(nbits: u32) -> Target {
+        Self::try_from_nbits(nbits).unwrap_or(Self::ZERO)
     }
 
-    let mut mant_le = [0u8; 32];
-    mant_le[0] = (mant & 0xff) as u8;
-    mant_le[1] = ((mant >> 8) & 0xff) as u8;
-    mant_le[2] = ((mant >> 16) & 0xff) as u8;
+    /// Convert compact `nBits` to a target, rejecting malformed encodings.
+    ///
+    /// A zero mantissa is checked first, so `0x1c80_0000` ("negative zero") is
+    /// [`NBitsError::ZeroMantissa`]. A small mantissa shifted right out of range still decodes,
+    /// to [`Target::ZERO`].
+    pub fn try_from_nbits(nbits: u32) -> Result<Target, NBitsError> {
+        let mant = nbits & 0x007f_ffff;
+        let exp = (nbits >> 24) as u8;
+
+        if mant == 0 {
+            return Err(NBitsError::ZeroMantissa);
+        }
+        if nbits & 0x0080_0000 != 0 {
+            return Err(NBitsError::NegativeSignBit);
+        }
+        if exp > 34 || (mant > 0xff && exp > 33) || (mant > 0xffff && exp > 32) {
+            return Err(NBitsError::Overflow);
+        }
+
+        let mut mant_le = [0u8; 32];
+        mant_le[0] = (mant & 0xff) as u8;
+        mant_le[1] = ((mant >> 8) & 0xff) as u8;
+        mant_le[2] = ((mant >> 16) & 0xff) as u8;
 
-    let shift_bytes = exp as i32 - 3;
-    if shift_bytes == 0 {
-        return mant_le;
+        // The overflow check bounds `exp` by 34, so the shift stays within 31 bytes either way.
+        let mut out = [0u8; 32];
+        match exp.checked_sub(3) {
+            Some(s) => out[s as usize..].copy_from_slice(&mant_le[..32 - s as usize]),
+            None => {
+                let s = (3 - exp) as usize;
+                out[..32 - s].copy_from_slice(&mant_le[s..]);
+            }
+        }
+
+        Ok(Target(out))
     }
 
-    let mut out = [0u8; 32];
-    if shift_bytes > 0 {
-        let s = shift_bytes as usize;
-        if s >= 32 {
-            return [0u8; 32];
+    /// Convert to compact `nBits`, as zcashd's `GetCompact`.
+    ///
+    /// The canonical form keeps the three most significant bytes of the target as the mantissa
+    /// (left-aligned when the target is shorter than three bytes) and its byte length as the
+    /// exponent. A mantissa with its top bit set would read as negative, so it is shifted down
+    /// a byte and the exponent bumped. Lower bytes are truncated, hence
+    /// `Target::from_nbits(t.to_nbits()) <= t`, with equality whenever `t` is itself the
+    /// expansion of some `nBits`.
+    pub fn to_nbits(&self) -> u32 {
+        let mut bytes_be = self.0;
+        bytes_be.reverse();
+
+        let mut i = 0usize;
+        while i < 32 && bytes_be[i] == 0 {
+            i += 1;
         }
-        for i in 0..(32 - s) {
-            out[i + s] = mant_le[i];
+        if i == 32 {
+            return 0;
         }
-    } else {
-        let s = (-shift_bytes) as usize;
-        if s >= 32 {
-            return [0u8; 32];
+
+        let mut size = (32 - i) as u32;
+        let mut mant = bytes_be[i..]
+            .iter()
+            .take(3)
+            .fold(0u32, |acc, &b| (acc << 8) | b as u32);
+        if size < 3 {
+            mant <<= 8 * (3 - size);
         }
-        for i in 0..(32 - s) {
-            out[i] = mant_le[i + s];
+
+        if mant & 0x0080_0000 != 0 {
+            mant >>= 8;
+            size += 1;
         }
+
+        (size << 24) | (mant & 0x007f_ffff)
+    }
+
+    /// Parse 64 hex digits in big-endian order, the form [`Display`](fmt::Display) writes and
+    /// RPC reports block hashes in.
+    pub fn from_hex(s: &str) -> Option<Target> {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(s, &mut bytes).ok()?;
+        bytes.reverse();
+        Some(Target(bytes))
+    }
+}
+
+/// Compares the targets `a` and `b` expand to, so distinct encodings of the same target are
+/// `Equal`.
+///
+/// This orders targets, not difficulty: a lower target is a higher difficulty, so `Less` means
+/// `a` is the harder of the two. Like [`Target::from_nbits`], malformed encodings expand to
+/// [`Target::ZERO`] and compare below every valid one.
+pub fn nbits_cmp(a: u32, b: u32) -> Ordering {
+    Target::from_nbits(a).cmp(&Target::from_nbits(b))
+}
+
+/// Big-endian hex, 64 digits with leading zeros, so targets and hashes read like RPC output.
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().rev().try_for_each(|b| write!(f, "{b:02x}"))
     }
+}
 
-    out
+/// Why [`Target::try_from_nbits`] rejected a compact encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NBitsError {
+    /// The 23-bit mantissa is zero, so the target is zero whatever the exponent.
+    ZeroMantissa,
+    /// Sign bit `0x0080_0000` set with a nonzero mantissa: a negative target.
+    NegativeSignBit,
+    /// The mantissa shifted by the exponent does not fit in 256 bits.
+    Overflow,
 }
 
-/// Convert a 256-bit little-endian target to compact `nBits`.
-pub fn target_to_nbits(target_le: &Target) -> u32 {
-    let mut bytes_be = [0u8; 32];
-    for i in 0..32 {
-        bytes_be[i] = target_le[31 - i];
+impl fmt::Display for NBitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NBitsError::ZeroMantissa => write!(f, "nBits has a zero mantissa"),
+            NBitsError::NegativeSignBit => write!(f, "nBits encodes a negative target"),
+            NBitsError::Overflow => write!(f, "nBits overflows 256 bits"),
+        }
     }
+}
+
+impl std::error::Error for NBitsError {}
 
-    let mut i = 0usize;
-    while i < 32 && bytes_be[i] == 0 {
-        i += 1;
+impl Ord for Target {
+    /// Compares as integers, i.e. starting from the most significant (last) byte.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
     }
-    if i == 32 {
-        return 0;
+}
+
+impl PartialOrd for Target {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    let mut size = (32 - i) as u32;
-    let mut mant: u32;
+/// Wrapping addition modulo 2^256.
+impl Add for Target {
+    type Output = Target;
 
-    if size <= 3 {
-        mant = (bytes_be[i] as u32) << 16;
-        if i + 1 < 32 {
-            mant |= (bytes_be[i + 1] as u32) << 8;
-        }
-        if i + 2 < 32 {
-            mant |= bytes_be[i + 2] as u32;
+    fn add(self, rhs: Target) -> Target {
+        let mut out = [0u8; 32];
+        let mut carry: u16 = 0;
+        for (o, (a, b)) in out.iter_mut().zip(self.0.iter().zip(rhs.0)) {
+            let sum = *a as u16 + b as u16 + carry;
+            *o = sum as u8;
+            carry = sum >> 8;
         }
-        mant <<= 8 * (3 - size);
-    } else {
-        mant =
-            (bytes_be[i] as u32) << 16 | (bytes_be[i + 1] as u32) << 8 | (bytes_be[i + 2] as u32);
+        Target(out)
     }
+}
 
-    if mant & 0x0080_0000 != 0 {
-        mant >>= 8;
-        size += 1;
+/// Wrapping multiplication modulo 2^256.
+impl Mul<u32> for Target {
+    type Output = Target;
+
+    fn mul(self, rhs: u32) -> Target {
+        let mut out = [0u8; 32];
+        let mut carry: u64 = 0;
+        for (o, x) in out.iter_mut().zip(self.0) {
+            let cur = x as u64 * rhs as u64 + carry;
+            *o = cur as u8;
+            carry = cur >> 8;
+        }
+        Target(out)
     }
+}
+
+/// Truncating division; panics if `rhs` is zero.
+impl Div<u32> for Target {
+    type Output = Target;
 
-    (size << 24) | (mant & 0x007f_ffff)
+    fn div(self, rhs: u32) -> Target {
+        let mut out = [0u8; 32];
+        let mut rem: u64 = 0;
+        for i in (0..32).rev() {
+            let cur = (rem << 8) | self.0[i] as u64;
+            out[i] = (cur / rhs as u64) as u8;
+            rem = cur % rhs as u64;
+        }
+        Target(out)
+    }
 }
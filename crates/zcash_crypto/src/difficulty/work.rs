@@ -0,0 +1,104 @@
+use crate::difficulty::filter::DiffError;
+use crate::difficulty::network::Network;
+use crate::difficulty::target::Target;
+
+const ONE: Target = {
+    let mut le = [0u8; 32];
+    le[0] = 1;
+    Target(le)
+};
+
+/// Work represented by a header with the given `nBits`: the expected number of hashes needed
+/// to meet its target, `2^256 / (target + 1)`, as a little-endian 256-bit integer.
+///
+/// Like zcashd's `GetBlockProof`, encodings that expand to a zero target (including negative
+/// and overflowing ones) contribute no work.
+pub fn work_from_nbits(nbits: u32) -> [u8; 32] {
+    let target = Target::from_nbits(nbits);
+    if target.is_zero() {
+        return [0u8; 32];
+    }
+    // 2^256 does not fit in 256 bits; (2^256 - target - 1) / (target + 1) + 1 is the same value.
+    let not_target = Target(target.0.map(|b| !b));
+    (div(not_target, target + ONE) + ONE).0
+}
+
+/// Difficulty as block explorers and zcashd's `getdifficulty` report it: how many times harder
+/// `n_bits`' target is to meet than the easiest target `network` allows.
+///
+/// Like zcashd, the PoW limit is first rounded to compact form (`0x1f07ffff` on mainnet), so a
+/// header at the limit has difficulty exactly 1.0. The ratio is computed in `f64` and is for
+/// display only. Targets above the limit give a ratio below 1.0; encodings that expand to a
+/// zero target are [`DiffError::InvalidTarget`].
+pub fn difficulty_ratio(n_bits: u32, network: Network) -> Result<f64, DiffError> {
+    let target = Target::from_nbits(n_bits);
+    if target.is_zero() {
+        return Err(DiffError::InvalidTarget);
+    }
+    let limit = Target::from_nbits(network.pow_limit().to_nbits());
+    Ok(to_f64(limit) / to_f64(target))
+}
+
+/// Cumulative chain work, for choosing between competing tips by most work.
+///
+/// Compares as the 256-bit total, so the heavier chain's `ChainWork` is the greater one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChainWork(Target);
+
+impl ChainWork {
+    /// Adds the work of one header.
+    pub fn push_nbits(&mut self, nbits: u32) {
+        self.0 = self.0 + Target(work_from_nbits(nbits));
+    }
+
+    /// Removes the work of one header added by [`push_nbits`](Self::push_nbits), e.g. when
+    /// rewinding past it.
+    pub fn pop_nbits(&mut self, nbits: u32) {
+        self.0 = sub(self.0, Target(work_from_nbits(nbits)));
+    }
+
+    /// Total work accumulated so far, little-endian.
+    pub fn total_work(&self) -> [u8; 32] {
+        self.0.0
+    }
+
+    /// The `ChainWork` whose [`total_work`](Self::total_work) is `total_work`.
+    pub fn from_total_work(total_work: [u8; 32]) -> Self {
+        ChainWork(Target(total_work))
+    }
+}
+
+/// Truncating 256-bit division by shift-and-subtract; `den` must be nonzero.
+fn div(num: Target, den: Target) -> Target {
+    let mut quot = Target::ZERO;
+    let mut rem = Target::ZERO;
+    for bit in (0..256).rev() {
+        // rem = rem * 2 + bit, remembering the bit shifted out of the top.
+        let carry = rem.0[31] >> 7;
+        rem = rem + rem;
+        rem.0[0] |= (num.0[bit / 8] >> (bit % 8)) & 1;
+        if carry == 1 || rem >= den {
+            rem = sub(rem, den);
+            quot.0[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+    quot
+}
+
+fn to_f64(t: Target) -> f64 {
+    t.0.iter()
+        .rev()
+        .fold(0.0, |acc, &b| acc * 256.0 + f64::from(b))
+}
+
+/// Wrapping 256-bit subtraction.
+fn sub(a: Target, b: Target) -> Target {
+    let mut out = [0u8; 32];
+    let mut borrow = 0i16;
+    for (o, (x, y)) in out.iter_mut().zip(a.0.iter().zip(b.0)) {
+        let diff = *x as i16 - y as i16 - borrow;
+        *o = diff as u8;
+        borrow = i16::from(diff < 0);
+    }
+    Target(out)
+}
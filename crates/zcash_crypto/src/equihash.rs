@@ -2,7 +2,8 @@
 //!
 //! High-level procedure:
 //! 1) Decode the minimal solution into `2^k` indices (big-endian bit-packed).
-//! 2) Initialize BLAKE2b with Zcash personalization ("ZcashPoW" || LE32(n) || LE32(k))
+//! 2) Initialize BLAKE2b with Zcash personalization ("ZcashPoW" || LE32(n) || LE32(k); the
+//!    prefix is configurable through [`Params`] for other Equihash chains)
 //!    and absorb the `powheader` (header bytes up to and including the nonce).
 //! 3) Build a binary merge tree over the indices:
 //!    - Require equal leading `collision_byte_length` bytes for each sibling pair.
@@ -10,28 +11,83 @@
 //!    - Ensure index sets are disjoint.
 //!    - Combine by XORing the remaining bytes (after trimming the collision prefix).
 //! 4) At the root, the remaining bytes must be all zeros; otherwise the solution is invalid.
-use blake2b_simd::{Hash as Blake2bHash, Params as Blake2bParams, State as Blake2bState};
+use crate::blake2b::{self, Hash as Blake2bHash, State as Blake2bState};
 use core::fmt;
 
-/// Equihash parameters `(n, k)`.
+/// Equihash parameters `(n, k)` and the BLAKE2b personalization prefix.
 ///
 /// - `n`: number of bits per leaf hash fragment.
 /// - `k`: number of reduction rounds; a solution has `2^k` indices.
-#[derive(Clone, Copy)]
+/// - personalization prefix: [`ZCASH_PERSONALIZATION`] unless replaced with
+///   [`with_personalization`](Self::with_personalization), for other Equihash chains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Params {
     n: u32,
     k: u32,
+    prefix: [u8; 8],
+    prefix_len: u8,
 }
 
+/// Zcash's personalization prefix, followed by `LE32(n) || LE32(k)` in the BLAKE2b
+/// personalization.
+pub const ZCASH_PERSONALIZATION: &[u8; 8] = b"ZcashPoW";
+
 impl Params {
+    /// Zcash mainnet parameters `(n=200, k=9)`; equal to `Params::new(200, 9)`.
+    pub const MAINNET: Params = Params {
+        n: 200,
+        k: 9,
+        prefix: *ZCASH_PERSONALIZATION,
+        prefix_len: 8,
+    };
+
     /// Construct validated parameters.
+    ///
+    /// Besides the structural Equihash constraints, `n` must fit in one BLAKE2b digest
+    /// (`n <= 512`) and the collision length must lie in `8..=24` bits: leaf hashes are expanded
+    /// in `collision_bit_length()`-bit chunks and solution indices in one bit more, and the bit
+    /// expansion only handles chunks of 8 to 25 bits.
     pub fn new(n: u32, k: u32) -> Option<Self> {
-        if n.is_multiple_of(8) && (k >= 3) && (k < n) && n.is_multiple_of(k + 1) {
-            Some(Self { n, k })
+        if n.is_multiple_of(8)
+            && (k >= 3)
+            && (k < n)
+            && n.is_multiple_of(k + 1)
+            && n <= 512
+            && (8..=24).contains(&(n / (k + 1)))
+        {
+            Some(Self {
+                n,
+                k,
+                ..Self::MAINNET
+            })
         } else {
             None
         }
     }
+    /// The same `(n, k)` with another personalization prefix (e.g. `b"BgoldPoW"`), or `None`
+    /// if it is longer than 8 bytes. A shorter prefix is followed directly by `LE32(n) ||
+    /// LE32(k)`, and BLAKE2b zero-pads the rest of the 16 bytes.
+    pub fn with_personalization(self, prefix: &[u8]) -> Option<Self> {
+        let mut padded = [0u8; 8];
+        padded.get_mut(..prefix.len())?.copy_from_slice(prefix);
+        Some(Self {
+            prefix: padded,
+            prefix_len: prefix.len() as u8,
+            ..self
+        })
+    }
+    /// The personalization prefix.
+    pub fn personalization_prefix(&self) -> &[u8] {
+        &self.prefix[..self.prefix_len as usize]
+    }
+    /// Bits per leaf hash fragment.
+    pub fn n(&self) -> u32 {
+        self.n
+    }
+    /// Number of reduction rounds.
+    pub fn k(&self) -> u32 {
+        self.k
+    }
     /// Number of indices represented per BLAKE2b digest output.
     pub fn indices_per_hash_output(&self) -> u32 {
         512 / self.n
@@ -48,6 +104,14 @@ impl Params {
     pub fn collision_byte_length(&self) -> usize {
         self.collision_bit_length().div_ceil(8)
     }
+    /// Length in bytes of a minimal solution, `(2^k * (collision_bit_length + 1)) / 8`
+    /// (1344 for mainnet), or `None` if that overflows `usize`.
+    pub fn solution_len(&self) -> Option<usize> {
+        let bits = 1usize
+            .checked_shl(self.k)?
+            .checked_mul(self.collision_bit_length() + 1)?;
+        Some(bits / 8)
+    }
 }
 
 /// Error wrapper indicating why verification failed.
@@ -63,13 +127,15 @@ impl fmt::Display for Error {
 /// Specific failure reasons during verification.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Kind {
-    /// Invalid `(n,k)` parameters or solution length/encoding.
+    /// Invalid `(n,k)` parameters or solution encoding.
     InvalidParams,
+    /// The solution is not the minimal-encoding length `(n,k)` require.
+    WrongSolutionLength { expected: usize, found: usize },
     /// Leading collision bytes did not match for a pair of siblings.
     Collision,
     /// Left subtree did not lexicographically precede the right subtree.
     OutOfOrder,
-    /// Duplicate index encountered across siblings.
+    /// The same index appears more than once in the solution.
     DuplicateIdxs,
     /// Final root bytes (after reductions) are not all zero.
     NonZeroRootHash,
@@ -79,6 +145,10 @@ impl fmt::Display for Kind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Kind::InvalidParams => f.write_str("invalid parameters"),
+            Kind::WrongSolutionLength { expected, found } => write!(
+                f,
+                "solution is {found} bytes, expected {expected} for these parameters"
+            ),
             Kind::Collision => f.write_str("invalid collision length between StepRows"),
             Kind::OutOfOrder => f.write_str("Index tree incorrectly ordered"),
             Kind::DuplicateIdxs => f.write_str("duplicate indices"),
@@ -87,44 +157,51 @@ impl fmt::Display for Kind {
     }
 }
 
-/// Initialize BLAKE2b with Zcash personalization and the desired digest length.
+/// Initialize BLAKE2b with the personalization of `p` and its digest length.
 ///
-/// Personalization: "ZcashPoW" || LE32(n) || LE32(k).
-fn initialise_state(n: u32, k: u32, digest_len: u8) -> Blake2bState {
-    // personalization = "ZcashPoW" || LE32(n) || LE32(k)
-    let mut personalization: [u8; 16] = *b"ZcashPoW\x00\x00\x00\x00\x00\x00\x00\x00";
-    personalization[8..12].copy_from_slice(&n.to_le_bytes());
-    personalization[12..16].copy_from_slice(&k.to_le_bytes());
-    Blake2bParams::new()
-        .hash_length(digest_len as usize)
-        .personal(&personalization)
-        .to_state()
+/// Personalization: prefix ("ZcashPoW" for Zcash) || LE32(n) || LE32(k), zero-padded to 16 bytes.
+fn initialise_state(p: &Params) -> Blake2bState {
+    let prefix = p.personalization_prefix();
+    let mut personalization = [0u8; 16];
+    personalization[..prefix.len()].copy_from_slice(prefix);
+    personalization[prefix.len()..prefix.len() + 4].copy_from_slice(&p.n.to_le_bytes());
+    personalization[prefix.len() + 4..prefix.len() + 8].copy_from_slice(&p.k.to_le_bytes());
+    blake2b::personalized_state(&personalization, p.hash_output() as usize)
 }
 
 /// Compute the `i`-th group BLAKE2b digest by hashing the 32-bit little-endian counter.
 ///
 /// A digest contains several adjacent `n`-bit slices; leaf construction selects one slice.
+/// Leaves recompute their digest rather than sharing one through a cache: a solution's indices
+/// rarely pair up on a counter (7 shared digests across the 73,728 leaves of the mainnet
+/// fixtures), so the bookkeeping costs more than it saves (`benches/leaf_digests.rs`).
 fn generate_hash(base_state: &Blake2bState, i: u32) -> Blake2bHash {
     let mut state = base_state.clone();
     state.update(&i.to_le_bytes());
-    
-    // println!("HASH: {:?}", hash);
     state.finalize()
 }
 
 /// Expand a compact big-endian bitstring into fixed-width, optionally byte-padded chunks.
 ///
 /// Used for both digest-slice expansion and minimal solution expansion to big-endian `u32`s.
-fn expand_array(vin: &[u8], bit_len: usize, byte_pad: usize) -> Vec<u8> {
-    // println!("v in: {:?}", vin);
-    assert!(bit_len >= 8);
-    assert!((u32::BITS as usize) >= 7 + bit_len);
-
+/// Returns `None` unless `bit_len` is in `8..=25` (the `u32` accumulator's reach), each padded
+/// chunk fits in four bytes and `vin` holds a whole number of `bit_len`-bit chunks.
+fn expand_array(vin: &[u8], bit_len: usize, byte_pad: usize) -> Option<Vec<u8>> {
+    if !(8..=(u32::BITS as usize - 7)).contains(&bit_len) {
+        return None;
+    }
     let out_width = bit_len.div_ceil(8) + byte_pad;
-    let out_len = 8 * out_width * vin.len() / bit_len;
+    if out_width > core::mem::size_of::<u32>() {
+        return None;
+    }
+    let in_bits = vin.len().checked_mul(8)?;
+    if !in_bits.is_multiple_of(bit_len) {
+        return None;
+    }
+    let out_len = in_bits / bit_len * out_width;
 
     if out_len == vin.len() {
-        return vin.to_vec();
+        return Some(vin.to_vec());
     }
     let mut vout: Vec<u8> = vec![0; out_len];
     let bit_len_mask: u32 = (1 << bit_len) - 1;
@@ -146,8 +223,7 @@ fn expand_array(vin: &[u8], bit_len: usize, byte_pad: usize) -> Vec<u8> {
             j += out_width;
         }
     }
-    // println!("v out: {:?}", vout);
-    vout
+    Some(vout)
 }
 
 /// Decode the minimal solution into a vector of big-endian `u32` indices.
@@ -155,12 +231,12 @@ fn expand_array(vin: &[u8], bit_len: usize, byte_pad: usize) -> Vec<u8> {
 /// Length check: `minimal.len() == (2^k * (c_bit_len+1)) / 8` where `c_bit_len = n/(k+1)`.
 pub fn indices_from_minimal(p: Params, minimal: &[u8]) -> Option<Vec<u32>> {
     let c_bit_len = p.collision_bit_length();
-    if minimal.len() != ((1 << p.k) * (c_bit_len + 1)) / 8 {
+    if minimal.len() != p.solution_len()? {
         return None;
     }
     let digit_bytes = (c_bit_len + 1).div_ceil(8);
-    let byte_pad = core::mem::size_of::<u32>() - digit_bytes;
-    let expanded = expand_array(minimal, c_bit_len + 1, byte_pad);
+    let byte_pad = core::mem::size_of::<u32>().checked_sub(digit_bytes)?;
+    let expanded = expand_array(minimal, c_bit_len + 1, byte_pad)?;
     if !expanded.len().is_multiple_of(4) {
         return None;
     }
@@ -171,6 +247,28 @@ pub fn indices_from_minimal(p: Params, minimal: &[u8]) -> Option<Vec<u32>> {
     Some(ret)
 }
 
+/// [`indices_from_minimal`] for the verifiers, reporting a wrong-size solution as
+/// [`Kind::WrongSolutionLength`] rather than the opaque [`Kind::InvalidParams`].
+fn decode_solution(p: Params, solution: &[u8]) -> Result<Vec<u32>, Error> {
+    let expected = p.solution_len().ok_or(Error(Kind::InvalidParams))?;
+    if solution.len() != expected {
+        return Err(Error(Kind::WrongSolutionLength {
+            expected,
+            found: solution.len(),
+        }));
+    }
+    indices_from_minimal(p, solution).ok_or(Error(Kind::InvalidParams))
+}
+
+/// Leaf hash for index `i`: the `n`-bit slice of its group digest, expanded to bytes.
+fn leaf_hash(p: &Params, state: &Blake2bState, i: u32) -> Result<Vec<u8>, Error> {
+    let hash = generate_hash(state, i / p.indices_per_hash_output());
+    let start = ((i % p.indices_per_hash_output()) * p.n / 8) as usize;
+    let end = start + (p.n as usize) / 8;
+    expand_array(&hash.as_bytes()[start..end], p.collision_bit_length(), 0)
+        .ok_or(Error(Kind::InvalidParams))
+}
+
 /// Tree node holding the current reduced hash bytes and the ordered index list.
 #[derive(Clone, Debug)]
 struct Node {
@@ -179,20 +277,12 @@ struct Node {
 }
 
 impl Node {
-    /// Construct a leaf:
-    /// - Take the appropriate `n`-bit slice from the group digest.
-    /// - Expand to bytes (big-endian) to form the leaf hash.
-    fn new(p: &Params, state: &Blake2bState, i: u32) -> Self {
-        // println!("i: {:?}", i);
-        let hash = generate_hash(state, i / p.indices_per_hash_output());
-        let start = ((i % p.indices_per_hash_output()) * p.n / 8) as usize;
-        let end = start + (p.n as usize) / 8;
-        let expanded = expand_array(&hash.as_bytes()[start..end], p.collision_bit_length(), 0);
-        // println!("expanded: {:?}", expanded);
-        Node {
-            hash: expanded,
+    /// Construct a leaf for index `i` (see [`leaf_hash`]).
+    fn new(p: &Params, state: &Blake2bState, i: u32) -> Result<Self, Error> {
+        Ok(Node {
+            hash: leaf_hash(p, state, i)?,
             indices: vec![i],
-        }
+        })
     }
     /// Combine siblings by XORing the post-collision bytes and concatenating indices
     /// with the lexicographically earlier subtree first.
@@ -234,26 +324,25 @@ fn has_collision(a: &Node, b: &Node, len: usize) -> bool {
         .all(|(x, y)| x == y)
 }
 
-/// Ensure index sets are disjoint.
-fn distinct_indices(a: &Node, b: &Node) -> bool {
-    for i in &a.indices {
-        for j in &b.indices {
-            if i == j {
-                return false;
-            }
-        }
-    }
-    true
+/// Check that no index appears twice in the full solution, in `O(n log n)`.
+///
+/// Every merge's index set is a subset of the root's, so a single sort-and-scan here
+/// replaces a pairwise disjointness check between siblings at every level (which costs
+/// about `2^(2k-1)` comparisons in total).
+pub fn indices_are_distinct(indices: &[u32]) -> bool {
+    let mut sorted = indices.to_vec();
+    sorted.sort_unstable();
+    sorted.windows(2).all(|w| w[0] != w[1])
 }
 
-/// Validate sibling constraints: collision equality, ordering, and distinctness.
+/// Validate sibling constraints: collision equality and ordering.
+///
+/// Distinctness is enforced once up front by [`indices_are_distinct`].
 fn validate_subtrees(p: &Params, a: &Node, b: &Node) -> Result<(), Kind> {
     if !has_collision(a, b, p.collision_byte_length()) {
         Err(Kind::Collision)
     } else if b.indices_before(a) {
         Err(Kind::OutOfOrder)
-    } else if !distinct_indices(a, b) {
-        Err(Kind::DuplicateIdxs)
     } else {
         Ok(())
     }
@@ -269,8 +358,58 @@ fn tree_validator(p: &Params, state: &Blake2bState, indices: &[u32]) -> Result<N
         validate_subtrees(p, &a, &b).map_err(Error)?;
         Ok(Node::from_children(a, b, p.collision_byte_length()))
     } else {
-        Ok(Node::new(p, state, indices[0]))
+        Node::new(p, state, indices[0])
+    }
+}
+
+/// Subtree summary kept by [`streaming_validator`].
+///
+/// Instead of the index list, only the subtree's leaf range within the decoded solution is
+/// tracked: once ordering has been checked below it, its first index is also its minimum.
+struct Partial {
+    hash: Vec<u8>,
+    start: usize,
+    len: usize,
+}
+
+/// Validate the merge tree bottom-up over the leaves, keeping at most `k + 1` subtrees alive.
+///
+/// Leaves are consumed left to right and equal-sized neighbours merged as soon as both exist,
+/// which visits sibling pairs in the same order as [`tree_validator`] and therefore reports the
+/// same first error. Returns the root hash.
+fn streaming_validator(
+    p: &Params,
+    state: &Blake2bState,
+    indices: &[u32],
+) -> Result<Vec<u8>, Error> {
+    let trim = p.collision_byte_length();
+    let mut stack: Vec<Partial> = Vec::with_capacity(p.k as usize + 1);
+    for (pos, &i) in indices.iter().enumerate() {
+        let mut b = Partial {
+            hash: leaf_hash(p, state, i)?,
+            start: pos,
+            len: 1,
+        };
+        while let Some(a) = stack.pop_if(|a| a.len == b.len) {
+            if a.hash[..trim] != b.hash[..trim] {
+                return Err(Error(Kind::Collision));
+            } else if indices[b.start] < indices[a.start] {
+                return Err(Error(Kind::OutOfOrder));
+            }
+            let hash = a.hash[trim..]
+                .iter()
+                .zip(&b.hash[trim..])
+                .map(|(x, y)| x ^ y)
+                .collect();
+            b = Partial {
+                hash,
+                start: a.start,
+                len: a.len + b.len,
+            };
+        }
+        stack.push(b);
     }
+    Ok(stack.pop().map(|root| root.hash).unwrap_or_default())
 }
 
 /// Verify that `solution` encodes a valid Equihash solution for the provided `powheader`,
@@ -282,7 +421,8 @@ pub fn verify_equihash_solution(powheader: &[u8], solution: &[u8]) -> Result<(),
 /// Verify a solution for arbitrary valid `(n, k)` parameters.
 ///
 /// Steps:
-/// 1) Validate `(n,k)` and decode the minimal solution to an index array.
+/// 1) Validate `(n,k)`, decode the minimal solution to an index array and reject
+///    repeated indices.
 /// 2) Initialize BLAKE2b with personalization and absorb `powheader`.
 /// 3) Recursively build and validate the Equihash merge tree over the indices.
 /// 4) Require that the root’s remaining bytes are all zero.
@@ -298,9 +438,37 @@ pub fn verify_equihash_solution_with_params(
     solution: &[u8],
 ) -> Result<(), Error> {
     let p = Params::new(n, k).ok_or(Error(Kind::InvalidParams))?;
-    let indices = indices_from_minimal(p, solution).ok_or(Error(Kind::InvalidParams))?;
+    verify_equihash_solution_for_params(p, powheader, solution)
+}
 
-    let mut state = initialise_state(p.n, p.k, p.hash_output());
+/// [`verify_equihash_solution_with_params`] for a chain that personalizes BLAKE2b with
+/// `personal_prefix || LE32(n) || LE32(k)` instead of Zcash's `"ZcashPoW"` prefix.
+pub fn verify_equihash_solution_with_personal(
+    personal_prefix: &[u8; 8],
+    n: u32,
+    k: u32,
+    powheader: &[u8],
+    solution: &[u8],
+) -> Result<(), Error> {
+    let p = Params::new(n, k)
+        .and_then(|p| p.with_personalization(personal_prefix))
+        .ok_or(Error(Kind::InvalidParams))?;
+    verify_equihash_solution_for_params(p, powheader, solution)
+}
+
+/// [`verify_equihash_solution_with_params`] for already validated [`Params`], including their
+/// personalization prefix.
+pub fn verify_equihash_solution_for_params(
+    p: Params,
+    powheader: &[u8],
+    solution: &[u8],
+) -> Result<(), Error> {
+    let indices = decode_solution(p, solution)?;
+    if !indices_are_distinct(&indices) {
+        return Err(Error(Kind::DuplicateIdxs));
+    }
+
+    let mut state = initialise_state(&p);
     state.update(powheader);
 
     let root = tree_validator(&p, &state, &indices)?;
@@ -311,6 +479,48 @@ pub fn verify_equihash_solution_with_params(
     }
 }
 
+/// Streaming variant of [`verify_equihash_solution`] for memory-constrained callers.
+pub fn verify_equihash_solution_streaming(powheader: &[u8], solution: &[u8]) -> Result<(), Error> {
+    verify_equihash_solution_streaming_with_params(200, 9, powheader, solution)
+}
+
+/// Streaming variant of [`verify_equihash_solution_with_params`].
+///
+/// Rather than materialising every subtree's index list on the way up, this keeps only
+/// the `k + 1` pending subtree hashes and leaf ranges (see [`streaming_validator`]). It
+/// accepts and rejects exactly the same solutions, with the same [`Kind`].
+pub fn verify_equihash_solution_streaming_with_params(
+    n: u32,
+    k: u32,
+    powheader: &[u8],
+    solution: &[u8],
+) -> Result<(), Error> {
+    let p = Params::new(n, k).ok_or(Error(Kind::InvalidParams))?;
+    verify_equihash_solution_streaming_for_params(p, powheader, solution)
+}
+
+/// Streaming variant of [`verify_equihash_solution_for_params`].
+pub fn verify_equihash_solution_streaming_for_params(
+    p: Params,
+    powheader: &[u8],
+    solution: &[u8],
+) -> Result<(), Error> {
+    let indices = decode_solution(p, solution)?;
+    if !indices_are_distinct(&indices) {
+        return Err(Error(Kind::DuplicateIdxs));
+    }
+
+    let mut state = initialise_state(&p);
+    state.update(powheader);
+
+    let root = streaming_validator(&p, &state, &indices)?;
+    if root.iter().take(p.collision_byte_length()).all(|v| *v == 0) {
+        Ok(())
+    } else {
+        Err(Error(Kind::NonZeroRootHash))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -449,4 +659,80 @@ mod tests {
         ];
         assert_eq!(indices, expected);
     }
+    #[test]
+    fn test_indices_are_distinct() {
+        assert!(indices_are_distinct(&[7, 3, 9, 1]));
+        assert!(!indices_are_distinct(&[7, 3, 9, 3]));
+        assert!(indices_are_distinct(&[]));
+    }
+
+    #[test]
+    fn test_params_getters() {
+        let p = Params::new(200, 9).unwrap();
+        assert_eq!(p, Params::MAINNET);
+        assert_eq!((p.n(), p.k()), (200, 9));
+        assert_eq!(p.collision_bit_length(), 20);
+        assert_ne!(Params::new(144, 5).unwrap(), Params::MAINNET);
+    }
+
+    #[test]
+    fn test_unsupported_params_are_errors_not_panics() {
+        // (16, 3): 4-bit collisions; (576, 8): n exceeds one digest.
+        for (n, k) in [(16, 3), (576, 8)] {
+            assert_eq!(Params::new(n, k), None, "{:?}", (n, k));
+            let err = verify_equihash_solution_with_params(n, k, b"", &[0u8; 64]).unwrap_err();
+            assert_eq!(err.0, Kind::InvalidParams);
+        }
+        // Oversized indices: (200, 7) has 25-bit collisions, i.e. 26-bit indices, one bit past
+        // what the expansion handles; (192, 7) is the largest accepted collision length.
+        assert_eq!(Params::new(200, 7), None);
+        let err = verify_equihash_solution_with_params(200, 7, b"", &[0u8; 416]).unwrap_err();
+        assert_eq!(err.0, Kind::InvalidParams);
+        assert_eq!(Params::new(192, 7).unwrap().collision_bit_length(), 24);
+        // (512, 63) is well-formed, but its 2^63-index solution length overflows `usize`.
+        let p = Params::new(512, 63).unwrap();
+        assert_eq!(indices_from_minimal(p, &[0u8; 64]), None);
+    }
+
+    #[test]
+    fn test_expand_array_rejects_bad_lengths() {
+        // 3 bytes = two 12-bit chunks, each widened to two bytes.
+        assert_eq!(
+            expand_array(&[0xab, 0xcd, 0xef], 12, 0),
+            Some(vec![0x0a, 0xbc, 0x0d, 0xef])
+        );
+        assert_eq!(expand_array(&[], 21, 1), Some(vec![]));
+        // 4 bytes is not a whole number of 12-bit chunks.
+        assert_eq!(expand_array(&[0xab, 0xcd, 0xef, 0x01], 12, 0), None);
+        // Chunk widths the u32 accumulator cannot hold.
+        assert_eq!(expand_array(&[0u8; 7], 7, 0), None);
+        assert_eq!(expand_array(&[0u8; 26], 26, 0), None);
+        assert_eq!(expand_array(&[0u8; 21], 21, 2), None);
+    }
+
+    #[test]
+    fn test_indices_from_minimal_rejects_bad_lengths() {
+        let p = Params::MAINNET;
+        assert_eq!(indices_from_minimal(p, &[]), None);
+        assert_eq!(indices_from_minimal(p, &[0u8; 1343]), None);
+        assert_eq!(indices_from_minimal(p, &[0u8; 1345]), None);
+        assert_eq!(indices_from_minimal(p, &[0u8; 1344]), Some(vec![0u32; 512]));
+    }
+
+    #[test]
+    fn test_wrong_solution_length_is_reported() {
+        assert_eq!(Params::MAINNET.solution_len(), Some(1344));
+        let err = verify_equihash_solution(b"", &[0u8; 1343]).unwrap_err();
+        assert_eq!(
+            err.0,
+            Kind::WrongSolutionLength {
+                expected: 1344,
+                found: 1343
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid solution: solution is 1343 bytes, expected 1344 for these parameters"
+        );
+    }
 }
@@ -1,21 +1,45 @@
 //! Minimal Equihash and difficulty verification for Zcash-style block headers.
 //!
 //! This crate exposes:
-//! - Equihash (n=200,k=9) verification: `verify_equihash_solution`, `verify_equihash_solution_with_params`
+//! - Equihash (n=200,k=9) verification: `verify_equihash_solution`, `verify_equihash_solution_with_params`,
+//!   and low-allocation `verify_equihash_solution_streaming{,_with_params}` variants
 //! - Difficulty filter: `verify_difficulty` (alias for `verify_difficulty_filter`)
 //! - Contextual difficulty: `difficulty::context::{DifficultyContext, expected_nbits, verify_difficulty}`
-//! - Combined helpers: `verify_pow`, `verify_pow_with_context`
+//! - Chain work for most-work fork choice: `difficulty::{work_from_nbits, ChainWork}`
+//! - Comparing two `nBits` by the targets they encode: `difficulty::nbits_cmp`
+//! - Explorer-style difficulty for display: `difficulty::difficulty_ratio`
+//! - Header hash straight from serialized bytes: `hash_header_bytes`
+//! - Combined helpers: `verify_pow`, `verify_pow_from_bytes`, `verify_pow_with_context`, `powheader_bytes`
+//!   (plus the read-only `check_pow_with_context`, and `verify_difficulty_only_with_context`, which
+//!   trusts Equihash)
+//! - Health reports: `diagnose_pow` runs the `verify_pow` checks independently and returns each result
+//! - Parent linkage: `verify_pow_linked` checks `prev_block` against a known parent hash first
+//! - Choosing the checks: `verify_pow_flags` with `VerifyFlags::{EQUIHASH, FILTER, CONTEXT, TIMESTAMP}`,
+//!   which the combined helpers wrap (read-only: `check_pow_flags`)
+mod blake2b;
 pub mod difficulty;
 pub mod equihash;
 
-use cairo_runner::run_stwo;
 use cairo_runner::types::InputData;
+use cairo_runner::{Felt252, execute, remove_stwo_files, run_stwo};
 use core::fmt;
+use core::ops::{BitOr, BitOrAssign};
+use difficulty::target::Target;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 use zcash_primitives::block::BlockHeader;
 
-pub use difficulty::context::DifficultyContext;
-pub use difficulty::filter::{DiffError, verify_difficulty, verify_difficulty_filter};
-pub use equihash::{Error, Kind, verify_equihash_solution, verify_equihash_solution_with_params};
+pub use difficulty::context::{DifficultyContext, DifficultyContextBuilder};
+pub use difficulty::filter::{
+    DiffError, verify_difficulty, verify_difficulty_filter, verify_difficulty_filter_for_network,
+};
+pub use difficulty::network::Network;
+pub use equihash::{
+    Error, Kind, verify_equihash_solution, verify_equihash_solution_for_params,
+    verify_equihash_solution_streaming, verify_equihash_solution_streaming_for_params,
+    verify_equihash_solution_streaming_with_params, verify_equihash_solution_with_params,
+    verify_equihash_solution_with_personal,
+};
 
 /// Combined Equihash + difficulty verification error.
 #[derive(Debug)]
@@ -23,6 +47,19 @@ pub enum PowError {
     Equihash(Error),
     Difficulty(DiffError),
     ContextDifficulty(DiffError),
+    /// The raw header bytes could not be decoded into a `BlockHeader`.
+    Decode(String),
+    /// The solution is not the length Equihash `(200, 9)` requires, or the Cairo input does not
+    /// carry the powheader verified in Rust.
+    MalformedHeader(String),
+    /// The Cairo program failed to run on the header, or its output disagrees with the header
+    /// hash computed in Rust.
+    Cairo(String),
+    /// `prev_block` is not the parent hash the caller expected (both in consensus byte order).
+    PrevMismatch {
+        expected: [u8; 32],
+        found: [u8; 32],
+    },
 }
 
 impl fmt::Display for PowError {
@@ -31,15 +68,88 @@ impl fmt::Display for PowError {
             PowError::Equihash(e) => write!(f, "Equihash error: {e}"),
             PowError::Difficulty(e) => write!(f, "Difficulty filter error: {e}"),
             PowError::ContextDifficulty(e) => write!(f, "Contextual difficulty error: {e}"),
+            PowError::Decode(e) => write!(f, "Header decode error: {e}"),
+            PowError::MalformedHeader(e) => write!(f, "Malformed header: {e}"),
+            PowError::Cairo(e) => write!(f, "Cairo execution error: {e}"),
+            PowError::PrevMismatch { expected, found } => write!(
+                f,
+                "prev_block {} does not match expected parent {}",
+                rpc_hex(found),
+                rpc_hex(expected)
+            ),
         }
     }
 }
 
 impl std::error::Error for PowError {}
 
-/// Verifies both the Equihash solution and difficulty filter for a parsed `BlockHeader`.
-pub fn verify_pow(header: &BlockHeader) -> Result<(), PowError> {
-    // Reconstruct the Equihash "powheader": header bytes up to and including the nonce.
+/// Hex of a consensus-order hash as RPC and block explorers show it.
+fn rpc_hex(hash: &[u8; 32]) -> String {
+    let mut be = *hash;
+    be.reverse();
+    hex::encode(be)
+}
+
+/// Set of PoW checks for [`verify_pow_flags`] to run, combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct VerifyFlags(u8);
+
+impl VerifyFlags {
+    /// Equihash solution validity.
+    pub const EQUIHASH: Self = Self(1 << 0);
+    /// `SHA256d(header) <= ToTarget(nBits)`, against the context's network (mainnet without one).
+    pub const FILTER: Self = Self(1 << 1);
+    /// Contextual difficulty (expected `nBits`); needs a ready [`DifficultyContext`].
+    pub const CONTEXT: Self = Self(1 << 2);
+    /// `nTime` after the context's median-time-past; needs 11 headers in the context.
+    pub const TIMESTAMP: Self = Self(1 << 3);
+    /// Every check: full verification.
+    pub const ALL: Self =
+        Self(Self::EQUIHASH.0 | Self::FILTER.0 | Self::CONTEXT.0 | Self::TIMESTAMP.0);
+
+    /// No checks.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Whether every check in `other` is selected.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for VerifyFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for VerifyFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Reconstructs the Equihash "powheader": header bytes up to and including the nonce.
+///
+/// This is the 140-byte input bound into the Equihash solution. It must equal the
+/// prefix of `BlockHeader::write`, which lays the header out as:
+///
+/// | offset | size | field                                        |
+/// |--------|------|----------------------------------------------|
+/// | 0      | 4    | `version` (LE32)                             |
+/// | 4      | 32   | `prev_block`                                 |
+/// | 36     | 32   | `merkle_root`                                |
+/// | 68     | 32   | `final_sapling_root` (block commitments)     |
+/// | 100    | 4    | `time` (LE32)                                |
+/// | 104    | 4    | `bits` (LE32)                                |
+/// | 108    | 32   | `nonce`                                      |
+/// | 140    | var  | compactsize length + Equihash `solution`     |
+///
+/// Debug builds assert this against `BlockHeader::write` to catch upstream drift.
+pub fn powheader_bytes(header: &BlockHeader) -> Vec<u8> {
     let mut powheader = Vec::with_capacity(140);
     powheader.extend_from_slice(&header.version.to_le_bytes());
     powheader.extend_from_slice(&header.prev_block.0);
@@ -49,81 +159,341 @@ pub fn verify_pow(header: &BlockHeader) -> Result<(), PowError> {
     powheader.extend_from_slice(&header.bits.to_le_bytes());
     powheader.extend_from_slice(&header.nonce);
 
-    // 1. Equihash solution validity.
-    equihash::verify_equihash_solution(&powheader, &header.solution).map_err(PowError::Equihash)?;
+    debug_assert!(
+        {
+            let mut serialized = Vec::new();
+            header.write(&mut serialized).is_ok() && serialized.starts_with(&powheader)
+        },
+        "powheader diverges from BlockHeader::write serialization"
+    );
+    powheader
+}
+
+/// SHA256d of a serialized header, without decoding it.
+///
+/// `serialized_header` must be the complete header as laid out in [`powheader_bytes`]: the
+/// 140-byte powheader followed by the compactsize-prefixed solution (1487 bytes on mainnet),
+/// exactly as `getblockheader <hash> false` returns it. Nothing is checked, so any other
+/// input just hashes to some other value.
+///
+/// The result is in consensus (little-endian) byte order, i.e. equal to
+/// `BlockHeader::hash().0` and ready for [`verify_difficulty_filter`]; reverse it for the
+/// hex shown by RPC and block explorers.
+pub fn hash_header_bytes(serialized_header: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(serialized_header)).into()
+}
+
+/// Verifies both the Equihash solution and difficulty filter for a parsed `BlockHeader`.
+pub fn verify_pow(header: &BlockHeader) -> Result<(), PowError> {
+    verify_pow_flags(header, 0, None, VerifyFlags::EQUIHASH | VerifyFlags::FILTER)
+}
+
+/// Runs exactly the checks selected by `flags`, in the order Equihash, filter, context,
+/// timestamp, and returns the first failure.
+///
+/// `height` and `ctx` are only used by [`VerifyFlags::CONTEXT`] and [`VerifyFlags::TIMESTAMP`],
+/// which fail with [`DiffError::InsufficientContext`] when `ctx` is `None` or too short. On
+/// success with `CONTEXT` selected the header is appended to `ctx`; otherwise `ctx` is never
+/// modified.
+///
+/// Any selection without both `EQUIHASH` and `FILTER` is weaker than consensus verification;
+/// see [`verify_difficulty_only_with_context`].
+pub fn verify_pow_flags(
+    header: &BlockHeader,
+    height: u32,
+    ctx: Option<&mut DifficultyContext>,
+    flags: VerifyFlags,
+) -> Result<(), PowError> {
+    check_pow_flags(header, height, ctx.as_deref(), flags)?;
+    if flags.contains(VerifyFlags::CONTEXT)
+        && let Some(ctx) = ctx
+    {
+        ctx.push_header(height, header.time, header.bits);
+    }
+    Ok(())
+}
+
+/// Checks that `header.solution` is as long as a minimal `(200, 9)` solution (1344 bytes).
+///
+/// `BlockHeader::read` accepts a solution of any length its compactSize prefix declares; this
+/// rejects a corrupt one up front with [`PowError::MalformedHeader`], rather than as an
+/// Equihash error from deep in the tree walk.
+pub fn check_solution_len(header: &BlockHeader) -> Result<(), PowError> {
+    let expected = equihash::Params::MAINNET
+        .solution_len()
+        .expect("the (200, 9) solution length fits in usize");
+    if header.solution.len() != expected {
+        return Err(PowError::MalformedHeader(format!(
+            "solution is {} bytes, expected {expected} for Equihash (200, 9)",
+            header.solution.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Read-only [`verify_pow_flags`]: the same checks, but `ctx` is never modified, so a caller can
+/// verify first and commit the header to the context later (e.g. once it has been persisted).
+pub fn check_pow_flags(
+    header: &BlockHeader,
+    height: u32,
+    ctx: Option<&DifficultyContext>,
+    flags: VerifyFlags,
+) -> Result<(), PowError> {
+    if flags.contains(VerifyFlags::EQUIHASH) {
+        check_solution_len(header)?;
+        let powheader = powheader_bytes(header);
+        equihash::verify_equihash_solution(&powheader, &header.solution)
+            .map_err(PowError::Equihash)?;
+    }
+
+    if flags.contains(VerifyFlags::FILTER) {
+        let network = ctx.map_or(Network::Mainnet, DifficultyContext::network);
+        let hash = header.hash();
+        difficulty::filter::verify_difficulty_filter_for_network(&hash.0, header.bits, network)
+            .map_err(PowError::Difficulty)?;
+    }
+
+    let ctx = || ctx.ok_or(PowError::ContextDifficulty(DiffError::InsufficientContext));
+    if flags.contains(VerifyFlags::CONTEXT) {
+        difficulty::context::verify_difficulty(ctx()?, height, header.bits)
+            .map_err(PowError::ContextDifficulty)?;
+    }
 
-    // 2. Difficulty filter using the full header hash and nBits.
-    let hash = header.hash();
-    difficulty::filter::verify_difficulty(&hash.0, header.bits).map_err(PowError::Difficulty)
+    if flags.contains(VerifyFlags::TIMESTAMP) {
+        difficulty::context::verify_timestamp(ctx()?, header.time)
+            .map_err(PowError::ContextDifficulty)?;
+    }
+    Ok(())
 }
 
+/// Outcome of every context-free PoW check on a header, from [`diagnose_pow`].
+#[derive(Debug)]
+pub struct PowDiagnostics {
+    pub equihash: Result<(), Error>,
+    /// The mainnet difficulty filter, as in [`verify_pow`].
+    pub difficulty: Result<(), DiffError>,
+    /// `SHA256d(header)` as a little-endian integer, i.e. `BlockHeader::hash().0`.
+    pub hash: Target,
+    /// `ToTarget(nBits)`; [`Target::ZERO`] for an invalid encoding.
+    pub target: Target,
+}
+
+impl PowDiagnostics {
+    /// `true` when every check passed, i.e. exactly when [`verify_pow`] succeeds.
+    pub fn passed(&self) -> bool {
+        self.equihash.is_ok() && self.difficulty.is_ok()
+    }
+}
+
+/// Runs the checks of [`verify_pow`] independently and reports each outcome, for health reports
+/// that show every failing aspect of a header rather than the first.
+pub fn diagnose_pow(header: &BlockHeader) -> PowDiagnostics {
+    let hash = header.hash().0;
+    PowDiagnostics {
+        equihash: verify_equihash_solution(&powheader_bytes(header), &header.solution),
+        difficulty: verify_difficulty_filter(&hash, header.bits),
+        hash: Target(hash),
+        target: Target::from_nbits(header.bits),
+    }
+}
+
+/// Decodes a serialized header with `BlockHeader::read` and runs [`verify_pow`] on it.
+pub fn verify_pow_from_bytes(raw: &[u8]) -> Result<(), PowError> {
+    let header = BlockHeader::read(raw).map_err(|e| PowError::Decode(e.to_string()))?;
+    verify_pow(&header)
+}
+
+/// The compiled Cairo verifier, relative to the working directory.
+const CAIRO_PROGRAM: &str = "cairo/build/main.json";
+
+/// Where the compiled Cairo verifier is loaded from: `$CAIRO_PROGRAM_PATH` if set, otherwise
+/// `cairo/build/main.json` under the working directory.
+pub fn cairo_program_path() -> PathBuf {
+    std::env::var_os("CAIRO_PROGRAM_PATH")
+        .map_or_else(|| PathBuf::from(CAIRO_PROGRAM), PathBuf::from)
+}
+
+/// Directory `verify_pow_in_cairo` writes the trace and proof for `height` into, relative to
+/// the working directory.
+fn cairo_output_dir(height: u32) -> String {
+    format!("output/block_{height}")
+}
+
+/// Where [`verify_pow_in_cairo`] with `prove` set leaves the STWO proof for `height`.
+pub fn cairo_proof_path(height: u32) -> PathBuf {
+    Path::new(&cairo_output_dir(height)).join(format!("proof_block_{height}.json"))
+}
+
+/// Checks that `input` has the lengths the Cairo program expects ([`InputData::validate`]) and
+/// packs exactly `powheader`, the bytes verified in Rust.
+///
+/// The Cairo program only sees the `u32` words of [`InputData::header_bytes`], so a packing
+/// mistake there would otherwise surface only after a full trace (and proof) over the wrong
+/// header, or as a panic inside the VM.
+pub fn check_cairo_input(powheader: &[u8], input: &InputData) -> Result<(), PowError> {
+    input
+        .validate()
+        .map_err(|e| PowError::MalformedHeader(format!("Cairo input: {e}")))?;
+    let packed = input.powheader();
+    if packed != powheader {
+        return Err(PowError::MalformedHeader(format!(
+            "Cairo input packs {} powheader bytes that differ from the {} verified",
+            packed.len(),
+            powheader.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that `output`, as the Cairo verifier writes it, is `header`'s hash.
+///
+/// The program outputs the hash as the `Uint256` it compares to the target: the little-endian
+/// integer of the consensus-order bytes, low 128 bits first. A mismatch means the program hashed
+/// something other than the header verified in Rust.
+pub fn check_cairo_output(header: &BlockHeader, output: &[Felt252]) -> Result<(), PowError> {
+    let hash = header.hash().0;
+    let limb = |bytes: &[u8]| Felt252::from(u128::from_le_bytes(bytes.try_into().unwrap()));
+    let expected = [limb(&hash[..16]), limb(&hash[16..])];
+    if output != expected {
+        return Err(PowError::Cairo(format!(
+            "program output {output:?} is not the header hash {}",
+            rpc_hex(&hash)
+        )));
+    }
+    Ok(())
+}
+
+/// Runs the Cairo verifier on `header` and, with `prove`, proves the run, leaving the trace and
+/// the proof under `output/block_<height>`.
+///
+/// Without `prove` nothing would use a trace, so this is [`execute_pow_in_cairo`] and writes no
+/// files at all.
 pub fn verify_pow_in_cairo(header: &BlockHeader, height: u32, prove: bool) -> Result<(), PowError> {
-    let mut powheader = Vec::with_capacity(140);
-    powheader.extend_from_slice(&header.version.to_le_bytes());
-    powheader.extend_from_slice(&header.prev_block.0);
-    powheader.extend_from_slice(&header.merkle_root);
-    powheader.extend_from_slice(&header.final_sapling_root);
-    powheader.extend_from_slice(&header.time.to_le_bytes());
-    powheader.extend_from_slice(&header.bits.to_le_bytes());
-    powheader.extend_from_slice(&header.nonce);
+    if !prove {
+        return execute_pow_in_cairo(header);
+    }
+    verify_pow_in_cairo_at(header, height, prove, &cairo_output_dir(height), true)
+}
 
-    let header_bytes: Vec<u32> = powheader
-        .chunks_exact(4)
-        .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-        .collect();
-    let solution_bytes = header
-        .solution
-        .chunks_exact(4)
-        .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-        .collect();
-
-    let input = InputData {
-        header_bytes,
-        solution_bytes,
-    };
-
-    let output_dir = format!("output/block_{height}");
-    run_stwo(
-        "cairo/build/main.json",
+/// Runs the Cairo verifier on `header` without recording a trace or writing any file, which
+/// is all that is needed when not proving and much cheaper than tracing with
+/// [`verify_pow_in_cairo_at`].
+pub fn execute_pow_in_cairo(header: &BlockHeader) -> Result<(), PowError> {
+    let powheader = powheader_bytes(header);
+
+    let input = InputData::from_powheader(&powheader, &header.solution);
+    check_cairo_input(&powheader, &input)?;
+
+    let program = cairo_program_path();
+    let output =
+        execute(&program.to_string_lossy(), input).map_err(|e| PowError::Cairo(e.to_string()))?;
+    check_cairo_output(header, &output)
+}
+
+/// [`verify_pow_in_cairo`] writing into `output_dir`, which is created if needed.
+///
+/// Runs sharing an `output_dir` overwrite each other's trace files, so concurrent runs need one
+/// each. Without `keep_trace` the trace files are deleted once the run succeeds, leaving only
+/// the proof (if `prove`), or nothing.
+pub fn verify_pow_in_cairo_at(
+    header: &BlockHeader,
+    height: u32,
+    prove: bool,
+    output_dir: &str,
+    keep_trace: bool,
+) -> Result<(), PowError> {
+    let powheader = powheader_bytes(header);
+
+    let input = InputData::from_powheader(&powheader, &header.solution);
+    check_cairo_input(&powheader, &input)?;
+
+    let run = run_stwo(
+        &cairo_program_path().to_string_lossy(),
         input,
         "info",
-        &output_dir,
+        output_dir,
         prove,
         false,
         Some(height),
     )
-    .unwrap();
+    .map_err(|e| PowError::Cairo(e.to_string()))?;
+    check_cairo_output(header, &run.output)?;
+
+    if !keep_trace {
+        // Leftover trace files only cost disk space; the block itself verified.
+        let _ = remove_stwo_files(output_dir);
+    }
 
     Ok(())
 }
 
-/// Verifies Equihash, the difficulty filter, and contextual difficulty for a header.
+/// Verifies Equihash, the difficulty filter, contextual difficulty, and that the header's time
+/// is after the context's median-time-past.
 ///
 /// The caller is responsible for maintaining `ctx` in chain order. On success,
-/// this function appends the header to the context.
+/// this function appends the header to the context; on failure `ctx` is unchanged.
 pub fn verify_pow_with_context(
     header: &BlockHeader,
     height: u32,
     ctx: &mut DifficultyContext,
 ) -> Result<(), PowError> {
-    // Reconstruct the Equihash "powheader": header bytes up to and including the nonce.
-    let mut powheader = Vec::with_capacity(140);
-    powheader.extend_from_slice(&header.version.to_le_bytes());
-    powheader.extend_from_slice(&header.prev_block.0);
-    powheader.extend_from_slice(&header.merkle_root);
-    powheader.extend_from_slice(&header.final_sapling_root);
-    powheader.extend_from_slice(&header.time.to_le_bytes());
-    powheader.extend_from_slice(&header.bits.to_le_bytes());
-    powheader.extend_from_slice(&header.nonce);
-
-    equihash::verify_equihash_solution(&powheader, &header.solution).map_err(PowError::Equihash)?;
+    verify_pow_flags(header, height, Some(ctx), VerifyFlags::ALL)
+}
 
-    let hash = header.hash();
-    difficulty::filter::verify_difficulty(&hash.0, header.bits).map_err(PowError::Difficulty)?;
+/// [`verify_pow_with_context`] for a header whose parent is already known, e.g. from a
+/// trusted checkpoint or the previously verified tip.
+///
+/// With `expected_prev` set, `header.prev_block` must equal it (consensus byte order, as
+/// `BlockHeader::hash().0`) or [`PowError::PrevMismatch`] is returned before any PoW check
+/// runs. `None` skips the linkage check.
+pub fn verify_pow_linked(
+    header: &BlockHeader,
+    height: u32,
+    ctx: &mut DifficultyContext,
+    expected_prev: Option<[u8; 32]>,
+) -> Result<(), PowError> {
+    if let Some(expected) = expected_prev
+        && header.prev_block.0 != expected
+    {
+        return Err(PowError::PrevMismatch {
+            expected,
+            found: header.prev_block.0,
+        });
+    }
+    verify_pow_with_context(header, height, ctx)
+}
 
-    difficulty::context::verify_difficulty(ctx, height, header.bits)
-        .map_err(PowError::ContextDifficulty)?;
+/// Runs the checks of [`verify_pow_with_context`] without appending the header to `ctx`.
+///
+/// Useful for speculative verification, e.g. of a candidate tip that may not be adopted:
+/// the header is only committed by a later [`verify_pow_with_context`] (or
+/// [`DifficultyContext::push_header`]).
+pub fn check_pow_with_context(
+    header: &BlockHeader,
+    height: u32,
+    ctx: &DifficultyContext,
+) -> Result<(), PowError> {
+    check_pow_flags(header, height, Some(ctx), VerifyFlags::ALL)
+}
 
-    ctx.push_header(height, header.time, header.bits);
-    Ok(())
+/// Like [`verify_pow_with_context`] but skips Equihash: checks only the difficulty filter,
+/// contextual difficulty and median-time-past, then appends the header to `ctx`.
+///
+/// This is **not** full consensus verification: a header with any solution at all, valid or
+/// not, passes. It is only meant for fast scans of chain structure and re-auditing difficulty
+/// adjustment over headers whose Equihash solutions are already trusted (e.g. a node's own
+/// chain). Without Equihash, a forger can meet the target by grinding the nonce with plain
+/// SHA256d hashes, skipping the memory-hard Equihash solve that each attempt would otherwise
+/// need.
+pub fn verify_difficulty_only_with_context(
+    header: &BlockHeader,
+    height: u32,
+    ctx: &mut DifficultyContext,
+) -> Result<(), PowError> {
+    verify_pow_flags(
+        header,
+        height,
+        Some(ctx),
+        VerifyFlags::FILTER | VerifyFlags::CONTEXT | VerifyFlags::TIMESTAMP,
+    )
 }
@@ -1,32 +1,13 @@
 use cairo_runner::{run_stwo, types::InputData};
+use zcash_crypto::powheader_bytes;
 use zcash_primitives::block::BlockHeader;
 
 fn main() {
     let header = BlockHeader::read(&HEADER_MAINNET_415000[..]).unwrap();
 
-    let mut powheader = Vec::with_capacity(140);
-    powheader.extend_from_slice(&header.version.to_le_bytes());
-    powheader.extend_from_slice(&header.prev_block.0);
-    powheader.extend_from_slice(&header.merkle_root);
-    powheader.extend_from_slice(&header.final_sapling_root);
-    powheader.extend_from_slice(&header.time.to_le_bytes());
-    powheader.extend_from_slice(&header.bits.to_le_bytes());
-    powheader.extend_from_slice(&header.nonce);
+    let powheader = powheader_bytes(&header);
 
-    let header_bytes: Vec<u32> = powheader
-        .chunks_exact(4)
-        .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-        .collect();
-    let solution_bytes = header
-        .solution
-        .chunks_exact(4)
-        .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-        .collect();
-
-    let input = InputData {
-        header_bytes,
-        solution_bytes,
-    };
+    let input = InputData::from_powheader(&powheader, &header.solution);
 
     run_stwo(
         "cairo/build/main.json",
@@ -0,0 +1,29 @@
+//! The real mainnet headers committed in `data/headers.jsonl`.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+const HEADERS_JSONL: &str = include_str!("../../../../data/headers.jsonl");
+
+/// Returns the committed `(height, header bytes)` fixtures in ascending height order.
+pub fn fixture_headers() -> Vec<(u32, Vec<u8>)> {
+    let mut headers = BTreeMap::new();
+    for line in HEADERS_JSONL.lines().filter(|l| !l.trim().is_empty()) {
+        let rec: Value = serde_json::from_str(line).unwrap();
+        let height = rec["height"].as_u64().unwrap() as u32;
+        let raw = hex::decode(rec["header_hex"].as_str().unwrap()).unwrap();
+        headers.insert(height, raw);
+    }
+    headers.into_iter().collect()
+}
+
+/// The serialized fixture header at `height`.
+pub fn header_bytes(height: u32) -> Vec<u8> {
+    fixture_headers()
+        .into_iter()
+        .find(|(h, _)| *h == height)
+        .map(|(_, raw)| raw)
+        .unwrap_or_else(|| panic!("no fixture for height {height}"))
+}
@@ -112,3 +112,37 @@ fn verify_pow_header_415000() {
     let header = BlockHeader::read(&HEADER_MAINNET_415000[..]).unwrap();
     verify_pow(&header).unwrap();
 }
+
+#[test]
+fn powheader_bytes_header_415000() {
+    use zcash_crypto::powheader_bytes;
+    use zcash_primitives::block::BlockHeader;
+
+    let header = BlockHeader::read(&HEADER_MAINNET_415000[..]).unwrap();
+    let powheader = powheader_bytes(&header);
+    assert_eq!(powheader.len(), 140);
+    assert_eq!(powheader[..], HEADER_MAINNET_415000[..140]);
+}
+
+#[test]
+fn powheader_bytes_is_prefix_of_header_write() {
+    use zcash_crypto::powheader_bytes;
+    use zcash_primitives::block::BlockHeader;
+
+    let header = BlockHeader::read(&HEADER_MAINNET_415000[..]).unwrap();
+    let mut serialized = Vec::new();
+    header.write(&mut serialized).unwrap();
+    assert_eq!(serialized[..], HEADER_MAINNET_415000[..]);
+    assert_eq!(serialized[..140], powheader_bytes(&header)[..]);
+}
+
+#[test]
+fn verify_pow_from_bytes_header_415000() {
+    use zcash_crypto::{PowError, verify_pow_from_bytes};
+
+    verify_pow_from_bytes(&HEADER_MAINNET_415000[..]).unwrap();
+    assert!(matches!(
+        verify_pow_from_bytes(&HEADER_MAINNET_415000[..100]),
+        Err(PowError::Decode(_))
+    ));
+}
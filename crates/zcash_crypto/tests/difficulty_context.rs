@@ -0,0 +1,420 @@
+use zcash_crypto::difficulty::context::{
+    DifficultyExplanation, expected_nbits, explain, verify_difficulty_with_override,
+};
+use zcash_crypto::difficulty::target::Target;
+use zcash_crypto::difficulty::{MAX_FUTURE_BLOCK_TIME, verify_future_time};
+use zcash_crypto::{DiffError, DifficultyContext, DifficultyContextBuilder, Network};
+
+const BITS: u32 = 0x1c0a_3e4f;
+
+fn push_n(ctx: &mut DifficultyContext, n: u32) {
+    for _ in 0..n {
+        let height = ctx.tip_height + 1;
+        ctx.push_header(height, 1_700_000_000 + height * 75, BITS);
+    }
+}
+
+#[test]
+fn partially_filled_context_is_not_ready() {
+    let mut ctx = DifficultyContext::new(99);
+    assert!(!ctx.is_ready());
+    assert_eq!((ctx.times_len(), ctx.bits_len()), (0, 0));
+
+    // Enough nBits for the averaging window, but not enough timestamps for both medians.
+    push_n(&mut ctx, 20);
+    assert_eq!((ctx.times_len(), ctx.bits_len()), (20, 17));
+    assert!(!ctx.is_ready());
+    assert!(matches!(
+        expected_nbits(&ctx, ctx.tip_height + 1),
+        Err(DiffError::InsufficientContext)
+    ));
+
+    push_n(&mut ctx, 8);
+    assert_eq!((ctx.times_len(), ctx.bits_len()), (28, 17));
+    assert!(ctx.is_ready());
+    assert!(expected_nbits(&ctx, ctx.tip_height + 1).is_ok());
+
+    // The window stays bounded as the context advances.
+    push_n(&mut ctx, 5);
+    assert_eq!((ctx.times_len(), ctx.bits_len()), (28, 17));
+    assert!(ctx.is_ready());
+}
+
+#[test]
+fn required_context_matches_mainnet_window() {
+    // PoWMedianBlockSpan (11) + PoWAveragingWindow (17).
+    let mut ctx = DifficultyContext::new(99);
+    let required = ctx.required_context();
+    assert_eq!(required, 28);
+    assert_eq!(DifficultyContext::REQUIRED_WINDOW, 28);
+
+    push_n(&mut ctx, required as u32 - 1);
+    assert!(!ctx.is_ready());
+    push_n(&mut ctx, 1);
+    assert!(ctx.is_ready());
+}
+
+fn push_times(ctx: &mut DifficultyContext, times: &[u32]) {
+    for &time in times {
+        let height = ctx.tip_height + 1;
+        ctx.push_header(height, time, BITS);
+    }
+}
+
+#[test]
+fn median_time_past_needs_eleven_times() {
+    let mut ctx = DifficultyContext::new(99);
+    assert_eq!(ctx.median_time_past(), None);
+
+    // Out of order on purpose: the median is of values, not positions.
+    let times = [110, 100, 108, 102, 106, 104, 109, 101, 107, 103];
+    push_times(&mut ctx, &times);
+    assert_eq!(ctx.median_time_past(), None);
+    assert!(matches!(
+        ctx.verify_timestamp(u32::MAX),
+        Err(DiffError::InsufficientContext)
+    ));
+
+    // The 11th sample completes the window: sorted 100..=110, median 105.
+    push_times(&mut ctx, &[105]);
+    assert_eq!(ctx.median_time_past(), Some(105));
+
+    // A 12th sample evicts the oldest (110): the window is now 99..=109.
+    push_times(&mut ctx, &[99]);
+    assert_eq!(ctx.median_time_past(), Some(104));
+}
+
+#[test]
+fn timestamp_must_exceed_median_time_past() {
+    let mut ctx = DifficultyContext::new(99);
+    push_times(
+        &mut ctx,
+        &[100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110],
+    );
+    assert_eq!(ctx.median_time_past(), Some(105));
+
+    // Backdated and equal-to-median times are rejected, one second later is fine.
+    for backdated in [0, 104, 105] {
+        assert!(matches!(
+            ctx.verify_timestamp(backdated),
+            Err(DiffError::TimeTooOld {
+                median_time_past: 105,
+                found
+            }) if found == backdated
+        ));
+    }
+    ctx.verify_timestamp(106).unwrap();
+    // Earlier than the tip's own time is allowed as long as it beats the median.
+    ctx.verify_timestamp(107).unwrap();
+}
+
+#[test]
+fn future_time_is_bounded_by_now_plus_drift() {
+    let now = 1_700_000_000;
+    for time in [0, now, now + MAX_FUTURE_BLOCK_TIME] {
+        verify_future_time(time, now, MAX_FUTURE_BLOCK_TIME).unwrap();
+    }
+    for time in [now + MAX_FUTURE_BLOCK_TIME + 1, u32::MAX] {
+        assert!(matches!(
+            verify_future_time(time, now, MAX_FUTURE_BLOCK_TIME),
+            Err(DiffError::TimeTooFarInFuture { time: t, limit })
+                if t == time && limit == now + MAX_FUTURE_BLOCK_TIME
+        ));
+    }
+
+    // Zero drift allows exactly `now`; the limit saturates instead of wrapping near `u32::MAX`.
+    verify_future_time(now, now, 0).unwrap();
+    assert!(verify_future_time(now + 1, now, 0).is_err());
+    verify_future_time(u32::MAX, u32::MAX - 1, MAX_FUTURE_BLOCK_TIME).unwrap();
+}
+
+#[test]
+fn override_replaces_the_computed_nbits() {
+    let mut ctx = DifficultyContext::new(0);
+    push_n(&mut ctx, DifficultyContext::REQUIRED_WINDOW as u32);
+    let height = ctx.tip_height + 1;
+    let computed = expected_nbits(&ctx, height).unwrap();
+    let checkpoint = computed + 1;
+
+    verify_difficulty_with_override(&ctx, height, checkpoint, Some(checkpoint)).unwrap();
+    assert!(matches!(
+        verify_difficulty_with_override(&ctx, height, computed, Some(checkpoint)),
+        Err(DiffError::BitsMismatch { expected, found })
+            if expected == checkpoint && found == computed
+    ));
+
+    // Without an override the computed value applies as usual.
+    verify_difficulty_with_override(&ctx, height, computed, None).unwrap();
+    assert!(verify_difficulty_with_override(&ctx, height, checkpoint, None).is_err());
+
+    // An override needs no context at all.
+    let empty = DifficultyContext::new(0);
+    verify_difficulty_with_override(&empty, 1, checkpoint, Some(checkpoint)).unwrap();
+    assert!(matches!(
+        verify_difficulty_with_override(&empty, 1, checkpoint, None),
+        Err(DiffError::InsufficientContext)
+    ));
+}
+
+#[test]
+fn try_push_rejects_gapped_heights() {
+    // Any height starts an empty context, whatever tip it was created with.
+    let mut ctx = DifficultyContext::new(0);
+    ctx.try_push_header(100, 1_700_000_000, BITS).unwrap();
+    assert_eq!(ctx.tip_height, 100);
+    ctx.try_push_header(101, 1_700_000_075, BITS).unwrap();
+
+    let before = ctx.clone();
+    for height in [103, 101, 100] {
+        assert!(matches!(
+            ctx.try_push_header(height, 1_700_000_150, BITS),
+            Err(DiffError::HeightMismatch {
+                expected: 102,
+                found
+            }) if found == height
+        ));
+    }
+    assert_eq!(ctx, before);
+
+    ctx.try_push_header(102, 1_700_000_150, BITS).unwrap();
+    assert_eq!((ctx.tip_height, ctx.times_len()), (102, 3));
+}
+
+#[test]
+fn builder_rejects_out_of_order_heights() {
+    let mut builder = DifficultyContextBuilder::new(Network::Mainnet);
+    builder.push(100, 1_700_000_000, BITS).unwrap();
+    builder.push(101, 1_700_000_075, BITS).unwrap();
+
+    for height in [101, 100, 103, 0] {
+        assert!(matches!(
+            builder.push(height, 1_700_000_150, BITS),
+            Err(DiffError::HeightMismatch {
+                expected: 102,
+                found
+            }) if found == height
+        ));
+    }
+    // Rejected pushes leave nothing behind.
+    assert_eq!(builder.missing(), DifficultyContext::REQUIRED_WINDOW - 2);
+}
+
+#[test]
+fn builder_only_builds_a_ready_context() {
+    let required = DifficultyContext::REQUIRED_WINDOW as u32;
+    let mut builder = DifficultyContextBuilder::new(Network::Testnet);
+    for height in 1000..1000 + required - 1 {
+        builder
+            .push(height, 1_700_000_000 + height * 75, BITS)
+            .unwrap();
+    }
+    assert!(!builder.is_ready());
+    assert_eq!(builder.missing(), 1);
+    assert!(matches!(
+        builder.clone().build(),
+        Err(DiffError::InsufficientContext)
+    ));
+
+    let height = 1000 + required - 1;
+    builder
+        .push(height, 1_700_000_000 + height * 75, BITS)
+        .unwrap();
+    assert!(builder.is_ready());
+    assert_eq!(builder.missing(), 0);
+
+    let ctx = builder.build().unwrap();
+    assert_eq!(ctx.tip_height, height);
+    assert_eq!(ctx.network(), Network::Testnet);
+    assert!(expected_nbits(&ctx, height + 1).is_ok());
+}
+
+#[test]
+fn rewind_past_the_retained_history_fails() {
+    let max_rewind = DifficultyContext::MAX_REWIND as u32;
+    let mut ctx = DifficultyContext::new(99);
+    push_n(
+        &mut ctx,
+        DifficultyContext::REQUIRED_WINDOW as u32 + max_rewind + 5,
+    );
+    let tip = ctx.tip_height;
+    let before = ctx.clone();
+
+    for new_tip in [tip - max_rewind - 1, tip + 1] {
+        assert!(matches!(
+            ctx.rewind_to(new_tip),
+            Err(DiffError::RewindUnavailable { tip_height, new_tip: found })
+                if tip_height == tip && found == new_tip
+        ));
+    }
+    assert_eq!(ctx, before);
+
+    // The deepest rewind still leaves a full window behind.
+    ctx.rewind_to(tip - max_rewind).unwrap();
+    assert_eq!(ctx.tip_height, tip - max_rewind);
+    assert!(ctx.is_ready());
+    assert!(expected_nbits(&ctx, ctx.tip_height + 1).is_ok());
+}
+
+#[test]
+fn successive_rewinds_share_the_retained_history() {
+    let max_rewind = DifficultyContext::MAX_REWIND as u32;
+    let mut ctx = DifficultyContext::new(99);
+    push_n(
+        &mut ctx,
+        DifficultyContext::REQUIRED_WINDOW as u32 + max_rewind + 5,
+    );
+    let tip = ctx.tip_height;
+
+    // Two rewinds in a row reach as deep as one, and no deeper.
+    ctx.rewind_to(tip - 28).unwrap();
+    ctx.rewind_to(tip - max_rewind).unwrap();
+    assert!(ctx.is_ready());
+    let before = ctx.clone();
+    assert!(matches!(
+        ctx.rewind_to(tip - max_rewind - 1),
+        Err(DiffError::RewindUnavailable { .. })
+    ));
+    assert_eq!(ctx, before);
+
+    // Pushing back up does not lower the floor either.
+    push_n(&mut ctx, 10);
+    assert!(matches!(
+        ctx.rewind_to(tip - max_rewind - 1),
+        Err(DiffError::RewindUnavailable { .. })
+    ));
+    ctx.rewind_to(tip - max_rewind).unwrap();
+    assert!(expected_nbits(&ctx, ctx.tip_height + 1).is_ok());
+}
+
+#[test]
+fn young_context_rewinds_to_where_it_started() {
+    let mut ctx = DifficultyContext::new(99);
+    push_n(&mut ctx, 10);
+
+    assert!(matches!(
+        ctx.rewind_to(98),
+        Err(DiffError::RewindUnavailable { .. })
+    ));
+    ctx.rewind_to(105).unwrap();
+    assert_eq!((ctx.tip_height, ctx.times_len()), (105, 6));
+    ctx.rewind_to(99).unwrap();
+    assert!(ctx.is_empty());
+    assert_eq!(ctx.total_work(), [0; 32]);
+}
+
+#[test]
+fn explanation_shows_each_step_of_the_adjustment() {
+    // Blocks every 300 s, four times the 75 s spacing, all at `BITS`.
+    let mut ctx = DifficultyContext::new(99);
+    let times: Vec<u32> = (0..28).map(|i| 1_700_000_000 + i * 300).collect();
+    push_times(&mut ctx, &times);
+
+    let explanation = explain(&ctx, ctx.tip_height + 1, BITS).unwrap();
+
+    // 0x0a3e4f * 256^(0x1c - 3), the target every header in the window encodes.
+    let mean = Target::from_hex("000000000a3e4f00000000000000000000000000000000000000000000000000");
+    // 0x0a3e4f...00 / 1275 * 1683, with the division truncated first.
+    let threshold =
+        Target::from_hex("000000000d8572851eb851eb851eb851eb851eb851eb851eb851eb851eb84e83");
+    assert_eq!(
+        explanation,
+        DifficultyExplanation {
+            mean_target: mean.unwrap(),
+            // 17 blocks apart at 300 s each.
+            actual_timespan: 5100,
+            // 1275 + (5100 - 1275) / 4.
+            damped_timespan: 2231,
+            // At most 32% above 1275.
+            clamped_timespan: 1683,
+            threshold_target: threshold.unwrap(),
+            expected_nbits: 0x1c0d_8572,
+            header_nbits: BITS,
+        }
+    );
+    // Slow blocks ease the difficulty, so the unchanged `BITS` is rejected.
+    assert!(!explanation.matches());
+    assert_eq!(
+        expected_nbits(&ctx, ctx.tip_height + 1).unwrap(),
+        explanation.expected_nbits
+    );
+
+    // Nothing to explain for a height that does not follow the tip.
+    assert!(matches!(
+        explain(&ctx, ctx.tip_height + 2, BITS),
+        Err(DiffError::HeightMismatch { .. })
+    ));
+}
+
+/// A window whose clock runs backward: each block 75 s before its parent.
+const BACKWARD_TIMES: [u32; 28] = {
+    let mut times = [0; 28];
+    let mut i = 0;
+    while i < 28 {
+        times[i] = 1_700_100_000 - 75 * i as u32;
+        i += 1;
+    }
+    times
+};
+
+/// Eleven blocks stamped ~2.8 hours ahead, then the clock jumps back to a regular 75 s spacing,
+/// so the newest median lands well before the oldest one.
+const BACKWARD_JUMP_TIMES: [u32; 28] = {
+    let mut times = [0; 28];
+    let mut i = 0;
+    while i < 28 {
+        let ahead = if i < 11 { 10_000 } else { 0 };
+        times[i] = 1_700_000_000 + ahead + 75 * i as u32;
+        i += 1;
+    }
+    times
+};
+
+/// Every block stamped with the same time, so the two medians are equal.
+const STALLED_TIMES: [u32; 28] = [1_700_000_000; 28];
+
+#[test]
+fn stalled_or_backward_timestamps_clamp_to_the_minimum_timespan() {
+    // zcashd: 1275 + (actual - 1275) / 4, truncated toward zero, then at least 84% of 1275.
+    for (times, actual, damped) in [
+        (&BACKWARD_TIMES, -1275, 638),
+        (&BACKWARD_JUMP_TIMES, -8725, -1225),
+        (&STALLED_TIMES, 0, 957),
+    ] {
+        let mut ctx = DifficultyContext::new(99);
+        push_times(&mut ctx, times);
+
+        let explanation = explain(&ctx, ctx.tip_height + 1, BITS).unwrap();
+        assert_eq!(explanation.actual_timespan, actual);
+        assert_eq!(explanation.damped_timespan, damped);
+        assert_eq!(
+            explanation.clamped_timespan, 1071,
+            "actual timespan {actual}"
+        );
+        // The largest step up in difficulty: 0x0a3e4f...00 / 1275 * 1071.
+        assert_eq!(explanation.expected_nbits, 0x1c08_9abd);
+        assert!(!explanation.matches());
+    }
+}
+
+#[test]
+fn context_bytes_round_trip() {
+    let mut ctx = DifficultyContext::with_network(99, Network::Testnet);
+    push_n(&mut ctx, 40);
+
+    let bytes = ctx.to_bytes();
+    let restored = DifficultyContext::from_bytes(&bytes).unwrap();
+    assert_eq!(restored, ctx);
+    assert_eq!(restored.total_work(), ctx.total_work());
+
+    assert!(DifficultyContext::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    let mut unknown_network = bytes.clone();
+    unknown_network[4] = 7;
+    assert!(DifficultyContext::from_bytes(&unknown_network).is_none());
+
+    // A context that dropped older headers keeps its rewind floor through a round trip.
+    push_n(&mut ctx, DifficultyContext::MAX_REWIND as u32);
+    let mut restored = DifficultyContext::from_bytes(&ctx.to_bytes()).unwrap();
+    assert_eq!(restored, ctx);
+    let deepest = ctx.tip_height - DifficultyContext::MAX_REWIND as u32;
+    assert!(restored.rewind_to(deepest - 1).is_err());
+}
@@ -0,0 +1,166 @@
+use core::cmp::Ordering;
+
+use zcash_crypto::difficulty::target::{NBitsError, POW_LIMIT, Target};
+use zcash_crypto::{
+    DiffError, Network, verify_difficulty_filter, verify_difficulty_filter_for_network,
+};
+
+/// Mainnet PoW limit in compact form (2^243 - 1 rounds down to 0x07ffff << 8*28).
+const POW_LIMIT_BITS: u32 = 0x1f07_ffff;
+
+fn le_add(Target(mut t): Target, delta: i8) -> Target {
+    if delta >= 0 {
+        for _ in 0..delta {
+            for b in t.iter_mut() {
+                let (v, carry) = b.overflowing_add(1);
+                *b = v;
+                if !carry {
+                    break;
+                }
+            }
+        }
+    } else {
+        for _ in 0..-delta {
+            for b in t.iter_mut() {
+                let (v, borrow) = b.overflowing_sub(1);
+                *b = v;
+                if !borrow {
+                    break;
+                }
+            }
+        }
+    }
+    Target(t)
+}
+
+#[test]
+fn target_orders_little_endian() {
+    let zero = Target::ZERO;
+    let mut low = zero;
+    low.0[0] = 0xff;
+    let mut high = zero;
+    high.0[31] = 0x01;
+
+    assert_eq!(zero.cmp(&zero), Ordering::Equal);
+    assert_eq!(low.cmp(&zero), Ordering::Greater);
+    // The last byte is most significant, regardless of the lower bytes.
+    assert_eq!(high.cmp(&low), Ordering::Greater);
+    assert_eq!(low.cmp(&high), Ordering::Less);
+
+    let mut a = Target([0xab; 32]);
+    let b = a;
+    a.0[0] = 0xac;
+    assert_eq!(a.cmp(&b), Ordering::Greater);
+    assert_eq!(b.cmp(&a), Ordering::Less);
+}
+
+#[test]
+fn filter_accepts_hash_equal_to_target() {
+    for bits in [0x1c0a_3e4f, 0x1d00_ffff, 0x0300_8000, POW_LIMIT_BITS] {
+        let target = Target::from_nbits(bits);
+        verify_difficulty_filter(&target.0, bits).unwrap();
+        verify_difficulty_filter(&le_add(target, -1).0, bits).unwrap();
+        assert!(matches!(
+            verify_difficulty_filter(&le_add(target, 1).0, bits),
+            Err(DiffError::HashAboveTarget)
+        ));
+    }
+}
+
+#[test]
+fn filter_bounds_target_by_pow_limit() {
+    let limit = Target::from_nbits(POW_LIMIT_BITS);
+    assert_eq!(limit.to_nbits(), POW_LIMIT_BITS);
+    assert_eq!(limit.0[31], 0x00);
+    assert_eq!(limit.0[30], 0x07);
+    assert!(limit <= POW_LIMIT);
+    verify_difficulty_filter(&[0u8; 32], POW_LIMIT_BITS).unwrap();
+
+    // The smallest encodable target above the limit.
+    assert!(matches!(
+        verify_difficulty_filter(&[0u8; 32], 0x1f08_0000),
+        Err(DiffError::TargetAbovePowLimit)
+    ));
+    // ...which the looser testnet limit accepts.
+    verify_difficulty_filter_for_network(&[0u8; 32], 0x1f08_0000, Network::Testnet).unwrap();
+}
+
+#[test]
+fn filter_rejects_negative_and_zero_targets() {
+    // Sign bit set with a nonzero mantissa: a negative target in zcashd.
+    for bits in [0x1c80_0001, 0x1cff_ffff, 0x1f87_ffff] {
+        assert!(matches!(
+            verify_difficulty_filter(&[0u8; 32], bits),
+            Err(DiffError::InvalidTarget)
+        ));
+    }
+    // Sign bit with a zero mantissa is "negative zero", i.e. just zero.
+    for bits in [0x1c80_0000, 0x1c00_0000, 0x0000_0000] {
+        assert!(matches!(
+            verify_difficulty_filter(&[0u8; 32], bits),
+            Err(DiffError::InvalidTarget)
+        ));
+    }
+}
+
+#[test]
+fn from_nbits_zeroes_negative_encodings() {
+    assert_eq!(Target::from_nbits(0x1c80_0001), Target::ZERO);
+    assert_eq!(Target::from_nbits(0x1f87_ffff), Target::ZERO);
+    // Clearing the sign bit gives back an ordinary positive target.
+    assert_ne!(Target::from_nbits(0x1c00_0001), Target::ZERO);
+}
+
+#[test]
+fn from_nbits_zeroes_overflowing_encodings() {
+    // A one-byte mantissa fits up to exponent 34 (its byte lands at index 31).
+    let top = Target::from_nbits(0x2200_0001);
+    assert_eq!(top.0[31], 0x01);
+    assert_eq!(Target::from_nbits(0x2300_0001), Target::ZERO);
+    assert_eq!(Target::from_nbits(0xff00_0001), Target::ZERO);
+
+    // Wider mantissas overflow at correspondingly smaller exponents instead of being truncated.
+    assert_ne!(Target::from_nbits(0x2100_0100), Target::ZERO);
+    assert_eq!(Target::from_nbits(0x2200_0100), Target::ZERO);
+    assert_ne!(Target::from_nbits(0x2001_0000), Target::ZERO);
+    assert_eq!(Target::from_nbits(0x2101_0000), Target::ZERO);
+
+    for bits in [0x2300_0001, 0x2200_0100, 0x2101_0000] {
+        assert!(matches!(
+            verify_difficulty_filter(&[0u8; 32], bits),
+            Err(DiffError::InvalidTarget)
+        ));
+    }
+}
+
+#[test]
+fn try_from_nbits_names_each_malformed_encoding() {
+    for bits in [0x0000_0000, 0x1c00_0000, 0x1c80_0000, 0xff00_0000] {
+        assert_eq!(Target::try_from_nbits(bits), Err(NBitsError::ZeroMantissa));
+    }
+    for bits in [0x1c80_0001, 0x1cff_ffff, 0x1f87_ffff] {
+        assert_eq!(
+            Target::try_from_nbits(bits),
+            Err(NBitsError::NegativeSignBit)
+        );
+    }
+    for bits in [0x2300_0001, 0x2200_0100, 0x2101_0000, 0xff00_0001] {
+        assert_eq!(Target::try_from_nbits(bits), Err(NBitsError::Overflow));
+    }
+    // Both flags set: the sign is reported first, as zcashd's `SetCompact` computes it first.
+    assert_eq!(
+        Target::try_from_nbits(0x2380_0001),
+        Err(NBitsError::NegativeSignBit)
+    );
+
+    assert_eq!(
+        Target::try_from_nbits(POW_LIMIT_BITS),
+        Ok(Target::from_nbits(POW_LIMIT_BITS))
+    );
+    // A mantissa shifted right past its last byte decodes, to zero, and the filter still rejects it.
+    assert_eq!(Target::try_from_nbits(0x0000_0001), Ok(Target::ZERO));
+    assert!(matches!(
+        verify_difficulty_filter(&[0u8; 32], 0x0000_0001),
+        Err(DiffError::InvalidTarget)
+    ));
+}
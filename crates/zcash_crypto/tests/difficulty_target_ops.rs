@@ -0,0 +1,74 @@
+use core::cmp::Ordering;
+
+use proptest::prelude::*;
+use zcash_crypto::difficulty::nbits_cmp;
+use zcash_crypto::difficulty::target::{POW_LIMIT, Target};
+
+fn from_u128(v: u128) -> Target {
+    let mut t = Target::ZERO;
+    t.0[..16].copy_from_slice(&v.to_le_bytes());
+    t
+}
+
+#[test]
+fn arithmetic_carries_across_bytes() {
+    let mut max_low = Target::ZERO;
+    max_low.0[..16].fill(0xff);
+    let mut carried = Target::ZERO;
+    carried.0[16] = 0x01;
+
+    assert_eq!(max_low + from_u128(1), carried);
+    assert_eq!(carried / 2, from_u128(1 << 127));
+    assert_eq!(from_u128(1 << 127) * 2, carried);
+    // Addition and multiplication wrap modulo 2^256.
+    assert_eq!(Target([0xff; 32]) + from_u128(1), Target::ZERO);
+    assert_eq!(
+        Target([0xff; 32]) * 2,
+        Target([0xff; 32]) + Target([0xff; 32])
+    );
+}
+
+#[test]
+fn nbits_cmp_orders_by_target() {
+    // Block 3000100's 0x1c0217f8 against the looser 0x1c089abd: its target is lower, i.e.
+    // harder.
+    assert_eq!(nbits_cmp(0x1c0217f8, 0x1c089abd), Ordering::Less);
+    assert_eq!(nbits_cmp(0x1f07ffff, 0x1c089abd), Ordering::Greater);
+    assert_eq!(nbits_cmp(0x1c0217f8, 0x1c0217f8), Ordering::Equal);
+    // A larger exponent outweighs a larger mantissa.
+    assert_eq!(nbits_cmp(0x1b7fffff, 0x1c010000), Ordering::Less);
+}
+
+#[test]
+fn nbits_cmp_compares_normalized_targets() {
+    // Same target, different encodings: the mantissa shifted a byte against the exponent.
+    assert_eq!(nbits_cmp(0x1d00ff00, 0x1e0000ff), Ordering::Equal);
+    assert_eq!(nbits_cmp(0x0300_0100, 0x0400_0001), Ordering::Equal);
+    // Below exponent 3 the mantissa is shifted right: both are 0x80.
+    assert_eq!(nbits_cmp(0x0200_8000, 0x0300_0080), Ordering::Equal);
+    // The canonical form compares equal to any encoding of its target.
+    let canonical = Target::from_nbits(0x1e0000ff).to_nbits();
+    assert_ne!(canonical, 0x1e0000ff);
+    assert_eq!(nbits_cmp(canonical, 0x1e0000ff), Ordering::Equal);
+    // Malformed encodings expand to the zero target, below every valid one.
+    assert_eq!(nbits_cmp(0x1c80_1234, 0x0300_0001), Ordering::Less);
+    assert_eq!(nbits_cmp(0x1c80_1234, 0x2300_0000), Ordering::Equal);
+}
+
+#[test]
+fn pow_limit_bounds_mainnet_targets() {
+    assert_eq!(POW_LIMIT.to_nbits(), 0x1f07_ffff);
+    assert_eq!(POW_LIMIT.min(Target([0xff; 32])), POW_LIMIT);
+    assert!(Target::from_nbits(0x1f08_0000) > POW_LIMIT);
+}
+
+proptest! {
+    #[test]
+    fn ops_match_u128(a in any::<u64>(), b in any::<u64>(), m in any::<u32>(), d in 1..=u32::MAX) {
+        let (a, b) = (a as u128, b as u128);
+        prop_assert_eq!(from_u128(a) + from_u128(b), from_u128(a + b));
+        prop_assert_eq!(from_u128(a) * m, from_u128(a * m as u128));
+        prop_assert_eq!(from_u128(a) / d, from_u128(a / d as u128));
+        prop_assert_eq!(from_u128(a).cmp(&from_u128(b)), a.cmp(&b));
+    }
+}
@@ -0,0 +1,72 @@
+use proptest::prelude::*;
+use zcash_crypto::difficulty::target::{POW_LIMIT, Target};
+
+/// Little-endian target from its significant big-endian bytes.
+fn target_from_be(be: &[u8]) -> Target {
+    let mut t = Target::ZERO;
+    for (i, b) in be.iter().rev().enumerate() {
+        t.0[i] = *b;
+    }
+    t
+}
+
+/// Random targets of every byte length, so short (`size <= 3`) encodings get covered too.
+fn any_target() -> impl Strategy<Value = Target> {
+    prop::collection::vec(any::<u8>(), 0..=32).prop_map(|be| target_from_be(&be))
+}
+
+#[test]
+fn short_targets_match_get_compact() {
+    // Expected values follow zcashd's `arith_uint256::GetCompact`.
+    assert_eq!(target_from_be(&[0x12]).to_nbits(), 0x0112_0000);
+    assert_eq!(target_from_be(&[0x12, 0x34]).to_nbits(), 0x0212_3400);
+    assert_eq!(target_from_be(&[0x12, 0x34, 0x56]).to_nbits(), 0x0312_3456);
+    assert_eq!(target_from_be(&[0x80]).to_nbits(), 0x0200_8000);
+    assert_eq!(target_from_be(&[0x80, 0x00]).to_nbits(), 0x0300_8000);
+    assert_eq!(
+        target_from_be(&[0x12, 0x34, 0x56, 0x78]).to_nbits(),
+        0x0412_3456
+    );
+    assert_eq!(Target::ZERO.to_nbits(), 0);
+}
+
+#[test]
+fn targets_display_as_big_endian_hex() {
+    assert_eq!(
+        POW_LIMIT.to_string(),
+        "0007ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+    );
+    // The nBits of mainnet block 3000000.
+    let t = Target::from_nbits(0x1c02_0141);
+    assert_eq!(
+        t.to_string(),
+        "0000000002014100000000000000000000000000000000000000000000000000"
+    );
+    assert_eq!(Target::from_hex(&t.to_string()), Some(t));
+    assert_eq!(Target::ZERO.to_string(), "0".repeat(64));
+
+    assert_eq!(Target::from_hex("00"), None);
+    assert_eq!(Target::from_hex(&"g".repeat(64)), None);
+}
+
+proptest! {
+    #[test]
+    fn nbits_round_trip_is_idempotent(t in any_target()) {
+        let bits = t.to_nbits();
+        let expanded = Target::from_nbits(bits);
+
+        // Compact encoding only ever drops low bytes.
+        prop_assert!(expanded <= t);
+        prop_assert_eq!(expanded.to_nbits(), bits);
+        prop_assert_eq!(Target::from_nbits(expanded.to_nbits()), expanded);
+        // The canonical form never sets the sign bit.
+        prop_assert_eq!(bits & 0x0080_0000, 0);
+    }
+
+    #[test]
+    fn hex_round_trips(t in any_target()) {
+        let s = t.to_string();
+        prop_assert_eq!(s.len(), 64);
+        prop_assert_eq!(Target::from_hex(&s), Some(t));
+    }
+}
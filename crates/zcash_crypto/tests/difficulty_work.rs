@@ -0,0 +1,121 @@
+mod common;
+
+use common::fixture_headers;
+use zcash_crypto::difficulty::filter::DiffError;
+use zcash_crypto::difficulty::{ChainWork, difficulty_ratio, work_from_nbits};
+use zcash_crypto::{DifficultyContext, Network};
+
+/// `nBits` sits after version, three 32-byte hashes and `nTime`.
+const BITS_OFFSET: usize = 4 + 3 * 32 + 4;
+
+fn fixture_bits() -> Vec<u32> {
+    fixture_headers()
+        .into_iter()
+        .map(|(_, raw)| u32::from_le_bytes(raw[BITS_OFFSET..BITS_OFFSET + 4].try_into().unwrap()))
+        .collect()
+}
+
+fn le(value: u128) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[..16].copy_from_slice(&value.to_le_bytes());
+    out
+}
+
+#[test]
+fn work_from_known_nbits() {
+    // Bitcoin's genesis difficulty: chainwork 0x100010001 per block.
+    assert_eq!(work_from_nbits(0x1d00_ffff), le(0x1_0001_0001));
+    // Zcash mainnet PoW limit, 2^243 - 1: exactly 2^13 hashes.
+    assert_eq!(work_from_nbits(0x1f07_ffff), le(8192));
+    // Mainnet blocks 3_000_000 and 3_000_143.
+    assert_eq!(work_from_nbits(0x1c02_0141), le(548_412_731_560));
+    assert_eq!(work_from_nbits(0x1c01_e979), le(575_057_611_730));
+}
+
+#[test]
+fn invalid_nbits_carry_no_work() {
+    for nbits in [0, 0x0180_0001, 0x2300_0001] {
+        assert_eq!(work_from_nbits(nbits), [0u8; 32], "{nbits:#010x}");
+    }
+}
+
+#[test]
+fn extreme_targets() {
+    // Target 1: 2^256 / 2 = 2^255.
+    let mut expected = [0u8; 32];
+    expected[31] = 0x80;
+    assert_eq!(work_from_nbits(0x0300_0001), expected);
+
+    // Target 0xffff * 2^240, above 2^255: a single hash is expected to suffice.
+    assert_eq!(work_from_nbits(0x2100_ffff), le(1));
+}
+
+#[test]
+fn chain_work_accumulates_over_fixtures() {
+    let bits = fixture_bits();
+    assert_eq!(bits.len(), 144);
+
+    let mut work = ChainWork::default();
+    let mut ctx = DifficultyContext::new(2_999_999);
+    for (height, &nbits) in (3_000_000..).zip(&bits) {
+        work.push_nbits(nbits);
+        ctx.push_header(height, 0, nbits);
+    }
+    assert_eq!(work.total_work(), le(74_946_014_752_046));
+    assert_eq!(ctx.total_work(), work.total_work());
+
+    // One more block makes the chain heavier.
+    let mut longer = work;
+    longer.push_nbits(bits[0]);
+    assert!(longer > work);
+}
+
+#[test]
+fn difficulty_ratio_is_one_at_the_pow_limit() {
+    for network in [Network::Mainnet, Network::Testnet, Network::Regtest] {
+        let at_limit = network.pow_limit().to_nbits();
+        assert_eq!(
+            difficulty_ratio(at_limit, network).unwrap(),
+            1.0,
+            "{network:?}"
+        );
+    }
+}
+
+#[test]
+fn harder_targets_have_higher_difficulty() {
+    // (0x07ffff / 0x020141) * 256^(0x1f - 0x1c), as zcashd's getdifficulty computes it.
+    let expected = 524_287.0 / 131_393.0 * 16_777_216.0;
+    let ratio = difficulty_ratio(0x1c02_0141, Network::Mainnet).unwrap();
+    assert!((ratio - expected).abs() < 1e-6, "{ratio}");
+
+    let bits = fixture_bits();
+    for pair in bits.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let ratio = |bits| difficulty_ratio(bits, Network::Mainnet).unwrap();
+        assert!(ratio(a) > 1.0);
+        // Smaller target, more work, higher difficulty.
+        assert_eq!(
+            ratio(a).partial_cmp(&ratio(b)),
+            work_from_nbits(a)
+                .iter()
+                .rev()
+                .cmp(work_from_nbits(b).iter().rev())
+                .into()
+        );
+    }
+    // Against testnet's higher (easier) limit the same target counts as harder.
+    assert!(difficulty_ratio(0x1c02_0141, Network::Testnet).unwrap() > ratio);
+}
+
+#[test]
+fn zero_target_has_no_difficulty() {
+    assert!(matches!(
+        difficulty_ratio(0x1c80_0001, Network::Mainnet),
+        Err(DiffError::InvalidTarget)
+    ));
+    assert!(matches!(
+        difficulty_ratio(0, Network::Mainnet),
+        Err(DiffError::InvalidTarget)
+    ));
+}
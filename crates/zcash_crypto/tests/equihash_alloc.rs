@@ -0,0 +1,58 @@
+//! Peak heap usage of the recursive vs streaming Equihash verifiers.
+//!
+//! Kept in its own test binary with a single test so the counting allocator only sees
+//! the allocations being measured.
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::fixture_headers;
+use zcash_crypto::{verify_equihash_solution, verify_equihash_solution_streaming};
+
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let now = self.current.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        self.peak.fetch_max(now, Ordering::SeqCst);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.current.fetch_sub(layout.size(), Ordering::SeqCst);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// Bytes allocated at the high-water mark of `f`, above what was live before it ran.
+fn peak_during(f: impl FnOnce()) -> usize {
+    let base = ALLOC.current.load(Ordering::SeqCst);
+    ALLOC.peak.store(base, Ordering::SeqCst);
+    f();
+    ALLOC.peak.load(Ordering::SeqCst) - base
+}
+
+#[test]
+fn streaming_verifier_uses_less_peak_memory() {
+    let (_, raw) = &fixture_headers()[0];
+    let (powheader, solution) = (&raw[..140], &raw[143..]);
+
+    let recursive = peak_during(|| verify_equihash_solution(powheader, solution).unwrap());
+    let streaming =
+        peak_during(|| verify_equihash_solution_streaming(powheader, solution).unwrap());
+
+    assert!(
+        streaming < recursive,
+        "peak heap: recursive {recursive} B, streaming {streaming} B"
+    );
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::fixture_headers;
+use zcash_crypto::equihash::{Params, indices_from_minimal};
+use zcash_crypto::{Kind, verify_equihash_solution, verify_equihash_solution_streaming};
+
+/// `(powheader, solution)` pairs split out of the committed mainnet fixtures.
+fn fixtures() -> Vec<(Vec<u8>, Vec<u8>)> {
+    fixture_headers()
+        .into_iter()
+        .map(|(_, raw)| {
+            // 140-byte powheader, 3-byte compactSize (0xfd 0x4005), 1344-byte solution.
+            (raw[..140].to_vec(), raw[143..].to_vec())
+        })
+        .collect()
+}
+
+/// Inverse of `indices_from_minimal` for `(200, 9)`: packs 21-bit big-endian indices.
+fn minimal_from_indices(indices: &[u32]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(indices.len() * 21 / 8);
+    let (mut acc, mut acc_bits) = (0u64, 0u32);
+    for &i in indices {
+        acc = (acc << 21) | u64::from(i);
+        acc_bits += 21;
+        while acc_bits >= 8 {
+            acc_bits -= 8;
+            out.push((acc >> acc_bits) as u8);
+        }
+    }
+    out
+}
+
+fn assert_same_outcome(powheader: &[u8], solution: &[u8]) -> Result<(), Kind> {
+    let recursive = verify_equihash_solution(powheader, solution).map_err(|e| e.0);
+    let streaming = verify_equihash_solution_streaming(powheader, solution).map_err(|e| e.0);
+    assert_eq!(recursive, streaming);
+    streaming
+}
+
+#[test]
+fn streaming_accepts_mainnet_fixtures() {
+    for (powheader, solution) in fixtures().iter().take(16) {
+        assert_eq!(assert_same_outcome(powheader, solution), Ok(()));
+    }
+}
+
+#[test]
+fn streaming_matches_recursive_errors() {
+    let (powheader, solution) = &fixtures()[0];
+
+    let mut header = powheader.clone();
+    header[108] ^= 0x01;
+    assert_eq!(assert_same_outcome(&header, solution), Err(Kind::Collision));
+
+    for pos in (0..solution.len()).step_by(97) {
+        let mut tampered = solution.clone();
+        tampered[pos] ^= 0x40;
+        assert!(assert_same_outcome(powheader, &tampered).is_err());
+    }
+
+    let indices = indices_from_minimal(Params::MAINNET, solution).unwrap();
+    assert_eq!(minimal_from_indices(&indices), *solution);
+
+    let mut swapped = indices.clone();
+    swapped.swap(0, 1);
+    assert_eq!(
+        assert_same_outcome(powheader, &minimal_from_indices(&swapped)),
+        Err(Kind::OutOfOrder)
+    );
+
+    let duplicated = vec![indices[0]; indices.len()];
+    assert_eq!(
+        assert_same_outcome(powheader, &minimal_from_indices(&duplicated)),
+        Err(Kind::DuplicateIdxs)
+    );
+
+    assert_eq!(
+        assert_same_outcome(powheader, &solution[1..]),
+        Err(Kind::WrongSolutionLength {
+            expected: solution.len(),
+            found: solution.len() - 1
+        })
+    );
+}
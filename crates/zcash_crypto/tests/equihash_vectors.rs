@@ -1,94 +1,276 @@
-// use zcash_crypto::verify_equihash_solution_with_params;
-//
-// // Create the types expected by the original test vector files so we can include them 1:1.
-// mod params {
-//     #[derive(Clone, Copy)]
-//     pub(crate) struct Params {
-//         pub(crate) n: u32,
-//         pub(crate) k: u32,
-//     }
-// }
-//
-// // Provide a local Kind enum so the invalid fixtures can be included unchanged.
-// mod verify {
-//     #[derive(Debug, PartialEq)]
-//     #[allow(dead_code)]
-//     pub(crate) enum Kind {
-//         InvalidParams,
-//         Collision,
-//         OutOfOrder,
-//         DuplicateIdxs,
-//         NonZeroRootHash,
-//     }
-// }
-//
-// // Include the valid test vectors unchanged.
-// mod vectors_valid {
-//     include!("../../equihash/src/test_vectors/valid.rs");
-// }
-//
-// // Include the invalid test vectors unchanged.
-// mod vectors_invalid {
-//     include!("../../equihash/src/test_vectors/invalid.rs");
-// }
-//
-// fn compress_array(array: &[u8], bit_len: usize, byte_pad: usize) -> Vec<u8> {
-//     let in_width: usize = (bit_len + 7) / 8 + byte_pad;
-//     let out_len = bit_len * array.len() / (8 * in_width);
-//     let mut out = Vec::with_capacity(out_len);
-//     let bit_len_mask: u32 = (1 << (bit_len as u32)) - 1;
-//     let mut acc_bits: usize = 0;
-//     let mut acc_value: u32 = 0;
-//     let mut j: usize = 0;
-//     for _ in 0..out_len {
-//         if acc_bits < 8 {
-//             acc_value <<= bit_len;
-//             for x in byte_pad..in_width {
-//                 acc_value |= ((array[j + x] & ((bit_len_mask >> (8 * (in_width - x - 1))) as u8))
-//                     as u32)
-//                     .wrapping_shl(8 * (in_width - x - 1) as u32);
-//             }
-//             j += in_width;
-//             acc_bits += bit_len;
-//         }
-//         acc_bits -= 8;
-//         out.push((acc_value >> acc_bits) as u8);
-//     }
-//     out
-// }
-//
-// fn minimal_from_indices(n: u32, k: u32, indices: &[u32]) -> Vec<u8> {
-//     let array: Vec<u8> = indices.iter().flat_map(|i| i.to_be_bytes()).collect();
-//     let c_bit_len = (n / (k + 1)) as usize;
-//     let digit_bytes = ((c_bit_len + 1) + 7) / 8;
-//     let byte_pad = core::mem::size_of::<u32>() - digit_bytes;
-//     compress_array(&array, c_bit_len + 1, byte_pad)
-// }
-//
-// fn powheader(input: &[u8], nonce: [u8; 32]) -> Vec<u8> {
-//     let mut out = input.to_vec();
-//     out.extend_from_slice(&nonce);
-//     out
-// }
-//
-// #[test]
-// fn valid_vectors_all_params() {
-//     for tv in vectors_valid::VALID_TEST_VECTORS {
-//         let pow = powheader(tv.input, tv.nonce);
-//         for sol in tv.solutions {
-//             let minimal = minimal_from_indices(tv.params.n, tv.params.k, sol);
-//             verify_equihash_solution_with_params(tv.params.n, tv.params.k, &pow, &minimal).unwrap();
-//         }
-//     }
-// }
-//
-// #[test]
-// fn invalid_vectors_all_params() {
-//     for tv in vectors_invalid::INVALID_TEST_VECTORS {
-//         let pow = powheader(tv.input, tv.nonce);
-//         let minimal = minimal_from_indices(tv.params.n, tv.params.k, tv.solution);
-//         assert!(
-//             verify_equihash_solution_with_params(tv.params.n, tv.params.k, &pow, &minimal).is_err()
-//         );
-//     }
-// }
+//! Equihash vectors for parameter sets beyond Zcash's `(200, 9)`, checked with both verifiers.
+//!
+//! The vectors below are generated by `scripts/gen_equihash_vectors.py`, an independent Python
+//! reference (Wagner's algorithm plus a verifier that is cross-checked against the committed
+//! mainnet `(200, 9)` solutions). Each invalid vector is a valid solution or near-solution
+//! perturbed to hit one specific [`Kind`].
+use zcash_crypto::equihash::{Params, ZCASH_PERSONALIZATION};
+use zcash_crypto::{
+    Kind, verify_equihash_solution_for_params, verify_equihash_solution_streaming_for_params,
+    verify_equihash_solution_streaming_with_params, verify_equihash_solution_with_params,
+};
+
+const INPUT: &[u8] = b"Equihash is an asymmetric PoW based on the Generalised Birthday problem.";
+
+struct ValidVector {
+    params: (u32, u32),
+    nonce: u32,
+    solutions: &'static [&'static [u32]],
+}
+
+struct InvalidVector {
+    params: (u32, u32),
+    nonce: u32,
+    solution: &'static [u32],
+    error: Kind,
+}
+
+/// `INPUT` followed by the 32-byte little-endian nonce.
+fn powheader(nonce: u32) -> Vec<u8> {
+    let mut out = INPUT.to_vec();
+    let mut nonce_bytes = [0u8; 32];
+    nonce_bytes[..4].copy_from_slice(&nonce.to_le_bytes());
+    out.extend_from_slice(&nonce_bytes);
+    out
+}
+
+/// Minimal encoding of `indices`: each packed big-endian into `n / (k + 1) + 1` bits.
+fn minimal_from_indices((n, k): (u32, u32), indices: &[u32]) -> Vec<u8> {
+    let bit_len = n / (k + 1) + 1;
+    let mut out = Vec::with_capacity(indices.len() * bit_len as usize / 8);
+    let (mut acc, mut acc_bits) = (0u64, 0u32);
+    for &i in indices {
+        acc = (acc << bit_len) | u64::from(i);
+        acc_bits += bit_len;
+        while acc_bits >= 8 {
+            acc_bits -= 8;
+            out.push((acc >> acc_bits) as u8);
+        }
+    }
+    out
+}
+
+fn verify_both((n, k): (u32, u32), nonce: u32, solution: &[u32]) -> Result<(), Kind> {
+    let pow = powheader(nonce);
+    let minimal = minimal_from_indices((n, k), solution);
+    let recursive = verify_equihash_solution_with_params(n, k, &pow, &minimal).map_err(|e| e.0);
+    let streaming =
+        verify_equihash_solution_streaming_with_params(n, k, &pow, &minimal).map_err(|e| e.0);
+    assert_eq!(recursive, streaming, "verifiers disagree for {:?}", (n, k));
+    recursive
+}
+
+#[test]
+fn valid_vectors_all_params() {
+    for tv in VALID {
+        for sol in tv.solutions {
+            assert_eq!(
+                verify_both(tv.params, tv.nonce, sol),
+                Ok(()),
+                "{:?}",
+                tv.params
+            );
+        }
+    }
+}
+
+#[test]
+fn invalid_vectors_all_params() {
+    for tv in INVALID {
+        assert_eq!(
+            verify_both(tv.params, tv.nonce, tv.solution),
+            Err(tv.error),
+            "{:?}",
+            tv.params
+        );
+    }
+}
+
+#[test]
+fn personalization_prefix_is_bound_into_the_leaves() {
+    for tv in VALID {
+        let (n, k) = tv.params;
+        let pow = powheader(tv.nonce);
+        let minimal = minimal_from_indices(tv.params, tv.solutions[0]);
+        let verify = |p: Params| {
+            let recursive = verify_equihash_solution_for_params(p, &pow, &minimal);
+            let streaming = verify_equihash_solution_streaming_for_params(p, &pow, &minimal);
+            assert_eq!(
+                recursive.as_ref().err().map(|e| e.0),
+                streaming.err().map(|e| e.0)
+            );
+            recursive.map_err(|e| e.0)
+        };
+        let zcash = Params::new(n, k).unwrap();
+        assert_eq!(zcash.personalization_prefix(), ZCASH_PERSONALIZATION);
+        assert_eq!(verify(zcash), Ok(()));
+
+        // Other prefixes, even just a shorter one, hash different leaves, so the Zcash
+        // solution no longer collides.
+        for prefix in [&b"BgoldPoW"[..], b"ZcashPo"] {
+            let other = zcash.with_personalization(prefix).unwrap();
+            assert_eq!(other.personalization_prefix(), prefix);
+            assert!(verify(other).is_err(), "{:?} with {prefix:?}", tv.params);
+        }
+    }
+    assert_eq!(Params::MAINNET.with_personalization(b"TooLongPoW"), None);
+}
+
+#[test]
+fn every_error_kind_is_covered_per_params() {
+    for tv in VALID {
+        for kind in [
+            Kind::Collision,
+            Kind::OutOfOrder,
+            Kind::DuplicateIdxs,
+            Kind::NonZeroRootHash,
+        ] {
+            assert!(
+                INVALID
+                    .iter()
+                    .any(|iv| iv.params == tv.params && iv.error == kind),
+                "no {kind:?} vector for {:?}",
+                tv.params
+            );
+        }
+    }
+}
+
+const VALID: &[ValidVector] = &[
+    ValidVector {
+        params: (48, 5),
+        nonce: 1,
+        solutions: &[&[
+            8, 229, 29, 139, 230, 351, 452, 507, 30, 374, 265, 424, 65, 90, 219, 502, 46, 255, 79,
+            262, 49, 51, 96, 203, 47, 182, 168, 366, 123, 411, 414, 504,
+        ]],
+    },
+    ValidVector {
+        params: (64, 3),
+        nonce: 0,
+        solutions: &[
+            &[1847, 127838, 85749, 103698, 5375, 59518, 16266, 49081],
+            &[2368, 102982, 3684, 112511, 25755, 69727, 55535, 116518],
+            &[34440, 42817, 55652, 67234, 43238, 59079, 72522, 126094],
+        ],
+    },
+    ValidVector {
+        params: (96, 5),
+        nonce: 0,
+        solutions: &[
+            &[
+                3130, 83179, 30454, 107686, 71240, 88412, 109700, 114639, 10024, 32706, 38019,
+                113013, 18399, 92942, 21094, 112263, 4146, 30807, 10631, 73192, 22216, 90216,
+                45581, 125042, 11256, 119455, 93603, 110112, 59851, 91545, 97403, 111102,
+            ],
+            &[
+                3822, 35317, 47508, 119823, 37652, 117039, 69087, 72058, 13147, 111794, 65435,
+                124256, 22247, 66272, 30298, 108956, 13157, 109175, 37574, 50978, 31258, 91519,
+                52568, 107874, 14999, 103687, 27027, 109468, 36918, 109660, 42196, 100424,
+            ],
+        ],
+    },
+];
+
+const INVALID: &[InvalidVector] = &[
+    InvalidVector {
+        params: (48, 5),
+        nonce: 1,
+        solution: &[
+            0, 137, 152, 292, 27, 358, 203, 334, 3, 117, 115, 408, 80, 237, 256, 495, 79, 262, 213,
+            476, 97, 456, 392, 393, 110, 377, 185, 399, 277, 382, 435, 485,
+        ],
+        error: Kind::NonZeroRootHash,
+    },
+    InvalidVector {
+        params: (48, 5),
+        nonce: 1,
+        solution: &[
+            229, 8, 29, 139, 230, 351, 452, 507, 30, 374, 265, 424, 65, 90, 219, 502, 46, 255, 79,
+            262, 49, 51, 96, 203, 47, 182, 168, 366, 123, 411, 414, 504,
+        ],
+        error: Kind::OutOfOrder,
+    },
+    InvalidVector {
+        params: (48, 5),
+        nonce: 1,
+        solution: &[
+            8, 8, 29, 139, 230, 351, 452, 507, 30, 374, 265, 424, 65, 90, 219, 502, 46, 255, 79,
+            262, 49, 51, 96, 203, 47, 182, 168, 366, 123, 411, 414, 504,
+        ],
+        error: Kind::DuplicateIdxs,
+    },
+    InvalidVector {
+        params: (48, 5),
+        nonce: 1,
+        solution: &[
+            0, 229, 29, 139, 230, 351, 452, 507, 30, 374, 265, 424, 65, 90, 219, 502, 46, 255, 79,
+            262, 49, 51, 96, 203, 47, 182, 168, 366, 123, 411, 414, 504,
+        ],
+        error: Kind::Collision,
+    },
+    InvalidVector {
+        params: (64, 3),
+        nonce: 0,
+        solution: &[2, 50631, 26741, 97702, 93127, 126542, 98823, 112099],
+        error: Kind::NonZeroRootHash,
+    },
+    InvalidVector {
+        params: (64, 3),
+        nonce: 0,
+        solution: &[127838, 1847, 85749, 103698, 5375, 59518, 16266, 49081],
+        error: Kind::OutOfOrder,
+    },
+    InvalidVector {
+        params: (64, 3),
+        nonce: 0,
+        solution: &[1847, 1847, 85749, 103698, 5375, 59518, 16266, 49081],
+        error: Kind::DuplicateIdxs,
+    },
+    InvalidVector {
+        params: (64, 3),
+        nonce: 0,
+        solution: &[0, 127838, 85749, 103698, 5375, 59518, 16266, 49081],
+        error: Kind::Collision,
+    },
+    InvalidVector {
+        params: (96, 5),
+        nonce: 0,
+        solution: &[
+            0, 60448, 10200, 41594, 1220, 110097, 6699, 42654, 10205, 79752, 27788, 105722, 10949,
+            105590, 107548, 123374, 968, 5108, 4211, 90084, 55639, 74042, 75682, 87236, 24081,
+            94834, 54787, 57607, 33488, 56533, 109165, 109311,
+        ],
+        error: Kind::NonZeroRootHash,
+    },
+    InvalidVector {
+        params: (96, 5),
+        nonce: 0,
+        solution: &[
+            83179, 3130, 30454, 107686, 71240, 88412, 109700, 114639, 10024, 32706, 38019, 113013,
+            18399, 92942, 21094, 112263, 4146, 30807, 10631, 73192, 22216, 90216, 45581, 125042,
+            11256, 119455, 93603, 110112, 59851, 91545, 97403, 111102,
+        ],
+        error: Kind::OutOfOrder,
+    },
+    InvalidVector {
+        params: (96, 5),
+        nonce: 0,
+        solution: &[
+            3130, 3130, 30454, 107686, 71240, 88412, 109700, 114639, 10024, 32706, 38019, 113013,
+            18399, 92942, 21094, 112263, 4146, 30807, 10631, 73192, 22216, 90216, 45581, 125042,
+            11256, 119455, 93603, 110112, 59851, 91545, 97403, 111102,
+        ],
+        error: Kind::DuplicateIdxs,
+    },
+    InvalidVector {
+        params: (96, 5),
+        nonce: 0,
+        solution: &[
+            0, 83179, 30454, 107686, 71240, 88412, 109700, 114639, 10024, 32706, 38019, 113013,
+            18399, 92942, 21094, 112263, 4146, 30807, 10631, 73192, 22216, 90216, 45581, 125042,
+            11256, 119455, 93603, 110112, 59851, 91545, 97403, 111102,
+        ],
+        error: Kind::Collision,
+    },
+];
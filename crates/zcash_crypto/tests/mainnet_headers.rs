@@ -0,0 +1,396 @@
+mod common;
+
+use cairo_runner::types::InputData;
+use cairo_runner::{Felt252, execute};
+use common::header_bytes;
+use zcash_crypto::difficulty::context::expected_nbits;
+use zcash_crypto::difficulty::target::Target;
+use zcash_crypto::{
+    DiffError, DifficultyContext, Kind, Network, PowError, cairo_program_path, check_cairo_input,
+    check_cairo_output, check_pow_with_context, check_solution_len, diagnose_pow,
+    execute_pow_in_cairo, hash_header_bytes, powheader_bytes, verify_difficulty_filter,
+    verify_difficulty_filter_for_network, verify_difficulty_only_with_context,
+    verify_equihash_solution_with_personal, verify_pow, verify_pow_from_bytes, verify_pow_linked,
+    verify_pow_with_context,
+};
+use zcash_primitives::block::BlockHeader;
+
+/// Heights sampled from the committed fixtures; all are post-NU5 (activated at 1_687_104)
+/// and post-NU6 (2_726_400) mainnet blocks.
+const HEIGHTS: [u32; 4] = [3_000_000, 3_000_001, 3_000_072, 3_000_143];
+
+/// Offset of the Equihash solution: 140-byte powheader plus the 3-byte compactSize.
+const SOLUTION_OFFSET: usize = 143;
+
+/// Offset of `nBits`: version, three 32-byte hashes and `nTime` precede it.
+const NBITS_OFFSET: usize = 104;
+
+/// Offset of the 32-byte nonce, right after `nBits`.
+const NONCE_OFFSET: usize = 108;
+
+#[test]
+fn mainnet_headers_verify() {
+    for height in HEIGHTS {
+        let raw = header_bytes(height);
+        let header = BlockHeader::read(&raw[..]).unwrap();
+        verify_pow(&header).unwrap_or_else(|e| panic!("height {height}: {e}"));
+        verify_pow_from_bytes(&raw).unwrap();
+    }
+}
+
+#[test]
+fn solution_only_verifies_under_the_zcash_personalization() {
+    for height in HEIGHTS {
+        let raw = header_bytes(height);
+        let (powheader, solution) = (&raw[..140], &raw[SOLUTION_OFFSET..]);
+
+        verify_equihash_solution_with_personal(b"ZcashPoW", 200, 9, powheader, solution)
+            .unwrap_or_else(|e| panic!("height {height}: {e}"));
+        let err = verify_equihash_solution_with_personal(b"BgoldPoW", 200, 9, powheader, solution)
+            .unwrap_err();
+        assert!(
+            matches!(err.0, Kind::Collision | Kind::NonZeroRootHash),
+            "height {height}: unexpected {err}"
+        );
+    }
+}
+
+#[test]
+fn wrong_length_solution_is_a_malformed_header() {
+    let raw = header_bytes(HEIGHTS[0]);
+    check_solution_len(&BlockHeader::read(&raw[..]).unwrap()).unwrap();
+
+    for len in [0usize, 1343, 1345] {
+        // Re-encode with a compactSize declaring `len` bytes, padding or cutting the solution.
+        let mut resized = raw[..140].to_vec();
+        if len < 0xfd {
+            resized.push(len as u8);
+        } else {
+            resized.push(0xfd);
+            resized.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+        let mut solution = raw[SOLUTION_OFFSET..].to_vec();
+        solution.resize(len, 0);
+        resized.extend_from_slice(&solution);
+
+        let header = BlockHeader::read(&resized[..]).unwrap();
+        assert_eq!(header.solution.len(), len);
+        match verify_pow(&header) {
+            Err(PowError::MalformedHeader(msg)) => {
+                assert!(msg.contains(&format!("{len} bytes")), "{msg}")
+            }
+            other => panic!("solution of {len} bytes: expected MalformedHeader, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn bit_flipped_solution_is_rejected() {
+    for height in HEIGHTS {
+        // First and last solution bytes: the flip lands in the first and last index.
+        let last = header_bytes(height).len() - 1;
+        for offset in [SOLUTION_OFFSET, last] {
+            let mut raw = header_bytes(height);
+            raw[offset] ^= 0x01;
+            match verify_pow_from_bytes(&raw) {
+                Err(PowError::Equihash(e)) => assert!(
+                    matches!(e.0, Kind::Collision | Kind::NonZeroRootHash),
+                    "height {height}, byte {offset}: unexpected {e}"
+                ),
+                other => {
+                    panic!("height {height}, byte {offset}: expected Equihash error, got {other:?}")
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn diagnostics_report_every_failing_check() {
+    let raw = header_bytes(3_000_000);
+    let header = BlockHeader::read(&raw[..]).unwrap();
+    let diag = diagnose_pow(&header);
+    assert!(diag.passed(), "{diag:?}");
+    assert_eq!(diag.hash, Target(header.hash().0));
+    assert_eq!(diag.target, Target::from_nbits(header.bits));
+    assert!(diag.hash <= diag.target);
+
+    // A broken solution also changes the header hash, which then misses the target too.
+    let mut tampered = raw.clone();
+    tampered[SOLUTION_OFFSET] ^= 0x01;
+    let tampered = BlockHeader::read(&tampered[..]).unwrap();
+    let diag = diagnose_pow(&tampered);
+    assert!(!diag.passed());
+    assert!(diag.equihash.is_err());
+    assert!(matches!(diag.difficulty, Err(DiffError::HashAboveTarget)));
+    assert!(diag.hash > diag.target);
+    // `verify_pow` stops at the first of them.
+    assert!(matches!(verify_pow(&tampered), Err(PowError::Equihash(_))));
+}
+
+#[test]
+fn difficulty_only_accepts_what_full_verification_accepts() {
+    let first = 3_000_000;
+    let seed = first + DifficultyContext::REQUIRED_WINDOW as u32;
+    let mut full = DifficultyContext::new(first - 1);
+    let mut fast = DifficultyContext::new(first - 1);
+    for height in first..seed {
+        let header = BlockHeader::read(&header_bytes(height)[..]).unwrap();
+        full.push_header(height, header.time, header.bits);
+        fast.push_header(height, header.time, header.bits);
+    }
+
+    for height in seed..=3_000_143 {
+        let raw = header_bytes(height);
+        let header = BlockHeader::read(&raw[..]).unwrap();
+
+        // Wrong nBits is rejected either way (failures leave the contexts untouched).
+        let mut tampered = raw.clone();
+        tampered[NBITS_OFFSET] ^= 0x01;
+        let tampered = BlockHeader::read(&tampered[..]).unwrap();
+        assert!(verify_pow_with_context(&tampered, height, &mut full).is_err());
+        assert!(verify_difficulty_only_with_context(&tampered, height, &mut fast).is_err());
+
+        verify_pow_with_context(&header, height, &mut full)
+            .unwrap_or_else(|e| panic!("height {height}: {e}"));
+        verify_difficulty_only_with_context(&header, height, &mut fast)
+            .unwrap_or_else(|e| panic!("height {height}: {e}"));
+        assert_eq!(fast.tip_height, full.tip_height);
+    }
+    assert_eq!(fast.total_work(), full.total_work());
+}
+
+#[test]
+fn difficulty_only_accepts_a_broken_solution() {
+    let height = 3_000_100;
+    let mut raw = header_bytes(height);
+    let time = BlockHeader::read(&raw[..]).unwrap().time;
+
+    // A steady regtest chain at the PoW limit, spaced at the 75 s target.
+    let window = DifficultyContext::REQUIRED_WINDOW as u32;
+    let mut ctx = DifficultyContext::with_network(height - window - 1, Network::Regtest);
+    let limit_bits = Network::Regtest.pow_limit().to_nbits();
+    for back in (1..=window).rev() {
+        ctx.push_header(height - back, time - back * 75, limit_bits);
+    }
+    let bits = expected_nbits(&ctx, height).unwrap();
+
+    // The header hash covers the solution, so a broken one almost never meets a mainnet target.
+    // Regtest's is met by about one hash in sixteen: retarget a real header to it, break its
+    // solution, and grind the nonce until the filter passes again.
+    let last = raw.len() - 1;
+    raw[last] ^= 0x01;
+    raw[NBITS_OFFSET..NBITS_OFFSET + 4].copy_from_slice(&bits.to_le_bytes());
+    let broken = (0u32..)
+        .find_map(|nonce| {
+            raw[NONCE_OFFSET..NONCE_OFFSET + 4].copy_from_slice(&nonce.to_le_bytes());
+            let header = BlockHeader::read(&raw[..]).unwrap();
+            verify_difficulty_filter_for_network(&header.hash().0, bits, Network::Regtest)
+                .is_ok()
+                .then_some(header)
+        })
+        .unwrap();
+
+    match verify_pow_with_context(&broken, height, &mut ctx.clone()) {
+        Err(PowError::Equihash(_)) => {}
+        other => panic!("expected Equihash error, got {other:?}"),
+    }
+    verify_difficulty_only_with_context(&broken, height, &mut ctx).unwrap();
+    assert_eq!(ctx.tip_height, height);
+}
+
+#[test]
+fn check_leaves_the_context_untouched() {
+    let first = 3_000_000;
+    let next = first + DifficultyContext::REQUIRED_WINDOW as u32;
+    let mut ctx = DifficultyContext::new(first - 1);
+    for height in first..next {
+        let header = BlockHeader::read(&header_bytes(height)[..]).unwrap();
+        ctx.push_header(height, header.time, header.bits);
+    }
+    let before = ctx.clone();
+
+    let raw = header_bytes(next);
+    let header = BlockHeader::read(&raw[..]).unwrap();
+    let mut tampered = raw.clone();
+    tampered[SOLUTION_OFFSET] ^= 0x01;
+    let tampered = BlockHeader::read(&tampered[..]).unwrap();
+
+    // Passing, failing, and repeated checks are all side-effect free.
+    check_pow_with_context(&header, next, &ctx).unwrap();
+    check_pow_with_context(&header, next, &ctx).unwrap();
+    assert!(check_pow_with_context(&tampered, next, &ctx).is_err());
+    assert_eq!(ctx, before);
+
+    // Committing is then exactly one push.
+    verify_pow_with_context(&header, next, &mut ctx).unwrap();
+    let mut pushed = before;
+    pushed.push_header(next, header.time, header.bits);
+    assert_eq!(ctx, pushed);
+}
+
+#[test]
+fn linked_verification_checks_the_parent_first() {
+    let first = 3_000_000;
+    let next = first + DifficultyContext::REQUIRED_WINDOW as u32;
+    let mut ctx = DifficultyContext::new(first - 1);
+    let mut parent = [0u8; 32];
+    for height in first..next {
+        let header = BlockHeader::read(&header_bytes(height)[..]).unwrap();
+        ctx.push_header(height, header.time, header.bits);
+        parent = header.hash().0;
+    }
+    let header = BlockHeader::read(&header_bytes(next)[..]).unwrap();
+
+    // A wrong parent fails before any PoW check and leaves the context alone.
+    let before = ctx.clone();
+    let mut wrong = parent;
+    wrong[0] ^= 0x01;
+    match verify_pow_linked(&header, next, &mut ctx, Some(wrong)) {
+        Err(PowError::PrevMismatch { expected, found }) => {
+            assert_eq!(expected, wrong);
+            assert_eq!(found, parent);
+        }
+        other => panic!("expected PrevMismatch, got {other:?}"),
+    }
+    assert_eq!(ctx, before);
+
+    verify_pow_linked(&header, next, &mut ctx, Some(parent)).unwrap();
+    assert_eq!(ctx.tip_height, next);
+
+    // Without an expected parent only the PoW checks run.
+    let following = BlockHeader::read(&header_bytes(next + 1)[..]).unwrap();
+    verify_pow_linked(&following, next + 1, &mut ctx, None).unwrap();
+}
+
+#[test]
+fn hash_header_bytes_matches_parsed_hash() {
+    for height in HEIGHTS {
+        let raw = header_bytes(height);
+        let header = BlockHeader::read(&raw[..]).unwrap();
+        let hash = hash_header_bytes(&raw);
+        assert_eq!(hash, header.hash().0, "height {height}");
+        verify_difficulty_filter(&hash, header.bits).unwrap();
+    }
+    // Mainnet block 3000100 as shown by explorers (byte-reversed).
+    let mut shown = hash_header_bytes(&header_bytes(3_000_100));
+    shown.reverse();
+    assert_eq!(
+        hex::encode(shown),
+        "0000000001f25e46948e054e55b05ad052d0e36811567756eb5148043b358a8a"
+    );
+}
+
+#[test]
+fn cairo_input_must_pack_the_verified_powheader() {
+    let header = BlockHeader::read(&header_bytes(3_000_000)[..]).unwrap();
+    let powheader = powheader_bytes(&header);
+    let mut input = InputData::from_powheader(&powheader, &header.solution);
+    check_cairo_input(&powheader, &input).unwrap();
+
+    // One flipped bit in the nonce words, as a packing bug might leave it.
+    input.header_bytes[30] ^= 1;
+    assert!(matches!(
+        check_cairo_input(&powheader, &input),
+        Err(PowError::MalformedHeader(_))
+    ));
+
+    // A dropped trailing word is caught as well.
+    let mut short = InputData::from_powheader(&powheader, &header.solution);
+    short.header_bytes.pop();
+    assert!(matches!(
+        check_cairo_input(&powheader, &short),
+        Err(PowError::MalformedHeader(_))
+    ));
+}
+
+#[test]
+fn cairo_output_must_be_the_rust_header_hash() {
+    // Block 3000100 (hash 0000000001f25e46948e054e55b05ad052d0e36811567756eb5148043b358a8a)
+    // as the Cairo verifier outputs it: the `Uint256` it compares to the target, low limb first.
+    let header = BlockHeader::read(&header_bytes(3_000_100)[..]).unwrap();
+    let low = Felt252::from(0x52d0e36811567756eb5148043b358a8a_u128);
+    let high = Felt252::from(0x0000000001f25e46948e054e55b05ad0_u128);
+    check_cairo_output(&header, &[low, high]).unwrap();
+
+    // Swapped limbs, a missing limb, or another block's hash all fail.
+    for output in [vec![high, low], vec![low], Vec::new()] {
+        assert!(matches!(
+            check_cairo_output(&header, &output),
+            Err(PowError::Cairo(_))
+        ));
+    }
+    let other = BlockHeader::read(&header_bytes(3_000_101)[..]).unwrap();
+    assert!(matches!(
+        check_cairo_output(&other, &[low, high]),
+        Err(PowError::Cairo(_))
+    ));
+}
+
+#[test]
+#[ignore = "needs cairo/build/main.json (make build-cairo)"]
+fn cairo_program_outputs_the_header_hash() {
+    let program = cairo_program_path();
+    assert!(
+        program.exists(),
+        "{} not found (run `make build-cairo` and set CAIRO_PROGRAM_PATH)",
+        program.display()
+    );
+    let header = BlockHeader::read(&header_bytes(3_000_100)[..]).unwrap();
+    let powheader = powheader_bytes(&header);
+    let input = InputData::from_powheader(&powheader, &header.solution);
+
+    // The same limbs as in `cairo_output_must_be_the_rust_header_hash`, from a real run.
+    let output = execute(&program.to_string_lossy(), input).unwrap();
+    assert_eq!(
+        output,
+        [
+            Felt252::from(0x52d0e36811567756eb5148043b358a8a_u128),
+            Felt252::from(0x0000000001f25e46948e054e55b05ad0_u128),
+        ]
+    );
+    execute_pow_in_cairo(&header).unwrap();
+}
+
+#[test]
+fn rewind_and_reset_recover_from_a_shallow_reorg() {
+    let first = 3_000_000;
+    let tip = 3_000_100;
+    let headers: Vec<BlockHeader> = (first..=tip)
+        .map(|height| BlockHeader::read(&header_bytes(height)[..]).unwrap())
+        .collect();
+    let mut ctx = DifficultyContext::new(first - 1);
+    for (height, header) in (first..).zip(&headers) {
+        ctx.push_header(height, header.time, header.bits);
+    }
+    let before = ctx.clone();
+
+    // Back out three blocks: the window below them is still held, so the same blocks verify
+    // again and bring the context back to where it was.
+    ctx.rewind_to(tip - 3).unwrap();
+    assert!(ctx.is_ready());
+    for height in tip - 2..=tip {
+        let header = &headers[(height - first) as usize];
+        verify_pow_with_context(header, height, &mut ctx)
+            .unwrap_or_else(|e| panic!("height {height}: {e}"));
+    }
+    assert_eq!(ctx, before);
+
+    // Rebuilding from just the last window agrees on what comes next.
+    let window = &headers[headers.len() - DifficultyContext::REQUIRED_WINDOW..];
+    let mut rebuilt = DifficultyContext::new(0);
+    rebuilt.reset_to(tip, window).unwrap();
+    assert_eq!(rebuilt.tip_height, tip);
+    let next = BlockHeader::read(&header_bytes(tip + 1)[..]).unwrap();
+    assert_eq!(expected_nbits(&rebuilt, tip + 1).unwrap(), next.bits);
+
+    // Dropping a header from the slice breaks the `prev_block` chain after it.
+    let mut gapped = window.to_vec();
+    gapped.remove(5);
+    let unchanged = rebuilt.clone();
+    assert!(matches!(
+        rebuilt.reset_to(tip, &gapped),
+        Err(DiffError::Discontinuous { height }) if height == tip - 21
+    ));
+    assert_eq!(rebuilt, unchanged);
+}
@@ -0,0 +1,195 @@
+mod common;
+
+use common::header_bytes;
+use zcash_crypto::difficulty::context::{expected_nbits, median_time_past};
+use zcash_crypto::{
+    DiffError, DifficultyContext, Network, PowError, VerifyFlags,
+    verify_difficulty_filter_for_network, verify_pow_flags,
+};
+use zcash_primitives::block::BlockHeader;
+
+const HEIGHT: u32 = 3_000_100;
+const TIME_OFFSET: usize = 100;
+const NBITS_OFFSET: usize = 104;
+const NONCE_OFFSET: usize = 108;
+
+const EQUIHASH: VerifyFlags = VerifyFlags::EQUIHASH;
+const FILTER: VerifyFlags = VerifyFlags::FILTER;
+const CONTEXT: VerifyFlags = VerifyFlags::CONTEXT;
+const TIMESTAMP: VerifyFlags = VerifyFlags::TIMESTAMP;
+
+fn header(height: u32) -> BlockHeader {
+    BlockHeader::read(&header_bytes(height)[..]).unwrap()
+}
+
+/// The real mainnet window before [`HEIGHT`].
+fn mainnet_ctx() -> DifficultyContext {
+    let first = HEIGHT - DifficultyContext::REQUIRED_WINDOW as u32;
+    let mut ctx = DifficultyContext::new(first - 1);
+    for height in first..HEIGHT {
+        let header = header(height);
+        ctx.push_header(height, header.time, header.bits);
+    }
+    ctx
+}
+
+/// A steady regtest chain at the PoW limit before [`HEIGHT`].
+fn regtest_ctx() -> DifficultyContext {
+    let window = DifficultyContext::REQUIRED_WINDOW as u32;
+    let time = header(HEIGHT).time;
+    let mut ctx = DifficultyContext::with_network(HEIGHT - window - 1, Network::Regtest);
+    let limit_bits = Network::Regtest.pow_limit().to_nbits();
+    for back in (1..=window).rev() {
+        ctx.push_header(HEIGHT - back, time - back * 75, limit_bits);
+    }
+    ctx
+}
+
+/// [`HEIGHT`]'s header retargeted to follow [`regtest_ctx`] at `time`, with a broken solution
+/// and a nonce ground until it meets regtest's (easy) target.
+fn broken_regtest_header(time: u32) -> BlockHeader {
+    let bits = expected_nbits(&regtest_ctx(), HEIGHT).unwrap();
+    let mut raw = header_bytes(HEIGHT);
+    let last = raw.len() - 1;
+    raw[last] ^= 0x01;
+    raw[TIME_OFFSET..TIME_OFFSET + 4].copy_from_slice(&time.to_le_bytes());
+    raw[NBITS_OFFSET..NBITS_OFFSET + 4].copy_from_slice(&bits.to_le_bytes());
+    (0u32..)
+        .find_map(|nonce| {
+            raw[NONCE_OFFSET..NONCE_OFFSET + 4].copy_from_slice(&nonce.to_le_bytes());
+            let header = BlockHeader::read(&raw[..]).unwrap();
+            verify_difficulty_filter_for_network(&header.hash().0, bits, Network::Regtest)
+                .is_ok()
+                .then_some(header)
+        })
+        .unwrap()
+}
+
+fn all_combinations() -> impl Iterator<Item = VerifyFlags> {
+    (0u8..16).map(|bits| {
+        let mut flags = VerifyFlags::empty();
+        for (bit, flag) in [EQUIHASH, FILTER, CONTEXT, TIMESTAMP]
+            .into_iter()
+            .enumerate()
+        {
+            if bits & (1 << bit) != 0 {
+                flags |= flag;
+            }
+        }
+        flags
+    })
+}
+
+/// The check among `flags` that produced `err`.
+fn failed_check(err: &PowError, flags: VerifyFlags) -> VerifyFlags {
+    match err {
+        PowError::Equihash(_) => EQUIHASH,
+        PowError::Difficulty(_) => FILTER,
+        PowError::ContextDifficulty(DiffError::TimeTooOld { .. }) => TIMESTAMP,
+        // Both context checks report a missing context; `CONTEXT` runs first.
+        PowError::ContextDifficulty(DiffError::InsufficientContext) if !flags.contains(CONTEXT) => {
+            TIMESTAMP
+        }
+        PowError::ContextDifficulty(_) => CONTEXT,
+        PowError::Decode(_)
+        | PowError::MalformedHeader(_)
+        | PowError::Cairo(_)
+        | PowError::PrevMismatch { .. } => panic!("unexpected error: {err}"),
+    }
+}
+
+/// Runs every flag combination and asserts it fails exactly when it selects one of `failing`,
+/// reporting the first failing check in Equihash, filter, context, timestamp order. Passing runs with
+/// `CONTEXT` append the header; nothing else touches the context.
+fn assert_fails_on(header: &BlockHeader, ctx: Option<&DifficultyContext>, failing: &[VerifyFlags]) {
+    for flags in all_combinations() {
+        let mut ctx = ctx.cloned();
+        let before = ctx.clone();
+        let expected = failing.iter().copied().find(|&check| flags.contains(check));
+        match (
+            verify_pow_flags(header, HEIGHT, ctx.as_mut(), flags),
+            expected,
+        ) {
+            (Ok(()), None) => {
+                let mut pushed = before;
+                if flags.contains(CONTEXT) {
+                    pushed
+                        .as_mut()
+                        .unwrap()
+                        .push_header(HEIGHT, header.time, header.bits);
+                }
+                assert_eq!(ctx, pushed, "{flags:?}");
+            }
+            (Err(e), Some(check)) => {
+                assert_eq!(failed_check(&e, flags), check, "{flags:?}: {e}");
+                assert_eq!(ctx, before, "{flags:?}");
+            }
+            (got, expected) => panic!("{flags:?}: got {got:?}, expected failure in {expected:?}"),
+        }
+    }
+}
+
+#[test]
+fn valid_header_passes_every_combination() {
+    assert_fails_on(&header(HEIGHT), Some(&mainnet_ctx()), &[]);
+}
+
+#[test]
+fn context_without_a_full_window_errors() {
+    let next = header(HEIGHT);
+    assert_fails_on(&next, None, &[CONTEXT, TIMESTAMP]);
+
+    // Ten headers: short of the median-time-past span and of the averaging window.
+    let mut partial = DifficultyContext::new(HEIGHT - 11);
+    for height in HEIGHT - 10..HEIGHT {
+        let header = header(height);
+        partial.push_header(height, header.time, header.bits);
+    }
+    assert!(!partial.is_ready());
+    assert_fails_on(&next, Some(&partial), &[CONTEXT, TIMESTAMP]);
+    assert!(matches!(
+        verify_pow_flags(&next, HEIGHT, Some(&mut partial), CONTEXT),
+        Err(PowError::ContextDifficulty(DiffError::InsufficientContext))
+    ));
+}
+
+#[test]
+fn mainnet_header_fails_only_the_context_of_another_chain() {
+    // Regtest's limit is far above the mainnet target, so only the expected nBits differ.
+    assert_fails_on(&header(HEIGHT), Some(&regtest_ctx()), &[CONTEXT]);
+}
+
+#[test]
+fn broken_solution_fails_only_equihash() {
+    let time = header(HEIGHT).time;
+    assert_fails_on(
+        &broken_regtest_header(time),
+        Some(&regtest_ctx()),
+        &[EQUIHASH],
+    );
+}
+
+#[test]
+fn stale_timestamp_fails_only_with_timestamp_selected() {
+    let ctx = regtest_ctx();
+    let mtp = median_time_past(&ctx).unwrap();
+    for time in [mtp - 1, mtp] {
+        assert_fails_on(
+            &broken_regtest_header(time),
+            Some(&ctx),
+            &[EQUIHASH, TIMESTAMP],
+        );
+    }
+    assert_fails_on(&broken_regtest_header(mtp + 1), Some(&ctx), &[EQUIHASH]);
+}
+
+#[test]
+fn every_check_can_fail_on_its_own_first() {
+    // Against mainnet, the regtest header's target exceeds the PoW limit and its nBits are
+    // not the expected ones either.
+    assert_fails_on(
+        &broken_regtest_header(header(HEIGHT).time),
+        Some(&mainnet_ctx()),
+        &[EQUIHASH, FILTER, CONTEXT],
+    );
+}
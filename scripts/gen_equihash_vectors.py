@@ -0,0 +1,128 @@
+#!/usr/bin/env python3
+"""Regenerate the Equihash vectors in crates/zcash_crypto/tests/equihash_vectors.rs.
+
+Self-contained reference solver (Wagner) and verifier; prints the VALID/INVALID consts to stdout.
+"""
+import hashlib, json, os, struct, sys
+
+def params_ok(n, k):
+    return n % 8 == 0 and 3 <= k < n and n % (k + 1) == 0
+
+def base_hasher(n, k, powheader):
+    per = 512 // n
+    h = hashlib.blake2b(digest_size=per * n // 8, person=b"ZcashPoW" + struct.pack("<II", n, k))
+    h.update(powheader)
+    return h
+
+def leaf(n, k, base, i, cache):
+    per = 512 // n
+    c = n // (k + 1)
+    key = i // per
+    if key not in cache:
+        h = base.copy(); h.update(struct.pack("<I", i // per)); cache[key] = h.digest()
+    d = cache[key]
+    s = d[(i % per) * n // 8:(i % per) * n // 8 + n // 8]
+    v = int.from_bytes(s, "big")
+    return tuple((v >> (c * (k - j))) & ((1 << c) - 1) for j in range(k + 1))
+
+def verify(n, k, powheader, indices):
+    """Returns None if valid, else the expected Kind name (recursive order like tree_validator)."""
+    if len(indices) != 1 << k:
+        return "InvalidParams"
+    if len(set(indices)) != len(indices):
+        return "DuplicateIdxs"
+    base = base_hasher(n, k, powheader)
+    cache = {}
+    def rec(ix):
+        if len(ix) == 1:
+            return list(leaf(n, k, base, ix[0], cache))
+        a = rec(ix[:len(ix)//2]); 
+        if isinstance(a, str): return a
+        b = rec(ix[len(ix)//2:])
+        if isinstance(b, str): return b
+        if a[0] != b[0]: return "Collision"
+        if ix[len(ix)//2] < ix[0]: return "OutOfOrder"
+        return [x ^ y for x, y in zip(a[1:], b[1:])]
+    r = rec(indices)
+    if isinstance(r, str): return r
+    return None if r[0] == 0 else "NonZeroRootHash"
+
+def indices_from_minimal(n, k, minimal):
+    c = n // (k + 1) + 1
+    v = int.from_bytes(minimal, "big"); total = len(minimal) * 8
+    cnt = (1 << k)
+    assert total == cnt * c
+    return [(v >> (c * (cnt - 1 - j))) & ((1 << c) - 1) for j in range(cnt)]
+
+def solve(n, k, powheader, want_near=True):
+    c = n // (k + 1)
+    base = base_hasher(n, k, powheader)
+    cache = {}
+    rows = [(leaf(n, k, base, i, cache), (i,)) for i in range(1 << (c + 1))]
+    near = []
+    for level in range(k):
+        buckets = {}
+        for r in rows:
+            buckets.setdefault(r[0][0], []).append(r)
+        nxt = []
+        for grp in buckets.values():
+            for x in range(len(grp)):
+                for y in range(x + 1, len(grp)):
+                    a, b = grp[x], grp[y]
+                    if set(a[1]) & set(b[1]):
+                        continue
+                    if a[1][0] > b[1][0]: a, b = b, a
+                    h = tuple(p ^ q for p, q in zip(a[0][1:], b[0][1:]))
+                    if level < k - 1 and all(v == 0 for v in h) :
+                        continue
+                    nxt.append((h, a[1] + b[1]))
+        rows = nxt
+    sols = sorted({r[1] for r in rows if r[0][0] == 0})
+    near = sorted({r[1] for r in rows if r[0][0] != 0})
+    return sols, near
+
+# Self-check the reference verifier against real mainnet (200, 9) solutions first.
+HEADERS = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..", "data", "headers.jsonl")
+for line in open(HEADERS):
+    raw = bytes.fromhex(json.loads(line)["header_hex"])
+    ix = indices_from_minimal(200, 9, raw[143:])
+    assert verify(200, 9, raw[:140], ix) is None
+    bad = list(ix); bad[0], bad[1] = bad[1], bad[0]
+    assert verify(200, 9, raw[:140], bad) == "OutOfOrder"
+
+INPUT = b"Equihash is an asymmetric PoW based on the Generalised Birthday problem."
+def nonce(i): return i.to_bytes(32, "little")
+sets = [(48, 5, 1), (64, 3, 0), (96, 5, 0)]
+valid, invalid = [], []
+for n, k, ni in sets:
+    ph = INPUT + nonce(ni)
+    sols, near = solve(n, k, ph)
+    for s in sols: assert verify(n, k, ph, list(s)) is None
+    valid.append((n, k, ni, sols))
+    s = list(sols[0])
+    cands = {}
+    nz = next(x for x in near if len(set(x)) == len(x) and verify(n, k, ph, list(x)) == "NonZeroRootHash")
+    cands["NonZeroRootHash"] = list(nz)
+    oo = s[:]; oo[0], oo[1] = oo[1], oo[0]; cands["OutOfOrder"] = oo
+    dup = s[:]; dup[1] = dup[0]; cands["DuplicateIdxs"] = dup
+    for r in range(1 << (n // (k + 1) + 1)):
+        col = s[:]; col[0] = r
+        if r not in s and verify(n, k, ph, col) == "Collision": break
+    cands["Collision"] = col
+    for kind, ix in cands.items():
+        assert verify(n, k, ph, ix) == kind, (n, k, kind, verify(n, k, ph, ix))
+        invalid.append((n, k, ni, ix, kind))
+
+def arr(ix): return "&[" + ", ".join(map(str, ix)) + "]"
+out = []
+out.append("const VALID: &[ValidVector] = &[")
+for n, k, ni, sols in valid:
+    out.append(f"    ValidVector {{\n        params: ({n}, {k}),\n        nonce: {ni},\n        solutions: &[")
+    for s in sols: out.append(f"            {arr(s)},")
+    out.append("        ],\n    },")
+out.append("];\n")
+out.append("const INVALID: &[InvalidVector] = &[")
+for n, k, ni, ix, kind in invalid:
+    out.append(f"    InvalidVector {{\n        params: ({n}, {k}),\n        nonce: {ni},\n        solution: {arr(ix)},\n        error: Kind::{kind},\n    }},")
+out.append("];")
+sys.stdout.write("\n".join(out) + "\n")